@@ -0,0 +1,200 @@
+use super::Compression;
+use crate::error::Result;
+use async_compression::{
+    futures::{
+        bufread::{GzipDecoder, ZlibDecoder},
+        write::{GzipEncoder, ZlibEncoder},
+    },
+    Level,
+};
+use futures::io::{AsyncRead, AsyncWrite, BufReader};
+use pin_project::pin_project;
+use std::{
+    io::{self, IoSlice},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A reader that decompresses the data from an inner reader asynchronously.
+#[pin_project(project = DecompressAsyncReaderProj)]
+#[derive(Debug)]
+pub enum DecompressAsyncReader<R>
+where
+    R: AsyncRead,
+{
+    None(#[pin] R),
+    Gzip(#[pin] GzipDecoder<BufReader<R>>),
+    Zlib(#[pin] ZlibDecoder<BufReader<R>>),
+}
+
+impl<R> DecompressAsyncReader<R>
+where
+    R: AsyncRead,
+{
+    /// Wrap a reader with the decoder for the compression type.
+    pub fn new(reader: R, compression: Compression) -> Self {
+        match compression {
+            Compression::None => Self::None(reader),
+            Compression::Gzip => {
+                let mut decoder = GzipDecoder::new(BufReader::new(reader));
+                decoder.multiple_members(true);
+                Self::Gzip(decoder)
+            }
+            Compression::Zlib => Self::Zlib(ZlibDecoder::new(BufReader::new(reader))),
+        }
+    }
+
+    /// Get the reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.get_ref().get_ref(),
+            Self::Zlib(reader) => reader.get_ref().get_ref(),
+        }
+    }
+
+    /// Get the mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.get_mut().get_mut(),
+            Self::Zlib(reader) => reader.get_mut().get_mut(),
+        }
+    }
+
+    /// Unwrap the inner reader.
+    pub fn into_inner(self) -> R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.into_inner().into_inner(),
+            Self::Zlib(reader) => reader.into_inner().into_inner(),
+        }
+    }
+}
+
+impl<R> AsyncRead for DecompressAsyncReader<R>
+where
+    R: AsyncRead,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.project() {
+            DecompressAsyncReaderProj::None(reader) => reader.poll_read(cx, buf),
+            DecompressAsyncReaderProj::Gzip(reader) => reader.poll_read(cx, buf),
+            DecompressAsyncReaderProj::Zlib(reader) => reader.poll_read(cx, buf),
+        }
+    }
+}
+
+/// A writer that compresses the data before writing to an inner writer asynchronously.
+///
+/// The compressed stream is finalized only when the writer is closed.
+#[pin_project(project = CompressAsyncWriterProj)]
+#[derive(Debug)]
+pub enum CompressAsyncWriter<W>
+where
+    W: AsyncWrite,
+{
+    None(#[pin] W),
+    Gzip(#[pin] GzipEncoder<W>),
+    Zlib(#[pin] ZlibEncoder<W>),
+}
+
+impl<W> CompressAsyncWriter<W>
+where
+    W: AsyncWrite,
+{
+    /// Wrap a writer with the encoder for the compression type.
+    ///
+    /// The default level of the codec is used if `level` is `None`.
+    pub fn new(writer: W, compression: Compression, level: Option<u32>) -> Result<Self> {
+        super::check_level(level)?;
+        let level = match level {
+            Some(level) => Level::Precise(level as i32),
+            None => Level::Default,
+        };
+
+        Ok(match compression {
+            Compression::None => Self::None(writer),
+            Compression::Gzip => Self::Gzip(GzipEncoder::with_quality(writer, level)),
+            Compression::Zlib => Self::Zlib(ZlibEncoder::with_quality(writer, level)),
+        })
+    }
+
+    /// Get the reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.get_ref(),
+            Self::Zlib(writer) => writer.get_ref(),
+        }
+    }
+
+    /// Get the mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.get_mut(),
+            Self::Zlib(writer) => writer.get_mut(),
+        }
+    }
+
+    /// Unwrap the inner writer.
+    ///
+    /// The compressed stream is not finalized unless the writer was closed beforehand.
+    pub fn into_inner(self) -> W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.into_inner(),
+            Self::Zlib(writer) => writer.into_inner(),
+        }
+    }
+}
+
+impl<W> AsyncWrite for CompressAsyncWriter<W>
+where
+    W: AsyncWrite,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.project() {
+            CompressAsyncWriterProj::None(writer) => writer.poll_write(cx, buf),
+            CompressAsyncWriterProj::Gzip(writer) => writer.poll_write(cx, buf),
+            CompressAsyncWriterProj::Zlib(writer) => writer.poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.project() {
+            CompressAsyncWriterProj::None(writer) => writer.poll_write_vectored(cx, bufs),
+            CompressAsyncWriterProj::Gzip(writer) => writer.poll_write_vectored(cx, bufs),
+            CompressAsyncWriterProj::Zlib(writer) => writer.poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.project() {
+            CompressAsyncWriterProj::None(writer) => writer.poll_flush(cx),
+            CompressAsyncWriterProj::Gzip(writer) => writer.poll_flush(cx),
+            CompressAsyncWriterProj::Zlib(writer) => writer.poll_flush(cx),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.project() {
+            CompressAsyncWriterProj::None(writer) => writer.poll_close(cx),
+            CompressAsyncWriterProj::Gzip(writer) => writer.poll_close(cx),
+            CompressAsyncWriterProj::Zlib(writer) => writer.poll_close(cx),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Compression codecs for TFRecord files.
+//!
+//! TensorFlow compresses the whole record stream rather than individual records,
+//! which corresponds to `TFRecordOptions(compression_type="GZIP")` or `"ZLIB"`.
+//! The readers and writers wrap the underlying I/O with the types in this module
+//! according to the [Compression] option in their configurations.
+//!
+//! Compatibility with TensorFlow is defined on the decoded stream. Files written by
+//! TensorFlow are read back as is, and files written here are readable by TensorFlow,
+//! but the compressed bytes may differ from TensorFlow's since the deflate encoders
+//! differ. The GZIP header of [CompressWriter] matches the one zlib writes on Unix,
+//! while the asynchronous writers use the header of their codec.
+
+#[cfg(feature = "futures-io")]
+mod r#async;
+#[cfg(feature = "futures-io")]
+pub use r#async::*;
+
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use self::tokio::*;
+
+mod sync;
+pub use sync::*;
+
+use crate::error::{ensure_argument, Result};
+
+/// The operating system byte zlib writes to GZIP headers on Unix.
+const GZIP_OS_UNIX: u8 = 3;
+
+/// The maximum compression level accepted by GZIP and ZLIB codecs.
+pub const MAX_COMPRESSION_LEVEL: u32 = 9;
+
+/// Compression type of a TFRecord file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Compression {
+    /// Uncompressed TFRecord file.
+    #[default]
+    None,
+    /// GZIP compressed TFRecord file.
+    Gzip,
+    /// ZLIB compressed TFRecord file.
+    Zlib,
+}
+
+fn check_level(level: Option<u32>) -> Result<()> {
+    if let Some(level) = level {
+        ensure_argument!(
+            level <= MAX_COMPRESSION_LEVEL,
+            "compression level must be in range 0..={}, but get {}",
+            MAX_COMPRESSION_LEVEL,
+            level
+        );
+    }
+    Ok(())
+}
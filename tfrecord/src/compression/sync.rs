@@ -0,0 +1,166 @@
+use super::Compression;
+use crate::error::Result;
+use flate2::{
+    read::{MultiGzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+    GzBuilder,
+};
+use std::io::{self, prelude::*, IoSlice};
+
+/// A reader that decompresses the data from an inner reader.
+#[derive(Debug)]
+pub enum DecompressReader<R>
+where
+    R: Read,
+{
+    None(R),
+    Gzip(MultiGzDecoder<R>),
+    Zlib(ZlibDecoder<R>),
+}
+
+impl<R> DecompressReader<R>
+where
+    R: Read,
+{
+    /// Wrap a reader with the decoder for the compression type.
+    pub fn new(reader: R, compression: Compression) -> Self {
+        match compression {
+            Compression::None => Self::None(reader),
+            Compression::Gzip => Self::Gzip(MultiGzDecoder::new(reader)),
+            Compression::Zlib => Self::Zlib(ZlibDecoder::new(reader)),
+        }
+    }
+
+    /// Get the reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.get_ref(),
+            Self::Zlib(reader) => reader.get_ref(),
+        }
+    }
+
+    /// Get the mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.get_mut(),
+            Self::Zlib(reader) => reader.get_mut(),
+        }
+    }
+
+    /// Unwrap the inner reader.
+    pub fn into_inner(self) -> R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.into_inner(),
+            Self::Zlib(reader) => reader.into_inner(),
+        }
+    }
+}
+
+impl<R> Read for DecompressReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::None(reader) => reader.read(buf),
+            Self::Gzip(reader) => reader.read(buf),
+            Self::Zlib(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// A writer that compresses the data before writing to an inner writer.
+///
+/// The compressed stream is finalized when [finish](CompressWriter::finish) is called
+/// or the writer is dropped.
+#[derive(Debug)]
+pub enum CompressWriter<W>
+where
+    W: Write,
+{
+    None(W),
+    Gzip(GzEncoder<W>),
+    Zlib(ZlibEncoder<W>),
+}
+
+impl<W> CompressWriter<W>
+where
+    W: Write,
+{
+    /// Wrap a writer with the encoder for the compression type.
+    ///
+    /// The default level of the codec is used if `level` is `None`.
+    pub fn new(writer: W, compression: Compression, level: Option<u32>) -> Result<Self> {
+        super::check_level(level)?;
+        let level = level.map(flate2::Compression::new).unwrap_or_default();
+
+        Ok(match compression {
+            Compression::None => Self::None(writer),
+            Compression::Gzip => Self::Gzip(
+                GzBuilder::new()
+                    .operating_system(super::GZIP_OS_UNIX)
+                    .write(writer, level),
+            ),
+            Compression::Zlib => Self::Zlib(ZlibEncoder::new(writer, level)),
+        })
+    }
+
+    /// Get the reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.get_ref(),
+            Self::Zlib(writer) => writer.get_ref(),
+        }
+    }
+
+    /// Get the mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.get_mut(),
+            Self::Zlib(writer) => writer.get_mut(),
+        }
+    }
+
+    /// Finalize the compressed stream and return the inner writer.
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::None(writer) => Ok(writer),
+            Self::Gzip(writer) => writer.finish(),
+            Self::Zlib(writer) => writer.finish(),
+        }
+    }
+}
+
+impl<W> Write for CompressWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::None(writer) => writer.write(buf),
+            Self::Gzip(writer) => writer.write(buf),
+            Self::Zlib(writer) => writer.write(buf),
+        }
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        match self {
+            Self::None(writer) => writer.write_vectored(bufs),
+            Self::Gzip(writer) => writer.write_vectored(bufs),
+            Self::Zlib(writer) => writer.write_vectored(bufs),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::None(writer) => writer.flush(),
+            Self::Gzip(writer) => writer.flush(),
+            Self::Zlib(writer) => writer.flush(),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use super::Compression;
+use crate::error::Result;
+use ::tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
+use async_compression::{
+    tokio::{
+        bufread::{GzipDecoder, ZlibDecoder},
+        write::{GzipEncoder, ZlibEncoder},
+    },
+    Level,
+};
+use pin_project::pin_project;
+use std::{
+    io::{self, IoSlice},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A reader that decompresses the data from an inner tokio reader.
+#[pin_project(project = DecompressTokioReaderProj)]
+#[derive(Debug)]
+pub enum DecompressTokioReader<R>
+where
+    R: AsyncRead,
+{
+    None(#[pin] R),
+    Gzip(#[pin] GzipDecoder<BufReader<R>>),
+    Zlib(#[pin] ZlibDecoder<BufReader<R>>),
+}
+
+impl<R> DecompressTokioReader<R>
+where
+    R: AsyncRead,
+{
+    /// Wrap a reader with the decoder for the compression type.
+    pub fn new(reader: R, compression: Compression) -> Self {
+        match compression {
+            Compression::None => Self::None(reader),
+            Compression::Gzip => {
+                let mut decoder = GzipDecoder::new(BufReader::new(reader));
+                decoder.multiple_members(true);
+                Self::Gzip(decoder)
+            }
+            Compression::Zlib => Self::Zlib(ZlibDecoder::new(BufReader::new(reader))),
+        }
+    }
+
+    /// Get the reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.get_ref().get_ref(),
+            Self::Zlib(reader) => reader.get_ref().get_ref(),
+        }
+    }
+
+    /// Get the mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.get_mut().get_mut(),
+            Self::Zlib(reader) => reader.get_mut().get_mut(),
+        }
+    }
+
+    /// Unwrap the inner reader.
+    pub fn into_inner(self) -> R {
+        match self {
+            Self::None(reader) => reader,
+            Self::Gzip(reader) => reader.into_inner().into_inner(),
+            Self::Zlib(reader) => reader.into_inner().into_inner(),
+        }
+    }
+}
+
+impl<R> AsyncRead for DecompressTokioReader<R>
+where
+    R: AsyncRead,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.project() {
+            DecompressTokioReaderProj::None(reader) => reader.poll_read(cx, buf),
+            DecompressTokioReaderProj::Gzip(reader) => reader.poll_read(cx, buf),
+            DecompressTokioReaderProj::Zlib(reader) => reader.poll_read(cx, buf),
+        }
+    }
+}
+
+/// A writer that compresses the data before writing to an inner tokio writer.
+///
+/// The compressed stream is finalized only when the writer is shut down.
+#[pin_project(project = CompressTokioWriterProj)]
+#[derive(Debug)]
+pub enum CompressTokioWriter<W>
+where
+    W: AsyncWrite,
+{
+    None(#[pin] W),
+    Gzip(#[pin] GzipEncoder<W>),
+    Zlib(#[pin] ZlibEncoder<W>),
+}
+
+impl<W> CompressTokioWriter<W>
+where
+    W: AsyncWrite,
+{
+    /// Wrap a writer with the encoder for the compression type.
+    ///
+    /// The default level of the codec is used if `level` is `None`.
+    pub fn new(writer: W, compression: Compression, level: Option<u32>) -> Result<Self> {
+        super::check_level(level)?;
+        let level = match level {
+            Some(level) => Level::Precise(level as i32),
+            None => Level::Default,
+        };
+
+        Ok(match compression {
+            Compression::None => Self::None(writer),
+            Compression::Gzip => Self::Gzip(GzipEncoder::with_quality(writer, level)),
+            Compression::Zlib => Self::Zlib(ZlibEncoder::with_quality(writer, level)),
+        })
+    }
+
+    /// Get the reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.get_ref(),
+            Self::Zlib(writer) => writer.get_ref(),
+        }
+    }
+
+    /// Get the mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.get_mut(),
+            Self::Zlib(writer) => writer.get_mut(),
+        }
+    }
+
+    /// Unwrap the inner writer.
+    ///
+    /// The compressed stream is not finalized unless the writer was shut down beforehand.
+    pub fn into_inner(self) -> W {
+        match self {
+            Self::None(writer) => writer,
+            Self::Gzip(writer) => writer.into_inner(),
+            Self::Zlib(writer) => writer.into_inner(),
+        }
+    }
+}
+
+impl<W> AsyncWrite for CompressTokioWriter<W>
+where
+    W: AsyncWrite,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.project() {
+            CompressTokioWriterProj::None(writer) => writer.poll_write(cx, buf),
+            CompressTokioWriterProj::Gzip(writer) => writer.poll_write(cx, buf),
+            CompressTokioWriterProj::Zlib(writer) => writer.poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.project() {
+            CompressTokioWriterProj::None(writer) => writer.poll_write_vectored(cx, bufs),
+            CompressTokioWriterProj::Gzip(writer) => writer.poll_write_vectored(cx, bufs),
+            CompressTokioWriterProj::Zlib(writer) => writer.poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::None(writer) => writer.is_write_vectored(),
+            Self::Gzip(writer) => writer.is_write_vectored(),
+            Self::Zlib(writer) => writer.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.project() {
+            CompressTokioWriterProj::None(writer) => writer.poll_flush(cx),
+            CompressTokioWriterProj::Gzip(writer) => writer.poll_flush(cx),
+            CompressTokioWriterProj::Zlib(writer) => writer.poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.project() {
+            CompressTokioWriterProj::None(writer) => writer.poll_shutdown(cx),
+            CompressTokioWriterProj::Gzip(writer) => writer.poll_shutdown(cx),
+            CompressTokioWriterProj::Zlib(writer) => writer.poll_shutdown(cx),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::IndexedDataset;
+use crate::{error::Result, indexer::RecordIndex, record::Record};
+use std::{ops::Range, sync::Arc};
+
+/// Asynchronous random-access dataset of record `T` over record indexes.
+///
+/// It runs the positional reads of an [IndexedDataset] on a blocking thread pool,
+/// so it works with any async runtime. It is cheap to clone and shares the file
+/// handles among clones.
+#[derive(Debug)]
+pub struct IndexedAsyncDataset<T>
+where
+    T: Record,
+{
+    inner: Arc<IndexedDataset<T>>,
+}
+
+impl<T> Clone for IndexedAsyncDataset<T>
+where
+    T: Record,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> From<IndexedDataset<T>> for IndexedAsyncDataset<T>
+where
+    T: Record,
+{
+    fn from(dataset: IndexedDataset<T>) -> Self {
+        Self {
+            inner: Arc::new(dataset),
+        }
+    }
+}
+
+impl<T> IndexedAsyncDataset<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Get the number of records.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check if the dataset has no records.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Get the record indexes.
+    pub fn indexes(&self) -> &[RecordIndex] {
+        self.inner.indexes()
+    }
+
+    /// Load the record at `index` asynchronously.
+    pub async fn get(&self, index: usize) -> Result<T> {
+        let inner = self.inner.clone();
+        blocking::unblock(move || inner.get(index)).await
+    }
+
+    /// Load the records in a range of indexes asynchronously.
+    pub async fn get_range(&self, range: Range<usize>) -> Result<Vec<T>> {
+        let inner = self.inner.clone();
+        blocking::unblock(move || inner.get_range(range)).await
+    }
+
+    /// Load the records at given indexes in order asynchronously.
+    pub async fn get_many(&self, indexes: &[usize]) -> Result<Vec<T>> {
+        let inner = self.inner.clone();
+        let indexes = indexes.to_vec();
+        blocking::unblock(move || inner.get_many(&indexes)).await
+    }
+}
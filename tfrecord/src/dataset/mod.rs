@@ -0,0 +1,129 @@
+//! Random-access datasets over indexed records.
+//!
+//! The [IndexedDataset](sync::IndexedDataset) loads records by their [RecordIndex](crate::indexer::RecordIndex)
+//! with positional reads on shared file handles, so it can be shared across threads.
+//! On platforms without positional reads, the reads fall back to seeking under a lock.
+//! The [IndexedAsyncDataset](async::IndexedAsyncDataset) is the asynchronous counterpart, which
+//! works with any runtime and is enabled by either the `async` or `tokio` feature.
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod r#async;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+pub use r#async::*;
+
+mod sync;
+pub use sync::*;
+
+use crate::error::Result;
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Configuration for indexed datasets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexedDatasetConfig {
+    /// Verify the data checksum of loaded records.
+    pub check_integrity: bool,
+    /// The maximum number of file handles kept open.
+    pub max_open_files: usize,
+}
+
+impl Default for IndexedDatasetConfig {
+    fn default() -> Self {
+        Self {
+            check_integrity: true,
+            max_open_files: 64,
+        }
+    }
+}
+
+/// A bounded pool of open file handles, evicting the least recently used one.
+#[derive(Debug)]
+struct FilePool {
+    capacity: usize,
+    files: Mutex<VecDeque<(Arc<PathBuf>, Arc<File>)>>,
+}
+
+impl FilePool {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            files: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    fn get(&self, path: &Arc<PathBuf>) -> Result<Arc<File>> {
+        if let Some(file) = self.lookup(path) {
+            return Ok(file);
+        }
+
+        // open the file without holding the lock
+        let file = Arc::new(File::open(&**path)?);
+
+        let mut files = self.files.lock().unwrap();
+        if let Some(index) = position(&files, path) {
+            return Ok(files[index].1.clone());
+        }
+        files.push_front((path.clone(), file.clone()));
+        files.truncate(self.capacity);
+        Ok(file)
+    }
+
+    fn lookup(&self, path: &Arc<PathBuf>) -> Option<Arc<File>> {
+        let mut files = self.files.lock().unwrap();
+        let index = position(&files, path)?;
+        let entry = files.remove(index).unwrap();
+        let file = entry.1.clone();
+        files.push_front(entry);
+        Some(file)
+    }
+}
+
+fn position(files: &VecDeque<(Arc<PathBuf>, Arc<File>)>, path: &Arc<PathBuf>) -> Option<usize> {
+    files
+        .iter()
+        .position(|(other, _)| Arc::ptr_eq(other, path) || other == path)
+}
+
+/// Read the exact number of bytes at an offset without moving the file cursor.
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+/// Read the exact number of bytes at an offset without moving the file cursor.
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt as _;
+
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+/// Read the exact number of bytes at an offset by seeking the shared file cursor.
+///
+/// Positional reads are not available on this platform, so the seek and the read are
+/// serialized by a global lock.
+#[cfg(not(any(unix, windows)))]
+fn read_exact_at(mut file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    use std::io::{Read as _, Seek as _, SeekFrom};
+
+    static LOCK: Mutex<()> = Mutex::new(());
+    let _guard = LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buf)
+}
@@ -0,0 +1,190 @@
+use super::{FilePool, IndexedDatasetConfig};
+use crate::{
+    error::{ChecksumKind, Error, Result},
+    indexer::{RecordIndex, RecordIndexerConfig},
+    io::{FOOTER_SIZE, HEADER_SIZE},
+    record::Record,
+};
+use std::{marker::PhantomData, ops::Range};
+
+/// Random-access dataset of record `T` over record indexes.
+///
+/// Records are loaded with positional reads on a bounded pool of shared file handles,
+/// so the dataset is [Sync] and can be shared across threads.
+///
+/// ```rust,no_run
+/// use tfrecord::{Example, IndexedDataset};
+///
+/// let dataset: IndexedDataset<Example> =
+///     IndexedDataset::load_pattern("data/train@64", Default::default(), Default::default())?;
+/// let example = dataset.get(42)?;
+/// let batch = dataset.get_many(&[3, 1, 4, 1, 5])?;
+/// # Ok::<_, tfrecord::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct IndexedDataset<T>
+where
+    T: Record,
+{
+    indexes: Vec<RecordIndex>,
+    pool: FilePool,
+    check_integrity: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> IndexedDataset<T>
+where
+    T: Record,
+{
+    /// Build a dataset from record indexes.
+    pub fn from_indexes<I>(indexes: I, config: IndexedDatasetConfig) -> Self
+    where
+        I: IntoIterator<Item = RecordIndex>,
+    {
+        let IndexedDatasetConfig {
+            check_integrity,
+            max_open_files,
+        } = config;
+
+        Self {
+            indexes: indexes.into_iter().collect(),
+            pool: FilePool::new(max_open_files),
+            check_integrity,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Build a dataset from files specified by a glob pattern or shard spec.
+    ///
+    /// See [discovery](crate::discovery) for the accepted patterns.
+    pub fn load_pattern(
+        pattern: &str,
+        indexer_config: RecordIndexerConfig,
+        config: IndexedDatasetConfig,
+    ) -> Result<Self> {
+        let indexes: Vec<_> =
+            crate::indexer::load_pattern(pattern, indexer_config)?.collect::<Result<_>>()?;
+        Ok(Self::from_indexes(indexes, config))
+    }
+
+    /// Get the number of records.
+    pub fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    /// Check if the dataset has no records.
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    /// Get the record indexes.
+    pub fn indexes(&self) -> &[RecordIndex] {
+        &self.indexes
+    }
+
+    /// Load the record at `index`.
+    pub fn get(&self, index: usize) -> Result<T> {
+        let bytes = self.read(self.index(index)?)?;
+        T::decode(&bytes)
+    }
+
+    /// Load the records in a range of indexes.
+    ///
+    /// Consecutive records in a file are loaded in a single read.
+    pub fn get_range(&self, range: Range<usize>) -> Result<Vec<T>> {
+        let Range { start, end } = range;
+        let indexes = self.indexes.get(start..end).ok_or_else(|| {
+            Error::invalid_argument(format!(
+                "range {start}..{end} is out of range for dataset of length {}",
+                self.len()
+            ))
+        })?;
+        self.read_many(indexes.iter())
+    }
+
+    /// Load the records at given indexes in order.
+    ///
+    /// Records that are consecutive in a file are loaded in a single read.
+    pub fn get_many(&self, indexes: &[usize]) -> Result<Vec<T>> {
+        let indexes: Vec<_> = indexes
+            .iter()
+            .map(|&index| self.index(index))
+            .collect::<Result<_>>()?;
+        self.read_many(indexes.into_iter())
+    }
+
+    fn index(&self, index: usize) -> Result<&RecordIndex> {
+        self.indexes.get(index).ok_or_else(|| {
+            Error::invalid_argument(format!(
+                "index {index} is out of range for dataset of length {}",
+                self.len()
+            ))
+        })
+    }
+
+    fn read(&self, index: &RecordIndex) -> Result<Vec<u8>> {
+        let RecordIndex {
+            ref path,
+            offset,
+            len,
+        } = *index;
+        let file = self.pool.get(path)?;
+        let mut buf = vec![0u8; len + FOOTER_SIZE];
+        super::read_exact_at(&file, &mut buf, offset)?;
+        self.verify(&buf)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    fn read_many<'a, I>(&self, indexes: I) -> Result<Vec<T>>
+    where
+        I: Iterator<Item = &'a RecordIndex>,
+    {
+        let mut records = vec![];
+        let mut indexes = indexes.peekable();
+
+        while let Some(first) = indexes.next() {
+            // collect the run of records following each other in the same file
+            let mut run = vec![first];
+            while let Some(next) = indexes.peek() {
+                let last = run.last().unwrap();
+                let end = last.offset + (last.len + FOOTER_SIZE + HEADER_SIZE) as u64;
+                if next.path != last.path || next.offset != end {
+                    break;
+                }
+                run.push(indexes.next().unwrap());
+            }
+
+            if let [index] = run[..] {
+                records.push(T::decode(&self.read(index)?)?);
+                continue;
+            }
+
+            let last = run.last().unwrap();
+            let start = first.offset;
+            let end = last.offset + (last.len + FOOTER_SIZE) as u64;
+            let file = self.pool.get(&first.path)?;
+            let mut buf = vec![0u8; (end - start) as usize];
+            super::read_exact_at(&file, &mut buf, start)?;
+
+            for index in run {
+                let begin = (index.offset - start) as usize;
+                let data = &buf[begin..(begin + index.len + FOOTER_SIZE)];
+                self.verify(data)?;
+                records.push(T::decode(&data[..index.len])?);
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Verify the data checksum with the footer following the data.
+    fn verify(&self, data_and_footer: &[u8]) -> Result<()> {
+        if self.check_integrity {
+            let (data, footer) = data_and_footer.split_at(data_and_footer.len() - FOOTER_SIZE);
+            let expect = u32::from_le_bytes(footer.try_into().unwrap());
+            crate::utils::verify_checksum(data, expect, ChecksumKind::Data)?;
+        }
+        Ok(())
+    }
+}
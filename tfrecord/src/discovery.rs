@@ -0,0 +1,158 @@
+//! File discovery by glob patterns and TensorFlow shard specs.
+//!
+//! A pattern is resolved to a sorted list of files in one of the following ways.
+//!
+//! | Pattern                          | Resolved files                                              |
+//! |----------------------------------|-------------------------------------------------------------|
+//! | `data/train@64`                  | `data/train-00000-of-00064` to `data/train-00063-of-00064`  |
+//! | `data/train@64.tfrecord`         | Same as above with `.tfrecord` file name suffix             |
+//! | `data/train-?????-of-00064`      | Same as `data/train@64`                                     |
+//! | `data/train-*.tfrecord`          | Files matching the glob pattern                             |
+//! | `data/train.tfrecord`            | The file itself                                             |
+//!
+//! Shard specs expand to the full list of shards, and it is an error if any of them is missing.
+//! Glob matches following the `-NNNNN-of-NNNNN` naming are checked for missing shards as well.
+
+use crate::{
+    error::{Error, Result},
+    sharded_writer::shard_path,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The maximum number of missing shards listed in an error message.
+const MAX_LISTED_MISSING: usize = 3;
+
+/// Resolve a glob pattern or shard spec to a sorted list of files.
+pub fn find_files(pattern: &str) -> Result<Vec<PathBuf>> {
+    let path = Path::new(pattern);
+    let file_name = path.file_name().and_then(|name| name.to_str());
+
+    if let Some((base, num_shards, suffix)) = file_name.and_then(parse_shard_spec) {
+        let prefix = path.with_file_name(base);
+        let paths: Vec<_> = (0..num_shards)
+            .map(|index| shard_path(&prefix, index, num_shards, suffix))
+            .collect();
+        check_missing(paths.iter().filter(|path| !path.is_file()), num_shards)?;
+        return Ok(paths);
+    }
+
+    if !is_glob(pattern) {
+        return Ok(vec![path.to_owned()]);
+    }
+
+    let mut paths = vec![];
+    let entries = glob::glob(pattern)
+        .map_err(|error| Error::invalid_argument(format!("invalid glob pattern: {error}")))?;
+    for entry in entries {
+        let path = entry.map_err(io::Error::from)?;
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no files match the pattern '{pattern}'"),
+        )
+        .into());
+    }
+    paths.sort();
+    check_shard_sets(&paths)?;
+
+    Ok(paths)
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Parse a file name in the `base@N[suffix]` or `base-?????-of-N[suffix]` form.
+///
+/// It returns the base name, the number of shards and the suffix.
+fn parse_shard_spec(file_name: &str) -> Option<(&str, usize, &str)> {
+    let (base, rest) = if let Some((base, rest)) = file_name.rsplit_once('@') {
+        (base, rest)
+    } else {
+        file_name.rsplit_once("-?????-of-")?
+    };
+    let (num_shards, suffix) = split_leading_digits(rest)?;
+    (num_shards > 0).then_some((base, num_shards, suffix))
+}
+
+/// Parse a file name in the `base-NNNNN-of-NNNNN[suffix]` form.
+///
+/// It returns the base name, the shard index, the number of shards and the suffix.
+fn parse_shard_name(file_name: &str) -> Option<(&str, usize, usize, &str)> {
+    let (head, rest) = file_name.rsplit_once("-of-")?;
+    let (num_shards, suffix) = split_leading_digits(rest)?;
+    let digits_start = head.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (base, index) = head.split_at(digits_start);
+    let base = base.strip_suffix('-')?;
+    let index = index.parse().ok()?;
+    (index < num_shards).then_some((base, index, num_shards, suffix))
+}
+
+fn split_leading_digits(text: &str) -> Option<(usize, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (digits, rest) = text.split_at(end);
+    Some((digits.parse().ok()?, rest))
+}
+
+/// Check that the shard files found by a glob pattern form complete sets.
+fn check_shard_sets(paths: &[PathBuf]) -> Result<()> {
+    let mut sets: BTreeMap<_, BTreeSet<usize>> = BTreeMap::new();
+    for path in paths {
+        let Some((base, index, num_shards, suffix)) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_shard_name)
+        else {
+            continue;
+        };
+        let prefix = path.with_file_name(base);
+        sets.entry((prefix, num_shards, suffix))
+            .or_default()
+            .insert(index);
+    }
+
+    for ((prefix, num_shards, suffix), indexes) in sets {
+        let missing = (0..num_shards)
+            .filter(|index| !indexes.contains(index))
+            .map(|index| shard_path(&prefix, index, num_shards, suffix));
+        check_missing(missing, num_shards)?;
+    }
+    Ok(())
+}
+
+fn check_missing<I, P>(missing: I, num_shards: usize) -> Result<()>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let missing: Vec<_> = missing.into_iter().collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut listed: Vec<_> = missing
+        .iter()
+        .take(MAX_LISTED_MISSING)
+        .map(|path| path.as_ref().display().to_string())
+        .collect();
+    if missing.len() > MAX_LISTED_MISSING {
+        listed.push("...".into());
+    }
+    let desc = format!(
+        "{} of {} shards are missing: {}",
+        missing.len(),
+        num_shards,
+        listed.join(", ")
+    );
+    Err(io::Error::new(io::ErrorKind::NotFound, desc).into())
+}
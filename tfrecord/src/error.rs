@@ -1,17 +1,49 @@
 //! Error types and error handling utilities.
 
-use std::{borrow::Cow, convert::Infallible};
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 /// The result with error type defaults to [Error].
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// The error type for this crate.
+///
+/// # Breaking changes
+///
+/// Since 0.16, errors raised by record readers and indexers are wrapped in [Error::WithContext]
+/// carrying the file location. Code matching on the variants directly, for example
+/// `matches!(err, Error::ChecksumMismatch { .. })`, no longer matches these errors.
+/// Match on [Error::kind] or [Error::inner] instead, which look through the context.
+///
+/// The [Error::ChecksumMismatch] variant gained the `kind` field telling which checksum
+/// mismatches. Patterns listing the fields explicitly must add it or use `..`.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("checksum mismatch error: expect {expect:#010x}, but found {found:#010x}")]
-    ChecksumMismatch { expect: u32, found: u32 },
+    #[error("{kind} checksum mismatch error: expect {expect:#010x}, but found {found:#010x}")]
+    ChecksumMismatch {
+        /// The part of the record which checksum mismatches.
+        ///
+        /// This field is added in 0.16.
+        kind: ChecksumKind,
+        expect: u32,
+        found: u32,
+    },
     #[error("unexpected end of file")]
     UnexpectedEof,
+    #[error(
+        "record length {len} exceeds the limit {max_len}{}",
+        .offset.map(|offset| format!(" at offset {offset}")).unwrap_or_default()
+    )]
+    RecordTooLarge {
+        len: u64,
+        max_len: usize,
+        /// The offset of the record header if known.
+        offset: Option<u64>,
+    },
     #[error("errored to decode example: {0}")]
     ExampleDecodeError(prost::DecodeError),
     #[error("errored to encode example: {0}")]
@@ -22,19 +54,188 @@ pub enum Error {
     ConversionError { desc: Cow<'static, str> },
     #[error("invalid arguments: {desc:}")]
     InvalidArgumentsError { desc: Cow<'static, str> },
+    #[error("{error} ({context})")]
+    WithContext {
+        /// The location where the error occurs.
+        context: ErrorContext,
+        /// The underlying error.
+        error: Box<Error>,
+    },
     #[cfg(feature = "with-tch")]
     #[error("tch error: {0}")]
     TchError(tch::TchError),
+    #[cfg(feature = "with-serde")]
+    #[error("JSON error: {0}")]
+    JsonError(serde_json::Error),
+    #[cfg(feature = "with-serde")]
+    #[error("bincode error: {0}")]
+    BincodeError(bincode::Error),
+}
+
+/// The kind of an [Error], regardless of the attached context.
+///
+/// It is returned by [Error::kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    ChecksumMismatch,
+    UnexpectedEof,
+    RecordTooLarge,
+    ExampleDecodeError,
+    ExampleEncodeError,
+    IoError,
+    ConversionError,
+    InvalidArgumentsError,
+    #[cfg(feature = "with-tch")]
+    TchError,
+    #[cfg(feature = "with-serde")]
+    JsonError,
+    #[cfg(feature = "with-serde")]
+    BincodeError,
+}
+
+/// The part of a record protected by a checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumKind {
+    /// The checksum of the record length in the header.
+    Length,
+    /// The checksum of the record data in the footer.
+    Data,
+}
+
+impl fmt::Display for ChecksumKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length => write!(f, "length"),
+            Self::Data => write!(f, "data"),
+        }
+    }
+}
+
+/// The location in a TFRecord file where an error occurs.
+///
+/// The fields are filled in as far as they are known to the reader raising the error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ErrorContext {
+    /// The path of the file.
+    pub path: Option<PathBuf>,
+    /// The ordinal of the record in the file, counting from zero.
+    pub record_index: Option<u64>,
+    /// The offset of the record header in the uncompressed stream.
+    pub offset: Option<u64>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            path,
+            record_index,
+            offset,
+        } = self;
+        let parts: Vec<_> = [
+            path.as_ref().map(|path| format!("file {}", path.display())),
+            record_index.map(|index| format!("record #{index}")),
+            offset.map(|offset| format!("offset {offset}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(f, "at {}", parts.join(", "))
+    }
 }
 
 impl Error {
+    /// Get the kind of the error.
+    ///
+    /// It looks through [Error::WithContext], so the kind is the same whether or not
+    /// the location is attached.
+    pub fn kind(&self) -> ErrorKind {
+        match self.inner() {
+            Self::ChecksumMismatch { .. } => ErrorKind::ChecksumMismatch,
+            Self::UnexpectedEof => ErrorKind::UnexpectedEof,
+            Self::RecordTooLarge { .. } => ErrorKind::RecordTooLarge,
+            Self::ExampleDecodeError(_) => ErrorKind::ExampleDecodeError,
+            Self::ExampleEncodeError(_) => ErrorKind::ExampleEncodeError,
+            Self::IoError(_) => ErrorKind::IoError,
+            Self::ConversionError { .. } => ErrorKind::ConversionError,
+            Self::InvalidArgumentsError { .. } => ErrorKind::InvalidArgumentsError,
+            Self::WithContext { error, .. } => error.kind(),
+            #[cfg(feature = "with-tch")]
+            Self::TchError(_) => ErrorKind::TchError,
+            #[cfg(feature = "with-serde")]
+            Self::JsonError(_) => ErrorKind::JsonError,
+            #[cfg(feature = "with-serde")]
+            Self::BincodeError(_) => ErrorKind::BincodeError,
+        }
+    }
+
+    /// Get the location of the error if it is known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Get the error without the location.
+    pub fn inner(&self) -> &Error {
+        match self {
+            Self::WithContext { error, .. } => error,
+            error => error,
+        }
+    }
+
+    /// Convert to the error without the location.
+    pub fn into_inner(self) -> Error {
+        match self {
+            Self::WithContext { error, .. } => *error,
+            error => error,
+        }
+    }
+
+    /// Attach the location to the error.
+    ///
+    /// Only the given fields are set, while the fields attached before are kept.
+    pub(crate) fn with_context(
+        self,
+        path: Option<&Path>,
+        record_index: Option<u64>,
+        offset: Option<u64>,
+    ) -> Self {
+        let (mut context, error) = match self {
+            Self::WithContext { context, error } => (context, error),
+            error => (ErrorContext::default(), Box::new(error)),
+        };
+        if let Some(path) = path {
+            context.path = Some(path.to_owned());
+        }
+        context.record_index = record_index.or(context.record_index);
+        context.offset = offset.or(context.offset);
+        Self::WithContext { context, error }
+    }
+
     #[allow(dead_code)]
     pub(crate) fn conversion(desc: impl Into<Cow<'static, str>>) -> Self {
         Self::ConversionError { desc: desc.into() }
     }
 
+    /// Attach the record offset to the error if the error is related to a record position.
+    pub(crate) fn with_offset(self, record_offset: u64) -> Self {
+        match self {
+            Self::RecordTooLarge {
+                len,
+                max_len,
+                offset: None,
+            } => Self::RecordTooLarge {
+                len,
+                max_len,
+                offset: Some(record_offset),
+            },
+            error => error,
+        }
+    }
+
     pub(crate) fn invalid_argument(desc: impl Into<Cow<'static, str>>) -> Self {
-        Self::ConversionError { desc: desc.into() }
+        Self::InvalidArgumentsError { desc: desc.into() }
     }
 }
 
@@ -69,6 +270,20 @@ impl From<tch::TchError> for Error {
     }
 }
 
+#[cfg(feature = "with-serde")]
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::JsonError(error)
+    }
+}
+
+#[cfg(feature = "with-serde")]
+impl From<bincode::Error> for Error {
+    fn from(error: bincode::Error) -> Self {
+        Self::BincodeError(error)
+    }
+}
+
 macro_rules! ensure_argument {
     ($cond:expr, $($arg:tt) *) => {
         if !$cond {
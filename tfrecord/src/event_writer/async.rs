@@ -9,9 +9,12 @@ use crate::{
     protobuf_ext::{IntoHistogram, IntoImageList},
     record_writer::RecordAsyncWriter,
 };
+#[cfg(feature = "async")]
 use async_std::{fs::File, io::BufWriter, path::Path};
 use futures::io::AsyncWrite;
-use std::{borrow::Cow, convert::TryInto, string::ToString};
+#[cfg(feature = "async")]
+use std::borrow::Cow;
+use std::{convert::TryInto, string::ToString};
 
 /// The event writer.
 ///
@@ -50,20 +53,28 @@ use std::{borrow::Cow, convert::TryInto, string::ToString};
 /// # anyhow::Ok(())
 /// # }).unwrap();
 /// ```
-#[derive(Debug, Clone, PartialEq)]
-pub struct EventAsyncWriter<W> {
+#[derive(Debug)]
+pub struct EventAsyncWriter<W>
+where
+    W: AsyncWrite,
+{
     auto_flush: bool,
     events_writer: RecordAsyncWriter<Event, W>,
 }
 
+#[cfg(feature = "async")]
 impl EventAsyncWriter<BufWriter<File>> {
     /// Build a writer writing events to a file.
+    ///
+    /// The file is finalized according to the [durability](EventWriterConfig::durability)
+    /// options on [close](EventAsyncWriter::close).
     pub async fn create<P>(path: P, config: EventWriterConfig) -> Result<Self>
     where
         P: AsRef<Path>,
     {
-        let writer = BufWriter::new(File::create(path).await?);
-        Self::from_writer(writer, config)
+        let events_writer =
+            RecordAsyncWriter::create_with_config(path, config.record_writer_config()).await?;
+        Ok(Self::from_record_writer(events_writer, config))
     }
 
     /// Build a writer writing events to a file, which path is specified by a path prefix and file name suffix.
@@ -89,11 +100,21 @@ where
 {
     /// Build from a writer with [AsyncWrite] trait.
     pub fn from_writer(writer: W, config: EventWriterConfig) -> Result<Self> {
-        let EventWriterConfig { auto_flush } = config;
-        Ok(Self {
+        Ok(Self::from_record_writer(
+            RecordAsyncWriter::from_writer(writer)?,
+            config,
+        ))
+    }
+
+    pub(super) fn from_record_writer(
+        events_writer: RecordAsyncWriter<Event, W>,
+        config: EventWriterConfig,
+    ) -> Self {
+        let EventWriterConfig { auto_flush, .. } = config;
+        Self {
             auto_flush,
-            events_writer: RecordAsyncWriter::from_writer(writer)?,
-        })
+            events_writer,
+        }
     }
 
     /// Write a scalar summary asynchronously.
@@ -104,12 +125,8 @@ where
         value: f32,
     ) -> Result<()> {
         let summary = Summary::from_scalar(tag, value)?;
-        let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event).await?;
-        if self.auto_flush {
-            self.events_writer.flush().await?;
-        }
-        Ok(())
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
     }
 
     /// Write a histogram summary asynchronously.
@@ -120,12 +137,8 @@ where
         histogram: impl IntoHistogram,
     ) -> Result<()> {
         let summary = Summary::from_histogram(tag, histogram)?;
-        let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event).await?;
-        if self.auto_flush {
-            self.events_writer.flush().await?;
-        }
-        Ok(())
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
     }
 
     /// Write a tensor summary asynchronously.
@@ -136,12 +149,8 @@ where
         tensor: impl TryInto<TensorProto, Error = impl Into<Error>>,
     ) -> Result<()> {
         let summary = Summary::from_tensor(tag, tensor)?;
-        let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event).await?;
-        if self.auto_flush {
-            self.events_writer.flush().await?;
-        }
-        Ok(())
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
     }
 
     /// Write an image summary asynchronously.
@@ -152,12 +161,8 @@ where
         image: impl TryInto<Image, Error = impl Into<Error>>,
     ) -> Result<()> {
         let summary = Summary::from_image(tag, image)?;
-        let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event).await?;
-        if self.auto_flush {
-            self.events_writer.flush().await?;
-        }
-        Ok(())
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
     }
 
     /// Write a summary with multiple images asynchronously.
@@ -168,12 +173,8 @@ where
         images: impl IntoImageList,
     ) -> Result<()> {
         let summary = Summary::from_image_list(tag, images)?;
-        let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event).await?;
-        if self.auto_flush {
-            self.events_writer.flush().await?;
-        }
-        Ok(())
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
     }
 
     /// Write an audio summary asynchronously.
@@ -184,17 +185,13 @@ where
         audio: impl TryInto<Audio, Error = impl Into<Error>>,
     ) -> Result<()> {
         let summary = Summary::from_audio(tag, audio)?;
-        let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event).await?;
-        if self.auto_flush {
-            self.events_writer.flush().await?;
-        }
-        Ok(())
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
     }
 
     /// Write a custom event asynchronously.
     pub async fn write_event(&mut self, event: Event) -> Result<()> {
-        self.events_writer.send(event).await?;
+        self.events_writer.send_ref(&event).await?;
         if self.auto_flush {
             self.events_writer.flush().await?;
         }
@@ -206,4 +203,9 @@ where
         self.events_writer.flush().await?;
         Ok(())
     }
+
+    /// Closes the inner writer and finalizes the file.
+    pub async fn close(&mut self) -> Result<()> {
+        self.events_writer.close().await
+    }
 }
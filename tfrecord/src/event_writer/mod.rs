@@ -3,12 +3,21 @@
 mod sync;
 pub use sync::*;
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 mod r#async;
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 pub use r#async::*;
 
-use crate::{error::Error, utils};
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use self::tokio::*;
+
+use crate::{
+    error::Error,
+    record_writer::{DurabilityConfig, RecordWriterConfig},
+    utils,
+};
 use std::{
     borrow::Cow,
     ffi::{OsStr, OsString},
@@ -22,11 +31,26 @@ use std::{
 pub struct EventWriterConfig {
     /// If set, the writer flushes the buffer after writing a event.
     pub auto_flush: bool,
+    /// The finalization of files created by the writer.
+    pub durability: DurabilityConfig,
 }
 
 impl Default for EventWriterConfig {
     fn default() -> Self {
-        Self { auto_flush: true }
+        Self {
+            auto_flush: true,
+            durability: Default::default(),
+        }
+    }
+}
+
+impl EventWriterConfig {
+    /// The configuration of the record writer for files created by event writers.
+    fn record_writer_config(&self) -> RecordWriterConfig {
+        RecordWriterConfig {
+            durability: self.durability.clone(),
+            ..Default::default()
+        }
     }
 }
 
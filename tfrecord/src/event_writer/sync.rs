@@ -25,7 +25,6 @@ use std::{
 ///
 /// It can be built from a writer using [from_writer](EventWriter::from_writer), or write a new file
 /// specified by path prefix using [from_writer](EventWriter::from_prefix).
-
 #[cfg_attr(
     feature = "tch",
     doc = r##"
@@ -55,20 +54,29 @@ writer.write_tensor(
 ```
 "##
 )]
-#[derive(Debug, Clone, PartialEq)]
-pub struct EventWriter<W> {
+#[derive(Debug)]
+pub struct EventWriter<W>
+where
+    W: Write,
+{
     auto_flush: bool,
     events_writer: RecordWriter<Event, W>,
 }
 
 impl EventWriter<BufWriter<File>> {
     /// Build a writer writing events to a file.
+    ///
+    /// The file is finalized according to the [durability](EventWriterConfig::durability)
+    /// options on [close](EventWriter::close).
     pub fn create<P>(path: P, config: EventWriterConfig) -> Result<Self>
     where
         P: AsRef<Path>,
     {
-        let writer = BufWriter::new(File::create(path)?);
-        Self::from_writer(writer, config)
+        let events_writer = RecordWriter::create_with_config(path, config.record_writer_config())?;
+        Ok(Self {
+            auto_flush: config.auto_flush,
+            events_writer,
+        })
     }
 
     /// Build a writer writing events to a file, which path is specified by a path prefix and file name suffix.
@@ -97,7 +105,7 @@ where
     where
         W: Write,
     {
-        let EventWriterConfig { auto_flush } = config;
+        let EventWriterConfig { auto_flush, .. } = config;
 
         Ok(Self {
             auto_flush,
@@ -114,7 +122,7 @@ where
     ) -> Result<()> {
         let summary = Summary::from_scalar(tag, value)?;
         let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event)?;
+        self.events_writer.send_ref(&event)?;
         if self.auto_flush {
             self.events_writer.flush()?;
         }
@@ -130,7 +138,7 @@ where
     ) -> Result<()> {
         let summary = Summary::from_histogram(tag, histogram)?;
         let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event)?;
+        self.events_writer.send_ref(&event)?;
         if self.auto_flush {
             self.events_writer.flush()?;
         }
@@ -146,7 +154,7 @@ where
     ) -> Result<()> {
         let summary = Summary::from_tensor(tag, tensor)?;
         let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event)?;
+        self.events_writer.send_ref(&event)?;
         if self.auto_flush {
             self.events_writer.flush()?;
         }
@@ -162,7 +170,7 @@ where
     ) -> Result<()> {
         let summary = Summary::from_image(tag, image)?;
         let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event)?;
+        self.events_writer.send_ref(&event)?;
         if self.auto_flush {
             self.events_writer.flush()?;
         }
@@ -178,7 +186,7 @@ where
     ) -> Result<()> {
         let summary = Summary::from_image_list(tag, images)?;
         let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event)?;
+        self.events_writer.send_ref(&event)?;
         if self.auto_flush {
             self.events_writer.flush()?;
         }
@@ -194,7 +202,7 @@ where
     ) -> Result<()> {
         let summary = Summary::from_audio(tag, audio)?;
         let event = event_meta.into().build_with_summary(summary);
-        self.events_writer.send(event)?;
+        self.events_writer.send_ref(&event)?;
         if self.auto_flush {
             self.events_writer.flush()?;
         }
@@ -209,7 +217,7 @@ where
 
     /// Write a custom event.
     pub fn write_event(&mut self, event: Event) -> Result<()> {
-        self.events_writer.send(event)?;
+        self.events_writer.send_ref(&event)?;
         if self.auto_flush {
             self.events_writer.flush()?;
         }
@@ -221,4 +229,9 @@ where
         self.events_writer.flush()?;
         Ok(())
     }
+
+    /// Finalize the output and return the inner writer.
+    pub fn close(self) -> Result<W> {
+        self.events_writer.close()
+    }
 }
@@ -0,0 +1,191 @@
+use super::EventWriterConfig;
+use crate::{
+    error::{Error, Result},
+    event::EventMeta,
+    protobuf::{
+        summary::{Audio, Image},
+        Event, Summary, TensorProto,
+    },
+    protobuf_ext::{IntoHistogram, IntoImageList},
+    record_writer::RecordTokioWriter,
+};
+use ::tokio::{
+    fs::File,
+    io::{AsyncWrite, BufWriter},
+};
+use std::{borrow::Cow, convert::TryInto, path::Path, string::ToString};
+
+/// The event writer on writers with tokio's [AsyncWrite] trait.
+///
+/// It is the tokio counterpart of [EventAsyncWriter](super::EventAsyncWriter), providing
+/// `write_scalar`, `write_image` methods, etc.
+///
+/// ```rust,no_run
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async move {
+/// use tfrecord::EventTokioWriter;
+///
+/// let mut writer = EventTokioWriter::from_prefix("log_dir/myprefix-", "", Default::default())
+///     .await?;
+///
+/// // step = 0, scalar = 3.14
+/// writer.write_scalar("my_scalar", 0, 3.14).await?;
+/// writer.close().await?;
+/// # Ok::<_, tfrecord::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct EventTokioWriter<W>
+where
+    W: AsyncWrite,
+{
+    auto_flush: bool,
+    events_writer: RecordTokioWriter<Event, W>,
+}
+
+impl EventTokioWriter<BufWriter<File>> {
+    /// Build a writer writing events to a file using the tokio runtime.
+    ///
+    /// The file is finalized according to the [durability](EventWriterConfig::durability)
+    /// options on [close](EventTokioWriter::close).
+    pub async fn create<P>(path: P, config: EventWriterConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let events_writer =
+            RecordTokioWriter::create_with_config(path, config.record_writer_config()).await?;
+        Ok(Self::from_record_writer(events_writer, config))
+    }
+
+    /// Build a writer writing events to a file using the tokio runtime, which path is specified
+    /// by a path prefix and file name suffix.
+    pub async fn from_prefix<'a, 'b, P, S>(
+        prefix: P,
+        file_name_suffix: S,
+        config: EventWriterConfig,
+    ) -> Result<Self>
+    where
+        P: Into<Cow<'a, str>>,
+        S: Into<Cow<'b, str>>,
+    {
+        let (dir_prefix, file_name) = super::create_tf_style_path(prefix, file_name_suffix)?;
+        ::tokio::fs::create_dir_all(&dir_prefix).await?;
+        let path = dir_prefix.join(file_name);
+        Self::create(path, config).await
+    }
+}
+
+impl<W> EventTokioWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    /// Build from a writer with tokio's [AsyncWrite] trait.
+    pub fn from_writer(writer: W, config: EventWriterConfig) -> Result<Self> {
+        Ok(Self::from_record_writer(
+            RecordTokioWriter::from_writer(writer)?,
+            config,
+        ))
+    }
+
+    fn from_record_writer(
+        events_writer: RecordTokioWriter<Event, W>,
+        config: EventWriterConfig,
+    ) -> Self {
+        let EventWriterConfig { auto_flush, .. } = config;
+        Self {
+            auto_flush,
+            events_writer,
+        }
+    }
+
+    /// Write a scalar summary.
+    pub async fn write_scalar(
+        &mut self,
+        tag: impl ToString,
+        event_meta: impl Into<EventMeta>,
+        value: f32,
+    ) -> Result<()> {
+        let summary = Summary::from_scalar(tag, value)?;
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
+    }
+
+    /// Write a histogram summary.
+    pub async fn write_histogram(
+        &mut self,
+        tag: impl ToString,
+        event_meta: impl Into<EventMeta>,
+        histogram: impl IntoHistogram,
+    ) -> Result<()> {
+        let summary = Summary::from_histogram(tag, histogram)?;
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
+    }
+
+    /// Write a tensor summary.
+    pub async fn write_tensor(
+        &mut self,
+        tag: impl ToString,
+        event_meta: impl Into<EventMeta>,
+        tensor: impl TryInto<TensorProto, Error = impl Into<Error>>,
+    ) -> Result<()> {
+        let summary = Summary::from_tensor(tag, tensor)?;
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
+    }
+
+    /// Write an image summary.
+    pub async fn write_image(
+        &mut self,
+        tag: impl ToString,
+        event_meta: impl Into<EventMeta>,
+        image: impl TryInto<Image, Error = impl Into<Error>>,
+    ) -> Result<()> {
+        let summary = Summary::from_image(tag, image)?;
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
+    }
+
+    /// Write a summary with multiple images.
+    pub async fn write_image_list(
+        &mut self,
+        tag: impl ToString,
+        event_meta: impl Into<EventMeta>,
+        images: impl IntoImageList,
+    ) -> Result<()> {
+        let summary = Summary::from_image_list(tag, images)?;
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
+    }
+
+    /// Write an audio summary.
+    pub async fn write_audio(
+        &mut self,
+        tag: impl ToString,
+        event_meta: impl Into<EventMeta>,
+        audio: impl TryInto<Audio, Error = impl Into<Error>>,
+    ) -> Result<()> {
+        let summary = Summary::from_audio(tag, audio)?;
+        self.write_event(event_meta.into().build_with_summary(summary))
+            .await
+    }
+
+    /// Write a custom event.
+    pub async fn write_event(&mut self, event: Event) -> Result<()> {
+        self.events_writer.send_ref(&event).await?;
+        if self.auto_flush {
+            self.events_writer.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Flush this output stream.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.events_writer.flush().await?;
+        Ok(())
+    }
+
+    /// Shut down the inner writer and finalize the file.
+    pub async fn close(&mut self) -> Result<()> {
+        self.events_writer.close().await
+    }
+}
@@ -1,21 +1,23 @@
-use super::{Position, RecordIndex, RecordIndexerConfig};
-use crate::{
-    error::{Error, Result},
-    record::Record,
-    utils,
-};
-use async_std::{
-    fs::File,
-    io::BufReader,
-    path::{Path, PathBuf},
-};
+use super::{Position, RecordIndexerConfig};
+use crate::{error::Result, io::r#async::FuturesIo};
 use futures::{
-    io::{AsyncRead, AsyncSeek, AsyncSeekExt as _},
-    stream,
-    stream::{Stream, StreamExt as _, TryStreamExt as _},
+    io::{AsyncRead, AsyncSeek},
+    stream::Stream,
+};
+#[cfg(feature = "async")]
+use {
+    super::RecordIndex,
+    crate::{error::Error, record::Record, utils},
+    async_std::{
+        fs::File,
+        io::BufReader,
+        path::{Path, PathBuf},
+    },
+    futures::stream::{self, StreamExt as _, TryStreamExt as _},
+    std::{borrow::Cow, future::Future, sync::Arc},
 };
-use std::{borrow::Cow, future::Future, io::SeekFrom, sync::Arc};
 
+#[cfg(feature = "async")]
 impl RecordIndex {
     /// Load the record data for the index.
     pub async fn load_async<T>(&self) -> Result<T>
@@ -27,14 +29,17 @@ impl RecordIndex {
             offset,
             len,
         } = *self;
-        let mut reader = BufReader::new(File::open(&**path).await?);
-        let bytes = read_record_at(&mut reader, offset, len).await?;
-        let record = T::from_bytes(bytes)?;
-        Ok(record)
+        let load = async {
+            let mut reader = BufReader::new(File::open(&**path).await?);
+            let bytes = super::poll::read_record_at(FuturesIo(&mut reader), offset, len).await?;
+            T::decode(&bytes)
+        };
+        load.await.map_err(|error| self.locate(error))
     }
 }
 
 /// Load record indexes from files specified by a prefix.
+#[cfg(feature = "async")]
 pub async fn load_prefix_async<'a, P>(
     prefix: P,
     config: RecordIndexerConfig,
@@ -50,6 +55,7 @@ where
 }
 
 /// Generate futures that load record indexes from files specified by a prefix.
+#[cfg(feature = "async")]
 pub async fn load_prefix_futures<'a, P>(
     prefix: P,
     config: RecordIndexerConfig,
@@ -92,7 +98,22 @@ where
     Ok(stream)
 }
 
+/// Load record indexes from files specified by a glob pattern or shard spec.
+///
+/// See [discovery](crate::discovery) for the accepted patterns.
+#[cfg(feature = "async")]
+pub async fn load_pattern_async(
+    pattern: &str,
+    config: RecordIndexerConfig,
+) -> Result<impl Stream<Item = Result<RecordIndex>>> {
+    let pattern = pattern.to_owned();
+    let paths =
+        async_std::task::spawn_blocking(move || crate::discovery::find_files(&pattern)).await?;
+    Ok(load_paths_async(paths, config))
+}
+
 /// Load record indexes from file paths.
+#[cfg(feature = "async")]
 pub fn load_paths_async<'a, P, I>(
     paths: I,
     config: RecordIndexerConfig,
@@ -107,6 +128,7 @@ where
 }
 
 /// Generate futures that load record indexes from file paths.
+#[cfg(feature = "async")]
 pub fn load_paths_futures<'a, P, I>(
     paths: I,
     config: RecordIndexerConfig,
@@ -121,6 +143,7 @@ where
 }
 
 /// Load record indexes from a file.
+#[cfg(feature = "async")]
 pub async fn load_file_async<'a, P>(
     file: P,
     config: RecordIndexerConfig,
@@ -129,11 +152,16 @@ where
     P: Into<Cow<'a, std::path::Path>>,
 {
     let file = file.into().into_owned();
-    let reader = BufReader::new(File::open(&file).await?);
+    let reader = BufReader::new(
+        File::open(&file)
+            .await
+            .map_err(|error| Error::from(error).with_context(Some(&file), None, None))?,
+    );
 
-    let file = Arc::new(std::path::PathBuf::from(file.into_os_string()));
+    let file = Arc::new(file);
     let stream = load_reader_async(reader, config).map(move |pos| {
-        let Position { offset, len } = pos?;
+        let Position { offset, len } =
+            pos.map_err(|error| error.with_context(Some(&file), None, None))?;
         Ok(RecordIndex {
             path: file.clone(),
             offset,
@@ -144,6 +172,8 @@ where
 }
 
 /// Load record indexes from a reader.
+///
+/// The errors are attached with the [context](crate::Error::context) locating the record.
 pub fn load_reader_async<R>(
     reader: R,
     config: RecordIndexerConfig,
@@ -151,40 +181,5 @@ pub fn load_reader_async<R>(
 where
     R: AsyncRead + AsyncSeek + Unpin,
 {
-    let RecordIndexerConfig { check_integrity } = config;
-
-    stream::try_unfold(reader, move |mut reader| async move {
-        let len = match crate::io::r#async::try_read_len(&mut reader, check_integrity).await? {
-            Some(len) => len,
-            None => return Ok(None),
-        };
-
-        let offset = reader.seek(SeekFrom::Current(0)).await?;
-        skip_or_check(&mut reader, len, check_integrity).await?;
-
-        let pos = Position { offset, len };
-        Result::<_, Error>::Ok(Some((pos, reader)))
-    })
-}
-
-async fn read_record_at<R>(reader: &mut R, offset: u64, len: usize) -> Result<Vec<u8>>
-where
-    R: AsyncRead + AsyncSeek + Unpin,
-{
-    reader.seek(SeekFrom::Start(offset)).await?;
-    let bytes = crate::io::r#async::try_read_record_data(reader, len, false).await?;
-
-    Ok(bytes)
-}
-
-async fn skip_or_check<R>(reader: &mut R, len: usize, check_integrity: bool) -> Result<()>
-where
-    R: AsyncRead + AsyncSeek + Unpin,
-{
-    if check_integrity {
-        crate::io::r#async::try_read_record_data(reader, len, check_integrity).await?;
-    } else {
-        reader.seek(SeekFrom::Current(len as i64)).await?;
-    }
-    Ok(())
+    super::poll::load_reader(FuturesIo(reader), config)
 }
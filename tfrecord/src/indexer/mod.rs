@@ -1,13 +1,27 @@
 //! The indexer that enumerate record locations from one or multiple TFRecord files.
+//!
+//! The record locations can be saved to and loaded from index sidecar files, including
+//! the text format of NVIDIA DALI's `tfrecord2idx`. See [IndexFormat].
 
 mod sync;
 pub use sync::*;
 
-#[cfg(feature = "async")]
+mod sidecar;
+pub use sidecar::*;
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod poll;
+
+#[cfg(feature = "futures-io")]
 mod r#async;
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 pub use r#async::*;
 
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use self::tokio::*;
+
 use std::{path::PathBuf, sync::Arc};
 
 /// The file path and record position in file.
@@ -29,12 +43,18 @@ pub struct Position {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RecordIndexerConfig {
     pub check_integrity: bool,
+    /// The maximum accepted record length in bytes.
+    ///
+    /// It defaults to [DEFAULT_MAX_RECORD_LEN](crate::io::DEFAULT_MAX_RECORD_LEN), and is not
+    /// bounded if `None`.
+    pub max_record_len: Option<usize>,
 }
 
 impl Default for RecordIndexerConfig {
     fn default() -> Self {
         Self {
             check_integrity: true,
+            max_record_len: Some(crate::io::DEFAULT_MAX_RECORD_LEN),
         }
     }
 }
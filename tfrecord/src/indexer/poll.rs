@@ -0,0 +1,88 @@
+use super::{Position, RecordIndexerConfig};
+use crate::{
+    error::{Error, Result},
+    io::{
+        poll::{self, PollRead, PollSeek},
+        HEADER_SIZE,
+    },
+};
+use futures::stream::{self, Stream};
+use std::io::SeekFrom;
+
+/// Load record indexes from a reader of any runtime.
+///
+/// The errors are attached with the [context](Error::context) locating the record.
+pub(super) fn load_reader<R>(
+    reader: R,
+    config: RecordIndexerConfig,
+) -> impl Stream<Item = Result<Position>>
+where
+    R: PollRead + PollSeek,
+{
+    let RecordIndexerConfig {
+        check_integrity,
+        max_record_len,
+    } = config;
+
+    stream::try_unfold((reader, 0), move |(mut reader, index)| async move {
+        let len = match poll::try_read_len_with_max_len(
+            &mut reader,
+            check_integrity,
+            max_record_len,
+            None,
+        )
+        .await
+        {
+            Ok(Some(len)) => len,
+            Ok(None) => return Ok(None),
+            Err(err) => {
+                // the header is consumed when the length is rejected
+                let err = match poll::seek(&mut reader, SeekFrom::Current(0)).await {
+                    Ok(pos) => {
+                        let offset = pos.saturating_sub(HEADER_SIZE as u64);
+                        err.with_offset(offset)
+                            .with_context(None, Some(index), Some(offset))
+                    }
+                    Err(_) => err.with_context(None, Some(index), None),
+                };
+                return Err(err);
+            }
+        };
+
+        let offset = poll::seek(&mut reader, SeekFrom::Current(0))
+            .await
+            .map_err(|err| Error::from(err).with_context(None, Some(index), None))?;
+        skip_or_check(&mut reader, len, check_integrity)
+            .await
+            .map_err(|err| {
+                let offset = offset.saturating_sub(HEADER_SIZE as u64);
+                err.with_context(None, Some(index), Some(offset))
+            })?;
+
+        let pos = Position { offset, len };
+        Result::<_, Error>::Ok(Some((pos, (reader, index + 1))))
+    })
+}
+
+pub(super) async fn read_record_at<R>(mut reader: R, offset: u64, len: usize) -> Result<Vec<u8>>
+where
+    R: PollRead + PollSeek,
+{
+    poll::seek(&mut reader, SeekFrom::Start(offset)).await?;
+    let mut bytes = vec![];
+    poll::try_read_record_data_into(&mut reader, len, false, &mut bytes).await?;
+    Ok(bytes)
+}
+
+async fn skip_or_check<R>(reader: &mut R, len: usize, check_integrity: bool) -> Result<()>
+where
+    R: PollRead + PollSeek,
+{
+    if check_integrity {
+        let mut buf = vec![];
+        poll::try_read_record_data_into(reader, len, check_integrity, &mut buf).await?;
+    } else {
+        poll::seek(reader, SeekFrom::Current(len as i64)).await?;
+    }
+    Ok(())
+}
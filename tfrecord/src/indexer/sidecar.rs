@@ -0,0 +1,397 @@
+use super::{Position, RecordIndex, RecordIndexerConfig};
+use crate::{
+    error::Result,
+    io::{FOOTER_SIZE, HEADER_SIZE},
+};
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, prelude::*, BufReader, BufWriter, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The magic bytes at the beginning of binary index files.
+const BINARY_MAGIC: &[u8; 8] = b"TFRIDX01";
+
+/// The size of the header of binary index files, which precedes the positions.
+const BINARY_HEADER_SIZE: usize = 36;
+
+/// The file format of record index sidecars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum IndexFormat {
+    /// The text format produced by NVIDIA DALI's `tfrecord2idx`.
+    ///
+    /// Each line has the offset and the total size of a framed record separated by a space.
+    /// The format has no room for metadata, so an index is considered stale if it is older
+    /// than the data file or does not cover the whole data file.
+    #[default]
+    Dali,
+    /// A compact binary format.
+    ///
+    /// It stores the size and the modification time of the data file, and the index is
+    /// considered stale if either one changes.
+    Binary,
+}
+
+impl IndexFormat {
+    /// Get the default sidecar path for a data file.
+    ///
+    /// It appends `.idx` for [Dali](IndexFormat::Dali) format and `.idx.bin` for
+    /// [Binary](IndexFormat::Binary) format to the file name.
+    pub fn sidecar_path<P>(&self, data_path: P) -> PathBuf
+    where
+        P: AsRef<Path>,
+    {
+        let mut path = OsString::from(data_path.as_ref());
+        path.push(match self {
+            Self::Dali => ".idx",
+            Self::Binary => ".idx.bin",
+        });
+        path.into()
+    }
+}
+
+/// The size and modification time of a data file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    mtime: Option<SystemTime>,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(Self {
+            len: metadata.len(),
+            mtime: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Save record positions of a data file to an index file.
+///
+/// The index is written to a temporary file first and renamed to `index_path`,
+/// so that readers never observe a partially written index.
+pub fn save_index<P, Q>(
+    data_path: P,
+    index_path: Q,
+    positions: &[Position],
+    format: IndexFormat,
+) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut writer = IndexWriter::create(index_path, format)?;
+    for &position in positions {
+        writer.push(position)?;
+    }
+    writer.finish(data_path.as_ref())
+}
+
+/// The writer of an index file, which streams the positions as they are pushed.
+///
+/// The positions are written to a temporary file, which is renamed to the index path
+/// on [finish](IndexWriter::finish). The temporary file is left behind if it is not finished.
+#[derive(Debug)]
+pub(crate) struct IndexWriter {
+    index_path: PathBuf,
+    tmp_path: PathBuf,
+    writer: BufWriter<File>,
+    format: IndexFormat,
+    count: u64,
+}
+
+impl IndexWriter {
+    /// Start writing an index file at `index_path`.
+    pub(crate) fn create<P>(index_path: P, format: IndexFormat) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let index_path = index_path.as_ref().to_owned();
+        let tmp_path = {
+            let mut path = OsString::from(&index_path);
+            path.push(".tmp");
+            PathBuf::from(path)
+        };
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        if format == IndexFormat::Binary {
+            // the header is filled on finish, when the data file is complete
+            writer.write_all(&[0; BINARY_HEADER_SIZE])?;
+        }
+
+        Ok(Self {
+            index_path,
+            tmp_path,
+            writer,
+            format,
+            count: 0,
+        })
+    }
+
+    /// Append the position of the next record.
+    pub(crate) fn push(&mut self, position: Position) -> io::Result<()> {
+        match self.format {
+            IndexFormat::Dali => write_dali_entry(&mut self.writer, position)?,
+            IndexFormat::Binary => write_binary_entry(&mut self.writer, position)?,
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Complete the index of the data file at `data_path` and move it to the index path.
+    pub(crate) fn finish(self, data_path: &Path) -> Result<()> {
+        let Self {
+            index_path,
+            tmp_path,
+            writer,
+            format,
+            count,
+        } = self;
+
+        let mut file = writer.into_inner().map_err(io::Error::from)?;
+        match format {
+            IndexFormat::Dali => {
+                // the index must not look older than the data file it covers
+                file.set_modified(SystemTime::now())?;
+            }
+            IndexFormat::Binary => {
+                let stamp = FileStamp::of(data_path)?;
+                file.seek(SeekFrom::Start(0))?;
+                write_binary_header(&mut file, stamp, count)?;
+            }
+        }
+        file.sync_all()?;
+        fs::rename(&tmp_path, index_path)?;
+        Ok(())
+    }
+}
+
+/// Load record positions of a data file from an index file.
+///
+/// It returns `Ok(None)` if the index file does not exist or is stale.
+pub fn load_index<P, Q>(
+    data_path: P,
+    index_path: Q,
+    format: IndexFormat,
+) -> Result<Option<Vec<Position>>>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let index_path = index_path.as_ref();
+    let file = match File::open(index_path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let stamp = FileStamp::of(data_path.as_ref())?;
+    let mut reader = BufReader::new(file);
+
+    let positions = match format {
+        IndexFormat::Dali => {
+            let index_mtime = fs::metadata(index_path)?.modified().ok();
+            let is_older =
+                matches!((index_mtime, stamp.mtime), (Some(index), Some(data)) if index < data);
+            if is_older {
+                return Ok(None);
+            }
+            read_dali(&mut reader)?
+        }
+        IndexFormat::Binary => {
+            let (saved_stamp, positions) = read_binary(&mut reader)?;
+            if saved_stamp != stamp {
+                return Ok(None);
+            }
+            positions
+        }
+    };
+
+    // the records must cover the whole data file
+    let end = positions
+        .last()
+        .map(|pos| pos.offset + (pos.len + FOOTER_SIZE) as u64)
+        .unwrap_or(0);
+    if end != stamp.len {
+        return Ok(None);
+    }
+    Ok(Some(positions))
+}
+
+/// Load record positions of a data file from an index file, or build the index if it is
+/// missing or stale.
+///
+/// A newly built index is saved to `index_path`.
+pub fn load_or_build_index<P, Q>(
+    data_path: P,
+    index_path: Q,
+    format: IndexFormat,
+    config: RecordIndexerConfig,
+) -> Result<Vec<Position>>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let data_path = data_path.as_ref();
+    let index_path = index_path.as_ref();
+
+    if let Some(positions) = load_index(data_path, index_path, format)? {
+        return Ok(positions);
+    }
+
+    let reader = BufReader::new(File::open(data_path)?);
+    let positions: Vec<_> = super::load_reader(reader, config).collect::<Result<_>>()?;
+    save_index(data_path, index_path, &positions, format)?;
+    Ok(positions)
+}
+
+/// Load record indexes from a file using the sidecar index at the default path.
+///
+/// The sidecar is built and saved if it is missing or stale.
+/// See [IndexFormat::sidecar_path] for the default path.
+pub fn load_file_cached<P>(
+    file: P,
+    format: IndexFormat,
+    config: RecordIndexerConfig,
+) -> Result<Vec<RecordIndex>>
+where
+    P: AsRef<Path>,
+{
+    let file = file.as_ref();
+    let positions = load_or_build_index(file, format.sidecar_path(file), format, config)?;
+    let file = Arc::new(file.to_owned());
+    let indexes = positions
+        .into_iter()
+        .map(|Position { offset, len }| RecordIndex {
+            path: file.clone(),
+            offset,
+            len,
+        })
+        .collect();
+    Ok(indexes)
+}
+
+fn write_dali_entry<W>(writer: &mut W, position: Position) -> io::Result<()>
+where
+    W: Write,
+{
+    let Position { offset, len } = position;
+    let start = offset - HEADER_SIZE as u64;
+    let size = crate::io::record_size(len);
+    writeln!(writer, "{start} {size}")
+}
+
+fn read_dali<R>(reader: &mut R) -> Result<Vec<Position>>
+where
+    R: BufRead,
+{
+    let mut positions = vec![];
+
+    for (lineno, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let parse = || -> Option<Position> {
+            let (start, size) = line.split_once(char::is_whitespace)?;
+            let start: u64 = start.parse().ok()?;
+            let size: usize = size.trim_start().parse().ok()?;
+            let len = size.checked_sub(HEADER_SIZE + FOOTER_SIZE)?;
+            Some(Position {
+                offset: start + HEADER_SIZE as u64,
+                len,
+            })
+        };
+        let position = parse()
+            .ok_or_else(|| invalid_data(format!("malformed index entry at line {}", lineno + 1)))?;
+        positions.push(position);
+    }
+
+    Ok(positions)
+}
+
+fn write_binary_header<W>(writer: &mut W, stamp: FileStamp, count: u64) -> io::Result<()>
+where
+    W: Write,
+{
+    let mtime = stamp
+        .mtime
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok());
+    let (secs, nanos) = match mtime {
+        Some(mtime) => (mtime.as_secs(), mtime.subsec_nanos()),
+        None => (u64::MAX, u32::MAX),
+    };
+
+    writer.write_all(BINARY_MAGIC)?;
+    writer.write_all(&stamp.len.to_le_bytes())?;
+    writer.write_all(&secs.to_le_bytes())?;
+    writer.write_all(&nanos.to_le_bytes())?;
+    writer.write_all(&count.to_le_bytes())
+}
+
+fn write_binary_entry<W>(writer: &mut W, position: Position) -> io::Result<()>
+where
+    W: Write,
+{
+    let Position { offset, len } = position;
+    writer.write_all(&offset.to_le_bytes())?;
+    writer.write_all(&(len as u64).to_le_bytes())
+}
+
+fn read_binary<R>(reader: &mut R) -> Result<(FileStamp, Vec<Position>)>
+where
+    R: Read,
+{
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != BINARY_MAGIC {
+        return Err(invalid_data("not a binary record index file".into()).into());
+    }
+
+    let len = read_u64(reader)?;
+    let secs = read_u64(reader)?;
+    let nanos = {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        u32::from_le_bytes(buf)
+    };
+    let mtime = if secs == u64::MAX {
+        None
+    } else {
+        if nanos >= 1_000_000_000 {
+            return Err(invalid_data("invalid modification time".into()).into());
+        }
+        let mtime = UNIX_EPOCH
+            .checked_add(Duration::new(secs, nanos))
+            .ok_or_else(|| invalid_data("modification time overflows".into()))?;
+        Some(mtime)
+    };
+
+    let count = read_u64(reader)?;
+    let mut positions = vec![];
+    for _ in 0..count {
+        let offset = read_u64(reader)?;
+        let len = usize::try_from(read_u64(reader)?)
+            .map_err(|_| invalid_data("record length overflows".into()))?;
+        positions.push(Position { offset, len });
+    }
+
+    Ok((FileStamp { len, mtime }, positions))
+}
+
+fn read_u64<R>(reader: &mut R) -> io::Result<u64>
+where
+    R: Read,
+{
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn invalid_data(desc: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, desc)
+}
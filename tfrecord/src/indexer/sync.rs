@@ -1,6 +1,7 @@
 use super::{Position, RecordIndex, RecordIndexerConfig};
 use crate::{
     error::{Error, Result},
+    io::HEADER_SIZE,
     record::Record,
     utils,
 };
@@ -14,6 +15,9 @@ use std::{
 };
 
 impl RecordIndex {
+    /// Load the record data for the index.
+    ///
+    /// The errors are attached with the [context](Error::context) locating the record.
     pub fn load<T>(&self) -> Result<T>
     where
         T: Record,
@@ -23,10 +27,18 @@ impl RecordIndex {
             offset,
             len,
         } = *self;
-        let mut reader = BufReader::new(File::open(&**path)?);
-        let bytes = read_record_at(&mut reader, offset, len)?;
-        let record = T::from_bytes(bytes)?;
-        Ok(record)
+        let load = || -> Result<T> {
+            let mut reader = BufReader::new(File::open(&**path)?);
+            let bytes = read_record_at(&mut reader, offset, len)?;
+            T::decode(&bytes)
+        };
+        load().map_err(|error| self.locate(error))
+    }
+
+    /// Attach the location of the record to an error.
+    pub(super) fn locate(&self, error: Error) -> Error {
+        let offset = self.offset.saturating_sub(HEADER_SIZE as u64);
+        error.with_context(Some(&self.path), None, Some(offset))
     }
 }
 
@@ -73,6 +85,17 @@ where
     Ok(indexes)
 }
 
+/// Load record indexes from files specified by a glob pattern or shard spec.
+///
+/// See [discovery](crate::discovery) for the accepted patterns.
+pub fn load_pattern(
+    pattern: &str,
+    config: RecordIndexerConfig,
+) -> Result<impl Iterator<Item = Result<RecordIndex>>> {
+    let paths = crate::discovery::find_files(pattern)?;
+    Ok(load_paths(paths, config))
+}
+
 /// Load record indexes from file paths.
 pub fn load_paths<'a, P, I>(
     paths: I,
@@ -86,7 +109,7 @@ where
         .into_iter()
         .map(|path| path.into().into_owned())
         .map(move |path| load_file(path, config.clone()))
-        .map(
+        .flat_map(
             |iter| -> Box<dyn Iterator<Item = Result<RecordIndex>> + Send> {
                 match iter {
                     Ok(iter) => Box::new(iter),
@@ -94,7 +117,6 @@ where
                 }
             },
         )
-        .flatten()
 }
 
 /// Load record indexes from a file.
@@ -106,10 +128,14 @@ where
     P: Into<Cow<'a, Path>>,
 {
     let file = file.into().into_owned();
-    let reader = BufReader::new(File::open(&file)?);
+    let reader = BufReader::new(
+        File::open(&file)
+            .map_err(|error| Error::from(error).with_context(Some(&file), None, None))?,
+    );
     let file = Arc::new(file);
     let iter = load_reader(reader, config).map(move |pos| {
-        let Position { offset, len } = pos?;
+        let Position { offset, len } =
+            pos.map_err(|error| error.with_context(Some(&file), None, None))?;
         Ok(RecordIndex {
             path: file.clone(),
             offset,
@@ -120,6 +146,8 @@ where
 }
 
 /// Load record indexes from a reader.
+///
+/// The errors are attached with the [context](Error::context) locating the record.
 pub fn load_reader<R>(
     reader: R,
     config: RecordIndexerConfig,
@@ -127,28 +155,51 @@ pub fn load_reader<R>(
 where
     R: Read + Seek,
 {
-    let RecordIndexerConfig { check_integrity } = config;
+    let RecordIndexerConfig {
+        check_integrity,
+        max_record_len,
+    } = config;
 
-    itertools::unfold(Some(reader), move |reader_opt| {
+    itertools::unfold((Some(reader), 0), move |(reader_opt, record_index)| {
         let mut reader = reader_opt.as_mut()?;
-        let len = match crate::io::sync::try_read_len(&mut reader, check_integrity).transpose()? {
+        let index = *record_index;
+        *record_index += 1;
+
+        let len = match crate::io::sync::try_read_len_with_max_len(
+            &mut reader,
+            check_integrity,
+            max_record_len,
+        )
+        .transpose()?
+        {
             Ok(len) => len,
             Err(err) => {
+                // the header is consumed when the length is rejected
+                let err = match reader.stream_position() {
+                    Ok(pos) => {
+                        let offset = pos.saturating_sub(HEADER_SIZE as u64);
+                        err.with_offset(offset)
+                            .with_context(None, Some(index), Some(offset))
+                    }
+                    Err(_) => err.with_context(None, Some(index), None),
+                };
                 *reader_opt = None;
                 return Some(Err(err));
             }
         };
 
-        let offset = (move || {
-            let offset = reader.seek(SeekFrom::Current(0))?;
-            skip_or_check(&mut reader, len, check_integrity)?;
+        let offset = (|| -> Result<_> {
+            let offset = reader.stream_position()?;
+            skip_or_check(&mut reader, len, check_integrity).map_err(|err| {
+                err.with_context(None, None, Some(offset.saturating_sub(HEADER_SIZE as u64)))
+            })?;
             Ok(offset)
         })();
         let offset = match offset {
             Ok(offset) => offset,
             Err(err) => {
                 *reader_opt = None;
-                return Some(Err(err));
+                return Some(Err(err.with_context(None, Some(index), None)));
             }
         };
 
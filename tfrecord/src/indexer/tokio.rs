@@ -0,0 +1,138 @@
+use super::{Position, RecordIndex, RecordIndexerConfig};
+use crate::{
+    error::{Error, Result},
+    io::tokio::TokioIo,
+    record::Record,
+    utils,
+};
+use ::tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncSeek, BufReader},
+};
+use futures::stream::{self, Stream, StreamExt as _, TryStreamExt as _};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+impl RecordIndex {
+    /// Load the record data for the index using the tokio runtime.
+    pub async fn load_tokio<T>(&self) -> Result<T>
+    where
+        T: Record,
+    {
+        let Self {
+            ref path,
+            offset,
+            len,
+        } = *self;
+        let load = async {
+            let mut reader = BufReader::new(File::open(&**path).await?);
+            let bytes = super::poll::read_record_at(TokioIo(&mut reader), offset, len).await?;
+            T::decode(&bytes)
+        };
+        load.await.map_err(|error| self.locate(error))
+    }
+}
+
+/// Load record indexes from files specified by a prefix using the tokio runtime.
+pub async fn load_prefix_tokio<'a, P>(
+    prefix: P,
+    config: RecordIndexerConfig,
+) -> Result<impl Stream<Item = Result<RecordIndex>>>
+where
+    P: Into<Cow<'a, str>>,
+{
+    let (dir, file_name_prefix) = utils::split_prefix(prefix);
+
+    // filter paths
+    let mut paths = vec![];
+    let mut entries = ::tokio::fs::read_dir(&dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.metadata().await?.is_file() {
+            continue;
+        }
+        let file_name = PathBuf::from(entry.file_name());
+        if file_name.starts_with(&file_name_prefix) {
+            paths.push(entry.path());
+        }
+    }
+
+    // sort paths
+    paths.sort();
+
+    // construct dataset
+    let stream = load_paths_tokio(paths, config);
+    Ok(stream)
+}
+
+/// Load record indexes from files specified by a glob pattern or shard spec using the tokio
+/// runtime.
+///
+/// See [discovery](crate::discovery) for the accepted patterns.
+pub async fn load_pattern_tokio(
+    pattern: &str,
+    config: RecordIndexerConfig,
+) -> Result<impl Stream<Item = Result<RecordIndex>>> {
+    let pattern = pattern.to_owned();
+    let paths = ::tokio::task::spawn_blocking(move || crate::discovery::find_files(&pattern))
+        .await
+        .map_err(std::io::Error::from)??;
+    Ok(load_paths_tokio(paths, config))
+}
+
+/// Load record indexes from file paths using the tokio runtime.
+pub fn load_paths_tokio<'a, P, I>(
+    paths: I,
+    config: RecordIndexerConfig,
+) -> impl Stream<Item = Result<RecordIndex>>
+where
+    I: IntoIterator<Item = P>,
+    P: Into<Cow<'a, Path>>,
+{
+    stream::iter(paths)
+        .map(|path| path.into().into_owned())
+        .then(move |path| load_file_tokio(path, config.clone()))
+        .try_flatten()
+}
+
+/// Load record indexes from a file using the tokio runtime.
+pub async fn load_file_tokio<'a, P>(
+    file: P,
+    config: RecordIndexerConfig,
+) -> Result<impl Stream<Item = Result<RecordIndex>>>
+where
+    P: Into<Cow<'a, Path>>,
+{
+    let file = file.into().into_owned();
+    let file_handle = File::open(&file)
+        .await
+        .map_err(|error| Error::from(error).with_context(Some(&file), None, None))?;
+    let reader = BufReader::new(file_handle);
+
+    let file = Arc::new(file);
+    let stream = load_reader_tokio(reader, config).map(move |pos| {
+        let Position { offset, len } =
+            pos.map_err(|error| error.with_context(Some(&file), None, None))?;
+        Ok(RecordIndex {
+            path: file.clone(),
+            offset,
+            len,
+        })
+    });
+    Ok(stream)
+}
+
+/// Load record indexes from a reader with tokio's [AsyncRead] trait.
+///
+/// The errors are attached with the [context](Error::context) locating the record.
+pub fn load_reader_tokio<R>(
+    reader: R,
+    config: RecordIndexerConfig,
+) -> impl Stream<Item = Result<Position>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    super::poll::load_reader(TokioIo(reader), config)
+}
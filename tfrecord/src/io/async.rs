@@ -1,53 +1,74 @@
-use std::mem;
-
-use crate::error::{Error, Result};
-use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+pub use super::ReadState;
+use super::{
+    poll::{self, PollRead, PollSeek, PollWrite},
+    CorruptedRange, RecoveryBuffer,
+};
+use crate::error::Result;
+use futures::{
+    future,
+    io::{AsyncRead, AsyncSeek, AsyncWrite},
+};
+use std::{
+    io::{self, ErrorKind, IoSlice, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 /// Try to extract raw bytes of a record from a generic reader.
 ///
 /// It reads the record length and data from a generic reader,
 /// and verifies the checksum if requested.
+/// Records longer than [DEFAULT_MAX_RECORD_LEN](super::DEFAULT_MAX_RECORD_LEN) are rejected
+/// before allocation.
 /// If the end of file is reached, it returns `Ok(None)`.
 pub async fn try_read_record<R>(reader: &mut R, check_integrity: bool) -> Result<Option<Vec<u8>>>
 where
     R: AsyncRead + Unpin,
 {
-    let len = match try_read_len(reader, check_integrity).await? {
-        Some(len) => len,
-        None => return Ok(None),
-    };
-    let data = try_read_record_data(reader, len, check_integrity).await?;
-    Ok(Some(data))
+    try_read_record_with_max_len(reader, check_integrity, Some(super::DEFAULT_MAX_RECORD_LEN)).await
+}
+
+/// Try to extract raw bytes of a record from a generic reader with a custom length limit.
+///
+/// If `max_len` is set, records longer than the limit are rejected before allocation.
+/// It is not bounded if `max_len` is `None`.
+pub async fn try_read_record_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+) -> Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    poll::try_read_record_with_max_len(&mut FuturesIo(reader), check_integrity, max_len).await
 }
 
 /// Try to read the record length from a generic reader.
 ///
 /// It is internally called by [try_read_record]. It returns `Ok(None)` if reaching the end of file.
+/// If the length exceeds [DEFAULT_MAX_RECORD_LEN](super::DEFAULT_MAX_RECORD_LEN), it returns
+/// [Error::RecordTooLarge](crate::Error::RecordTooLarge).
 pub async fn try_read_len<R>(reader: &mut R, check_integrity: bool) -> Result<Option<usize>>
 where
     R: AsyncRead + Unpin,
 {
-    let len_buf = {
-        let len_buf = [0u8; mem::size_of::<u64>()];
-        let len_buf = try_read_exact(reader, len_buf).await?;
-        match len_buf {
-            Some(buf) => buf,
-            None => return Ok(None),
-        }
-    };
-    let len = u64::from_le_bytes(len_buf);
-
-    let expect_cksum = {
-        let mut buf = [0; mem::size_of::<u32>()];
-        reader.read_exact(&mut buf).await?;
-        u32::from_le_bytes(buf)
-    };
-
-    if check_integrity {
-        crate::utils::verify_checksum(&len_buf, expect_cksum)?;
-    }
+    try_read_len_with_max_len(reader, check_integrity, Some(super::DEFAULT_MAX_RECORD_LEN)).await
+}
 
-    Ok(Some(len as usize))
+/// Try to read the record length from a generic reader with a custom length limit.
+///
+/// If `max_len` is set and the length exceeds the limit, it returns
+/// [Error::RecordTooLarge](crate::Error::RecordTooLarge).
+/// It is not bounded if `max_len` is `None`.
+pub async fn try_read_len_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+) -> Result<Option<usize>>
+where
+    R: AsyncRead + Unpin,
+{
+    poll::try_read_len_with_max_len(&mut FuturesIo(reader), check_integrity, max_len, None).await
 }
 
 /// Read the record raw bytes with given length from a generic reader.
@@ -61,77 +82,187 @@ pub async fn try_read_record_data<R>(
 where
     R: AsyncRead + Unpin,
 {
-    let buf = {
-        let mut buf = vec![0u8; len];
-        reader.read_exact(&mut buf).await?;
-        buf
-    };
-    let expect_cksum = {
-        let mut buf = [0u8; mem::size_of::<u32>()];
-        reader.read_exact(&mut buf).await?;
-        u32::from_le_bytes(buf)
-    };
-
-    if check_integrity {
-        crate::utils::verify_checksum(&buf, expect_cksum)?;
-    }
+    let mut buf = vec![];
+    try_read_record_data_into(reader, len, check_integrity, &mut buf).await?;
     Ok(buf)
 }
 
+/// Read the record raw bytes with given length into a buffer.
+///
+/// The previous content of the buffer is discarded, while its allocation is reused.
+pub async fn try_read_record_data_into<R>(
+    reader: &mut R,
+    len: usize,
+    check_integrity: bool,
+    buf: &mut Vec<u8>,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    poll::try_read_record_data_into(&mut FuturesIo(reader), len, check_integrity, buf).await
+}
+
+/// Poll to read the next record into a buffer from a generic reader.
+///
+/// The partial progress is kept in `state`, so the call can be resumed after
+/// it returns [Poll::Pending]. The same buffer must be given until the call
+/// completes. It returns the offset of the record, or `Ok(None)` if the end of
+/// file is reached. If `max_len` is set, records longer than the limit are
+/// rejected before allocation.
+pub fn poll_read_record_into<R>(
+    reader: &mut R,
+    cx: &mut Context<'_>,
+    state: &mut ReadState,
+    check_integrity: bool,
+    max_len: Option<usize>,
+    buf: &mut Vec<u8>,
+) -> Poll<Result<Option<u64>>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    state.poll_read_record(cx, check_integrity, max_len, buf, &mut FuturesIo(reader))
+}
+
 /// Write the raw record bytes to a generic writer.
 pub async fn try_write_record<W>(writer: &mut W, bytes: Vec<u8>) -> Result<()>
 where
     W: AsyncWrite + Unpin,
 {
-    // write data size
-    {
-        let len = bytes.len();
-        let len_buf = len.to_le_bytes();
-        let cksum = crate::utils::checksum(&len_buf);
-        let cksum_buf = cksum.to_le_bytes();
+    try_write_record_slice(writer, &bytes).await
+}
+
+/// Write the borrowed record bytes to a generic writer.
+///
+/// The header, data and footer are written together with vectored writes.
+pub async fn try_write_record_slice<W>(writer: &mut W, bytes: &[u8]) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    poll::try_write_record_slice(&mut FuturesIo(writer), bytes).await
+}
 
-        writer.write_all(&len_buf).await?;
-        writer.write_all(&cksum_buf).await?;
+/// A reader that skips corrupted bytes and resynchronizes to the next valid record.
+///
+/// The checksums are always verified. When the length or data checksum mismatches,
+/// it scans forward byte by byte for the next position where a record with valid
+/// checksums starts.
+#[derive(Debug)]
+pub struct RecoveryReader<R> {
+    reader: R,
+    buffer: RecoveryBuffer,
+}
+
+impl<R> RecoveryReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Wrap a generic reader.
+    ///
+    /// If `max_len` is set, records longer than the limit are treated as corrupted.
+    pub fn new(reader: R, max_len: Option<usize>) -> Self {
+        Self {
+            reader,
+            buffer: RecoveryBuffer::new(max_len),
+        }
     }
 
-    // write data
+    /// Get the stream offset of the next unconsumed byte.
+    pub fn offset(&self) -> u64 {
+        self.buffer.offset()
+    }
+
+    /// Try to extract raw bytes of the next valid record.
+    ///
+    /// The `on_corrupted` callback is called with every skipped byte range.
+    /// If the end of file is reached, it returns `Ok(None)`.
+    pub async fn try_read_record<F>(&mut self, on_corrupted: F) -> Result<Option<Vec<u8>>>
+    where
+        F: FnMut(CorruptedRange),
     {
-        let cksum = crate::utils::checksum(&bytes);
-        let cksum_buf = cksum.to_le_bytes();
+        let mut buf = vec![];
+        let offset = self.try_read_record_into(&mut buf, on_corrupted).await?;
+        Ok(offset.map(|_| buf))
+    }
 
-        writer.write_all(bytes.as_slice()).await?;
-        writer.write_all(&cksum_buf).await?;
+    /// Try to read raw bytes of the next valid record into a buffer.
+    ///
+    /// It returns the offset of the record if found. The previous content of the buffer
+    /// is discarded, while its allocation is reused.
+    pub async fn try_read_record_into<F>(
+        &mut self,
+        buf: &mut Vec<u8>,
+        mut on_corrupted: F,
+    ) -> Result<Option<u64>>
+    where
+        F: FnMut(CorruptedRange),
+    {
+        future::poll_fn(|cx| self.poll_read_record_into(cx, buf, &mut on_corrupted)).await
+    }
+
+    /// Poll to read raw bytes of the next valid record into a buffer.
+    ///
+    /// It is the poll-based counterpart of [try_read_record_into](RecoveryReader::try_read_record_into).
+    /// The progress is kept in the reader, so the call can be resumed after it returns
+    /// [Poll::Pending].
+    pub fn poll_read_record_into<F>(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<u8>,
+        on_corrupted: F,
+    ) -> Poll<Result<Option<u64>>>
+    where
+        F: FnMut(CorruptedRange),
+    {
+        let Self { reader, buffer } = self;
+        buffer.poll_read_record(cx, buf, on_corrupted, &mut FuturesIo(reader))
     }
-    Ok(())
 }
 
-async fn try_read_exact<R, B>(reader: &mut R, mut buf: B) -> Result<Option<B>>
+/// Adapter of a reader or writer with the futures I/O traits to the [poll-based I/O](super::poll).
+#[derive(Debug)]
+pub(crate) struct FuturesIo<T>(pub(crate) T);
+
+impl<T> PollRead for FuturesIo<T>
 where
-    R: AsyncRead + Unpin,
-    B: AsMut<[u8]>,
+    T: AsyncRead + Unpin,
 {
-    let as_mut = buf.as_mut();
-    let mut offset = 0;
-    let len = as_mut.len();
-
-    loop {
-        match reader.read(&mut as_mut[offset..]).await {
-            Ok(0) => {
-                if offset == len {
-                    return Ok(Some(buf));
-                } else if offset == 0 {
-                    return Ok(None);
-                } else {
-                    return Err(Error::UnexpectedEof);
-                }
+    fn poll_read_bytes(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        loop {
+            match Pin::new(&mut self.0).poll_read(cx, buf) {
+                Poll::Ready(Err(error)) if error.kind() == ErrorKind::Interrupted => {}
+                poll => return poll,
             }
-            Ok(n) => {
-                offset += n;
-                if offset == len {
-                    return Ok(Some(buf));
-                }
-            }
-            Err(error) => return Err(error.into()),
         }
     }
 }
+
+impl<T> PollSeek for FuturesIo<T>
+where
+    T: AsyncSeek + Unpin,
+{
+    fn poll_seek_to(
+        &mut self,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+        _started: &mut bool,
+    ) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.0).poll_seek(cx, pos)
+    }
+}
+
+impl<T> PollWrite for FuturesIo<T>
+where
+    T: AsyncWrite + Unpin,
+{
+    fn poll_write_slices(
+        &mut self,
+        cx: &mut Context<'_>,
+        slices: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write_vectored(cx, slices)
+    }
+
+    fn poll_flush_bytes(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+}
@@ -3,6 +3,510 @@
 //! The functions are used internally to work with generic readers and writers.
 //! It is not intended for common users, while we encourage using high level API.
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 pub mod r#async;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+pub(crate) mod poll;
 pub mod sync;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+
+use crate::error::{ChecksumKind, Error, Result};
+
+/// The kind of corruption found in a record stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CorruptionKind {
+    /// The checksum of the record length does not match.
+    LengthChecksum,
+    /// The checksum of the record data does not match.
+    DataChecksum,
+    /// The stream ends in the middle of a record.
+    Truncated,
+    /// The record length exceeds the configured limit.
+    TooLarge,
+}
+
+/// A range of bytes skipped due to corruption.
+///
+/// The range starts at the first corrupted byte and ends at the start of the next
+/// valid record, or at the end of stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorruptedRange {
+    /// The offset of the first skipped byte.
+    pub start: u64,
+    /// The offset after the last skipped byte.
+    pub end: u64,
+    /// The kind of corruption at the start of the range.
+    pub kind: CorruptionKind,
+}
+
+/// The default maximum record length in bytes, which is 256 MiB.
+///
+/// It guards against huge allocations from corrupted record headers. Readers of larger
+/// records raise the limit in their configuration.
+pub const DEFAULT_MAX_RECORD_LEN: usize = 256 << 20;
+
+/// The size of length field and its checksum at the beginning of a record.
+pub(crate) const HEADER_SIZE: usize = std::mem::size_of::<u64>() + std::mem::size_of::<u32>();
+
+/// The size of the data checksum at the end of a record.
+pub(crate) const FOOTER_SIZE: usize = std::mem::size_of::<u32>();
+
+/// The size of a framed record with the given data length.
+pub(crate) fn record_size(len: usize) -> u64 {
+    (HEADER_SIZE + len + FOOTER_SIZE) as u64
+}
+
+/// Build the record header, which is the data length followed by its checksum.
+pub(crate) fn record_header(len: usize) -> [u8; HEADER_SIZE] {
+    let len_buf = (len as u64).to_le_bytes();
+    let cksum_buf = crate::utils::checksum(&len_buf).to_le_bytes();
+    let mut header = [0; HEADER_SIZE];
+    header[..len_buf.len()].copy_from_slice(&len_buf);
+    header[len_buf.len()..].copy_from_slice(&cksum_buf);
+    header
+}
+
+/// Build the record footer, which is the checksum of the data.
+pub(crate) fn record_footer(data: &[u8]) -> [u8; FOOTER_SIZE] {
+    crate::utils::checksum(data).to_le_bytes()
+}
+
+/// Check the record length against the optional limit.
+///
+/// The `offset` of the record header, if known, is attached to the error.
+pub(crate) fn check_len(len: u64, max_len: Option<usize>, offset: Option<u64>) -> Result<usize> {
+    match usize::try_from(len) {
+        Ok(len) if len <= max_len.unwrap_or(usize::MAX) => Ok(len),
+        _ => Err(Error::RecordTooLarge {
+            len,
+            max_len: max_len.unwrap_or(usize::MAX),
+            offset,
+        }),
+    }
+}
+
+/// The resumable progress of reading records by `poll_read_record_into`.
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+#[derive(Debug, Clone, Default)]
+pub struct ReadState {
+    /// The stream offset of the record being read.
+    offset: u64,
+    header: [u8; HEADER_SIZE],
+    footer: [u8; FOOTER_SIZE],
+    /// The number of bytes of the current record read so far.
+    filled: usize,
+    /// The data length after the header is read.
+    len: Option<usize>,
+}
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+impl ReadState {
+    /// Create the state of reading records starting at the stream offset.
+    pub(crate) fn at(offset: u64) -> Self {
+        Self {
+            offset,
+            ..Default::default()
+        }
+    }
+
+    /// Get the stream offset of the next record.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Discard the partially read record and move the offset past it.
+    fn finish_record(&mut self, len: usize) {
+        self.offset += record_size(len);
+        self.filled = 0;
+        self.len = None;
+    }
+
+    /// Poll to read the next record into a buffer from `reader`.
+    ///
+    /// It is shared by the asynchronous readers of all runtimes.
+    pub(crate) fn poll_read_record<R>(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        check_integrity: bool,
+        max_len: Option<usize>,
+        buf: &mut Vec<u8>,
+        reader: &mut R,
+    ) -> std::task::Poll<Result<Option<u64>>>
+    where
+        R: poll::PollRead + ?Sized,
+    {
+        use std::task::{ready, Poll};
+
+        let len = match self.len {
+            Some(len) => len,
+            None => {
+                while self.filled < HEADER_SIZE {
+                    let n = ready!(reader.poll_read_bytes(cx, &mut self.header[self.filled..]))?;
+                    if n == 0 {
+                        let filled = std::mem::take(&mut self.filled);
+                        return Poll::Ready(if filled == 0 {
+                            Ok(None)
+                        } else {
+                            Err(Error::UnexpectedEof)
+                        });
+                    }
+                    self.filled += n;
+                }
+                // the header is consumed even if it is rejected
+                self.filled = 0;
+
+                let (len_buf, cksum_buf) = self.header.split_at(std::mem::size_of::<u64>());
+                if check_integrity {
+                    let expect = u32::from_le_bytes(cksum_buf.try_into().unwrap());
+                    crate::utils::verify_checksum(len_buf, expect, ChecksumKind::Length)?;
+                }
+                let len = u64::from_le_bytes(len_buf.try_into().unwrap());
+                let len = check_len(len, max_len, Some(self.offset))?;
+
+                buf.clear();
+                buf.resize(len, 0);
+                self.filled = HEADER_SIZE;
+                self.len = Some(len);
+                len
+            }
+        };
+
+        // the record is consumed even if it is truncated or the checksum mismatches
+        let data_end = HEADER_SIZE + len;
+        while self.filled < data_end + FOOTER_SIZE {
+            let dst = if self.filled < data_end {
+                &mut buf[(self.filled - HEADER_SIZE)..]
+            } else {
+                &mut self.footer[(self.filled - data_end)..]
+            };
+            let n = match ready!(reader.poll_read_bytes(cx, dst)) {
+                Ok(n) => n,
+                Err(error) => {
+                    self.finish_record(len);
+                    return Poll::Ready(Err(error.into()));
+                }
+            };
+            if n == 0 {
+                self.finish_record(len);
+                return Poll::Ready(Err(
+                    std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()
+                ));
+            }
+            self.filled += n;
+        }
+
+        let offset = self.offset;
+        self.finish_record(len);
+        if check_integrity {
+            let expect = u32::from_le_bytes(self.footer);
+            crate::utils::verify_checksum(buf, expect, ChecksumKind::Data)?;
+        }
+        Poll::Ready(Ok(Some(offset)))
+    }
+}
+
+/// Try to parse a record header, returning the data length if the length checksum matches.
+fn parse_header(header: &[u8]) -> Option<u64> {
+    let (len_buf, cksum_buf) = header[..HEADER_SIZE].split_at(std::mem::size_of::<u64>());
+    let expect = u32::from_le_bytes(cksum_buf.try_into().unwrap());
+    crate::utils::verify_checksum(len_buf, expect, ChecksumKind::Length).ok()?;
+    let len = u64::from_le_bytes(len_buf.try_into().unwrap());
+    Some(len)
+}
+
+/// Check the data checksum of a record with the footer following the data.
+fn verify_data(data_and_footer: &[u8]) -> bool {
+    let (data, cksum_buf) = data_and_footer.split_at(data_and_footer.len() - FOOTER_SIZE);
+    let expect = u32::from_le_bytes(cksum_buf.try_into().unwrap());
+    crate::utils::verify_checksum(data, expect, ChecksumKind::Data).is_ok()
+}
+
+/// The minimum number of bytes requested from the reader in recovery mode.
+const RECOVERY_READ_SIZE: usize = 8192;
+
+/// The outcome of scanning buffered bytes at the current position.
+enum Scan {
+    /// At least this number of bytes is needed to make a decision.
+    NeedMore(usize),
+    /// A valid record with the data length is found.
+    Record(usize),
+    /// The current position is corrupted.
+    Corrupted(CorruptionKind),
+}
+
+/// Buffer and bookkeeping shared by sync and async recovery readers.
+#[derive(Debug, Default)]
+pub(crate) struct RecoveryBuffer {
+    buf: Vec<u8>,
+    /// The position of the first unconsumed byte in `buf`.
+    pos: usize,
+    /// The stream offset of the first unconsumed byte.
+    offset: u64,
+    /// The start and kind of the corrupted range being skipped.
+    pending: Option<(u64, CorruptionKind)>,
+    /// The maximum accepted record length.
+    max_len: Option<usize>,
+}
+
+impl RecoveryBuffer {
+    /// Create the buffer treating records longer than `max_len` as corrupted.
+    pub(crate) fn new(max_len: Option<usize>) -> Self {
+        Self {
+            max_len,
+            ..Default::default()
+        }
+    }
+
+    /// Get the stream offset of the next unconsumed byte.
+    pub(crate) fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn remaining(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    fn scan(&self) -> Scan {
+        let buf = self.remaining();
+        if buf.len() < HEADER_SIZE {
+            return Scan::NeedMore(HEADER_SIZE);
+        }
+        let Some(len) = parse_header(buf) else {
+            return Scan::Corrupted(CorruptionKind::LengthChecksum);
+        };
+        let Ok(len) = check_len(len, self.max_len, None) else {
+            return Scan::Corrupted(CorruptionKind::TooLarge);
+        };
+        let Some(total) = len.checked_add(HEADER_SIZE + FOOTER_SIZE) else {
+            return Scan::Corrupted(CorruptionKind::LengthChecksum);
+        };
+        if buf.len() < total {
+            return Scan::NeedMore(total);
+        }
+        if verify_data(&buf[HEADER_SIZE..total]) {
+            Scan::Record(len)
+        } else {
+            Scan::Corrupted(CorruptionKind::DataChecksum)
+        }
+    }
+
+    /// Drop consumed bytes and return the writable region for at least `min_len` unconsumed bytes.
+    fn prepare_fill(&mut self, min_len: usize) -> usize {
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        let filled = self.buf.len();
+        self.buf
+            .resize(filled.max(min_len).max(RECOVERY_READ_SIZE), 0);
+        filled
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos += amount;
+        self.offset += amount as u64;
+    }
+
+    fn mark_corrupted(&mut self, kind: CorruptionKind) {
+        if self.pending.is_none() {
+            self.pending = Some((self.offset, kind));
+        }
+        self.consume(1);
+    }
+
+    /// Copy the valid record at the current position into `buf`, returning the record offset
+    /// and the range skipped before it.
+    fn take_record(&mut self, len: usize, buf: &mut Vec<u8>) -> (u64, Option<CorruptedRange>) {
+        buf.clear();
+        buf.extend_from_slice(&self.remaining()[HEADER_SIZE..(HEADER_SIZE + len)]);
+        let offset = self.offset;
+        let skipped = self.pending.take().map(|(start, kind)| CorruptedRange {
+            start,
+            end: offset,
+            kind,
+        });
+        self.consume(HEADER_SIZE + len + FOOTER_SIZE);
+        (offset, skipped)
+    }
+
+    /// Poll to buffer at least `min_len` unconsumed bytes from `reader`.
+    ///
+    /// It returns `false` if the end of stream is reached before that.
+    #[cfg(any(feature = "futures-io", feature = "tokio"))]
+    fn poll_fill<R>(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        min_len: usize,
+        reader: &mut R,
+    ) -> std::task::Poll<Result<bool>>
+    where
+        R: poll::PollRead + ?Sized,
+    {
+        use std::task::Poll;
+
+        loop {
+            let filled = self.prepare_fill(min_len);
+            if filled >= min_len {
+                self.buf.truncate(filled);
+                return Poll::Ready(Ok(true));
+            }
+            match reader.poll_read_bytes(cx, &mut self.buf[filled..]) {
+                Poll::Ready(Ok(0)) => {
+                    self.buf.truncate(filled);
+                    return Poll::Ready(Ok(false));
+                }
+                Poll::Ready(Ok(n)) => self.buf.truncate(filled + n),
+                Poll::Ready(Err(error)) => {
+                    self.buf.truncate(filled);
+                    return Poll::Ready(Err(error.into()));
+                }
+                Poll::Pending => {
+                    self.buf.truncate(filled);
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    /// Poll to read the next valid record into `buf` from `reader`.
+    ///
+    /// It is shared by the asynchronous recovery readers of all runtimes.
+    #[cfg(any(feature = "futures-io", feature = "tokio"))]
+    pub(crate) fn poll_read_record<R, C>(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut Vec<u8>,
+        mut on_corrupted: C,
+        reader: &mut R,
+    ) -> std::task::Poll<Result<Option<u64>>>
+    where
+        R: poll::PollRead + ?Sized,
+        C: FnMut(CorruptedRange),
+    {
+        use std::task::{ready, Poll};
+
+        loop {
+            match self.scan() {
+                Scan::NeedMore(len) => {
+                    if !ready!(self.poll_fill(cx, len, reader))? {
+                        if let Some(range) = self.take_eof() {
+                            on_corrupted(range);
+                        }
+                        return Poll::Ready(Ok(None));
+                    }
+                }
+                Scan::Record(len) => {
+                    let (offset, skipped) = self.take_record(len, buf);
+                    if let Some(range) = skipped {
+                        on_corrupted(range);
+                    }
+                    return Poll::Ready(Ok(Some(offset)));
+                }
+                Scan::Corrupted(kind) => self.mark_corrupted(kind),
+            }
+        }
+    }
+
+    /// Consume the remaining bytes at the end of stream, returning the range skipped if any.
+    fn take_eof(&mut self) -> Option<CorruptedRange> {
+        let remaining = self.remaining().len();
+        let (start, kind) = match self.pending.take() {
+            Some(pending) => pending,
+            None if remaining > 0 => (self.offset, CorruptionKind::Truncated),
+            None => return None,
+        };
+        self.consume(remaining);
+        Some(CorruptedRange {
+            start,
+            end: self.offset,
+            kind,
+        })
+    }
+}
+
+/// Buffer of a file tail which may end with an incomplete record.
+///
+/// It is shared by sync and async readers following files that are still being written.
+#[derive(Debug)]
+pub(crate) struct TailBuffer {
+    buf: Vec<u8>,
+    /// The stream offset of the first buffered byte.
+    offset: u64,
+    /// The number of bytes needed to complete the next record.
+    needed: usize,
+    check_integrity: bool,
+    max_len: Option<usize>,
+}
+
+impl TailBuffer {
+    pub(crate) fn new(check_integrity: bool, max_len: Option<usize>) -> Self {
+        Self {
+            buf: vec![],
+            offset: 0,
+            needed: HEADER_SIZE,
+            check_integrity,
+            max_len,
+        }
+    }
+
+    /// Check if there are buffered bytes of an incomplete record.
+    pub(crate) fn has_partial(&self) -> bool {
+        !self.buf.is_empty()
+    }
+
+    /// Get the stream offset of the next record.
+    pub(crate) fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Take the next record into a buffer if it is completely buffered.
+    ///
+    /// It returns the offset of the record. The previous content of the buffer is discarded,
+    /// while its allocation is reused.
+    pub(crate) fn take_record_into(&mut self, buf: &mut Vec<u8>) -> Result<Option<u64>> {
+        if self.buf.len() < HEADER_SIZE {
+            self.needed = HEADER_SIZE;
+            return Ok(None);
+        }
+
+        let (len_buf, cksum_buf) = self.buf[..HEADER_SIZE].split_at(std::mem::size_of::<u64>());
+        if self.check_integrity {
+            let expect = u32::from_le_bytes(cksum_buf.try_into().unwrap());
+            crate::utils::verify_checksum(len_buf, expect, ChecksumKind::Length)?;
+        }
+        let len = u64::from_le_bytes(len_buf.try_into().unwrap());
+        let len = check_len(len, self.max_len, Some(self.offset))?;
+
+        let total = HEADER_SIZE + len + FOOTER_SIZE;
+        if self.buf.len() < total {
+            self.needed = total;
+            return Ok(None);
+        }
+        if self.check_integrity {
+            let data_and_footer = &self.buf[HEADER_SIZE..total];
+            let (data, cksum_buf) = data_and_footer.split_at(len);
+            let expect = u32::from_le_bytes(cksum_buf.try_into().unwrap());
+            crate::utils::verify_checksum(data, expect, ChecksumKind::Data)?;
+        }
+
+        buf.clear();
+        buf.extend_from_slice(&self.buf[HEADER_SIZE..(HEADER_SIZE + len)]);
+        self.buf.drain(..total);
+        let offset = self.offset;
+        self.offset += total as u64;
+        Ok(Some(offset))
+    }
+
+    /// Grow the buffer for the next read, returning the number of filled bytes
+    /// and the writable region.
+    pub(crate) fn prepare_fill(&mut self) -> (usize, &mut [u8]) {
+        let filled = self.buf.len();
+        self.buf
+            .resize(self.needed.max(filled + RECOVERY_READ_SIZE), 0);
+        (filled, &mut self.buf[filled..])
+    }
+
+    /// Shrink the buffer to the filled bytes after a read.
+    pub(crate) fn finish_fill(&mut self, filled: usize) {
+        self.buf.truncate(filled);
+    }
+}
@@ -0,0 +1,250 @@
+//! Poll-based I/O shared by the asynchronous runtimes.
+//!
+//! The readers and writers of each runtime are adapted to the traits here, so that
+//! the record framing is implemented once for futures-io and tokio.
+
+use crate::error::{ChecksumKind, Error, Result};
+use futures::future;
+use std::{
+    io::{self, ErrorKind, IoSlice, SeekFrom},
+    mem,
+    task::{Context, Poll},
+};
+
+/// A reader polled for bytes.
+pub(crate) trait PollRead {
+    /// Poll to read into a byte slice, retrying on interruption.
+    ///
+    /// It returns the number of bytes read, which is zero at the end of file.
+    fn poll_read_bytes(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>>;
+}
+
+/// A seekable stream polled for seeking.
+pub(crate) trait PollSeek {
+    /// Poll to seek to the position and return the new offset.
+    ///
+    /// `started` is initially `false` and is kept by the caller across polls of the same
+    /// seek, for runtimes that start the seek before polling for its completion.
+    fn poll_seek_to(
+        &mut self,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+        started: &mut bool,
+    ) -> Poll<io::Result<u64>>;
+}
+
+/// A writer polled for writing and flushing.
+pub(crate) trait PollWrite {
+    /// Poll to write from the slices and return the number of bytes written.
+    fn poll_write_slices(
+        &mut self,
+        cx: &mut Context<'_>,
+        slices: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>>;
+
+    /// Poll to flush the buffered bytes.
+    fn poll_flush_bytes(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+}
+
+impl<T> PollRead for &mut T
+where
+    T: PollRead + ?Sized,
+{
+    fn poll_read_bytes(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        (**self).poll_read_bytes(cx, buf)
+    }
+}
+
+impl<T> PollSeek for &mut T
+where
+    T: PollSeek + ?Sized,
+{
+    fn poll_seek_to(
+        &mut self,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+        started: &mut bool,
+    ) -> Poll<io::Result<u64>> {
+        (**self).poll_seek_to(cx, pos, started)
+    }
+}
+
+/// Read into a byte slice, returning zero at the end of file.
+pub(crate) async fn read<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize>
+where
+    R: PollRead + ?Sized,
+{
+    future::poll_fn(|cx| reader.poll_read_bytes(cx, buf)).await
+}
+
+/// Fill the byte slice, failing if the end of file is reached before that.
+pub(crate) async fn read_exact<R>(reader: &mut R, mut buf: &mut [u8]) -> io::Result<()>
+where
+    R: PollRead + ?Sized,
+{
+    while !buf.is_empty() {
+        match read(reader, buf).await? {
+            0 => return Err(ErrorKind::UnexpectedEof.into()),
+            n => buf = &mut buf[n..],
+        }
+    }
+    Ok(())
+}
+
+/// Seek to the position and return the new offset.
+pub(crate) async fn seek<S>(stream: &mut S, pos: SeekFrom) -> io::Result<u64>
+where
+    S: PollSeek + ?Sized,
+{
+    let mut started = false;
+    future::poll_fn(|cx| stream.poll_seek_to(cx, pos, &mut started)).await
+}
+
+/// Flush the buffered bytes of a writer.
+pub(crate) async fn flush<W>(writer: &mut W) -> io::Result<()>
+where
+    W: PollWrite + ?Sized,
+{
+    future::poll_fn(|cx| writer.poll_flush_bytes(cx)).await
+}
+
+/// Try to extract raw bytes of a record with an optional length limit.
+pub(crate) async fn try_read_record_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+) -> Result<Option<Vec<u8>>>
+where
+    R: PollRead + ?Sized,
+{
+    let len = match try_read_len_with_max_len(reader, check_integrity, max_len, None).await? {
+        Some(len) => len,
+        None => return Ok(None),
+    };
+    let mut data = vec![];
+    try_read_record_data_into(reader, len, check_integrity, &mut data).await?;
+    Ok(Some(data))
+}
+
+/// Try to read the record length with an optional length limit.
+///
+/// It returns `Ok(None)` if reaching the end of file. The `offset` of the record header, if
+/// known, is attached to [Error::RecordTooLarge].
+pub(crate) async fn try_read_len_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+    offset: Option<u64>,
+) -> Result<Option<usize>>
+where
+    R: PollRead + ?Sized,
+{
+    let len_buf = {
+        let len_buf = [0u8; mem::size_of::<u64>()];
+        let len_buf = try_read_exact(reader, len_buf).await?;
+        match len_buf {
+            Some(buf) => buf,
+            None => return Ok(None),
+        }
+    };
+    let len = u64::from_le_bytes(len_buf);
+
+    let expect_cksum = {
+        let mut buf = [0; mem::size_of::<u32>()];
+        read_exact(reader, &mut buf).await?;
+        u32::from_le_bytes(buf)
+    };
+
+    if check_integrity {
+        crate::utils::verify_checksum(&len_buf, expect_cksum, ChecksumKind::Length)?;
+    }
+
+    let len = super::check_len(len, max_len, offset)?;
+    Ok(Some(len))
+}
+
+/// Read the record raw bytes with given length into a buffer.
+///
+/// The previous content of the buffer is discarded, while its allocation is reused.
+pub(crate) async fn try_read_record_data_into<R>(
+    reader: &mut R,
+    len: usize,
+    check_integrity: bool,
+    buf: &mut Vec<u8>,
+) -> Result<()>
+where
+    R: PollRead + ?Sized,
+{
+    buf.clear();
+    buf.resize(len, 0);
+    read_exact(reader, buf).await?;
+
+    let expect_cksum = {
+        let mut buf = [0u8; mem::size_of::<u32>()];
+        read_exact(reader, &mut buf).await?;
+        u32::from_le_bytes(buf)
+    };
+
+    if check_integrity {
+        crate::utils::verify_checksum(buf, expect_cksum, ChecksumKind::Data)?;
+    }
+    Ok(())
+}
+
+/// Write the borrowed record bytes.
+///
+/// The header, data and footer are written together with vectored writes.
+pub(crate) async fn try_write_record_slice<W>(writer: &mut W, bytes: &[u8]) -> Result<()>
+where
+    W: PollWrite + ?Sized,
+{
+    let header = super::record_header(bytes.len());
+    let footer = super::record_footer(bytes);
+    let mut slices = [
+        IoSlice::new(&header),
+        IoSlice::new(bytes),
+        IoSlice::new(&footer),
+    ];
+    let mut slices = &mut slices[..];
+
+    while !slices.is_empty() {
+        match future::poll_fn(|cx| writer.poll_write_slices(cx, slices)).await {
+            Ok(0) => return Err(io::Error::from(ErrorKind::WriteZero).into()),
+            Ok(len) => IoSlice::advance_slices(&mut slices, len),
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(())
+}
+
+async fn try_read_exact<R, B>(reader: &mut R, mut buf: B) -> Result<Option<B>>
+where
+    R: PollRead + ?Sized,
+    B: AsMut<[u8]>,
+{
+    let as_mut = buf.as_mut();
+    let mut offset = 0;
+    let len = as_mut.len();
+
+    loop {
+        match read(reader, &mut as_mut[offset..]).await {
+            Ok(0) => {
+                if offset == len {
+                    return Ok(Some(buf));
+                } else if offset == 0 {
+                    return Ok(None);
+                } else {
+                    return Err(Error::UnexpectedEof);
+                }
+            }
+            Ok(n) => {
+                offset += n;
+                if offset == len {
+                    return Ok(Some(buf));
+                }
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
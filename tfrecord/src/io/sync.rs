@@ -1,16 +1,34 @@
-use crate::error::{Error, Result};
-use std::io::prelude::*;
+use super::{CorruptedRange, RecoveryBuffer, Scan};
+use crate::error::{ChecksumKind, Error, Result};
+use std::io::{self, prelude::*, ErrorKind, IoSlice};
 
 /// Try to extract raw bytes of a record from a generic reader.
 ///
 /// It reads the record length and data from a generic reader,
 /// and verifies the checksum if requested.
+/// Records longer than [DEFAULT_MAX_RECORD_LEN](super::DEFAULT_MAX_RECORD_LEN) are rejected
+/// before allocation.
 /// If the end of file is reached, it returns `Ok(None)`.
 pub fn try_read_record<R>(reader: &mut R, check_integrity: bool) -> Result<Option<Vec<u8>>>
 where
     R: Read,
 {
-    let len = match try_read_len(reader, check_integrity)? {
+    try_read_record_with_max_len(reader, check_integrity, Some(super::DEFAULT_MAX_RECORD_LEN))
+}
+
+/// Try to extract raw bytes of a record from a generic reader with a custom length limit.
+///
+/// If `max_len` is set, records longer than the limit are rejected before allocation.
+/// It is not bounded if `max_len` is `None`.
+pub fn try_read_record_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+) -> Result<Option<Vec<u8>>>
+where
+    R: Read,
+{
+    let len = match try_read_len_with_max_len(reader, check_integrity, max_len)? {
         Some(len) => len,
         None => return Ok(None),
     };
@@ -21,7 +39,39 @@ where
 /// Try to read the record length from a generic reader.
 ///
 /// It is internally called by [try_read_record]. It returns `Ok(None)` if reaching the end of file.
+/// If the length exceeds [DEFAULT_MAX_RECORD_LEN](super::DEFAULT_MAX_RECORD_LEN), it returns
+/// [Error::RecordTooLarge].
 pub fn try_read_len<R>(reader: &mut R, check_integrity: bool) -> Result<Option<usize>>
+where
+    R: Read,
+{
+    try_read_len_with_max_len(reader, check_integrity, Some(super::DEFAULT_MAX_RECORD_LEN))
+}
+
+/// Try to read the record length from a generic reader with a custom length limit.
+///
+/// If `max_len` is set and the length exceeds the limit, it returns [Error::RecordTooLarge].
+/// It is not bounded if `max_len` is `None`.
+pub fn try_read_len_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+) -> Result<Option<usize>>
+where
+    R: Read,
+{
+    try_read_len_at(reader, check_integrity, max_len, None)
+}
+
+/// Try to read the length of the record whose header is at the stream offset.
+///
+/// The offset, if known, is attached to [Error::RecordTooLarge].
+pub(crate) fn try_read_len_at<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+    offset: Option<u64>,
+) -> Result<Option<usize>>
 where
     R: Read,
 {
@@ -41,10 +91,11 @@ where
     };
 
     if check_integrity {
-        crate::utils::verify_checksum(&len_buf, expect_cksum)?;
+        crate::utils::verify_checksum(&len_buf, expect_cksum, ChecksumKind::Length)?;
     }
 
-    Ok(Some(len as usize))
+    let len = super::check_len(len, max_len, offset)?;
+    Ok(Some(len))
 }
 
 /// Read the record raw bytes with given length from a generic reader.
@@ -54,11 +105,27 @@ pub fn try_read_record_data<R>(reader: &mut R, len: usize, check_integrity: bool
 where
     R: Read,
 {
-    let buf = {
-        let mut buf = vec![0u8; len];
-        reader.read_exact(&mut buf)?;
-        buf
-    };
+    let mut buf = vec![];
+    try_read_record_data_into(reader, len, check_integrity, &mut buf)?;
+    Ok(buf)
+}
+
+/// Read the record raw bytes with given length into a buffer.
+///
+/// The previous content of the buffer is discarded, while its allocation is reused.
+pub fn try_read_record_data_into<R>(
+    reader: &mut R,
+    len: usize,
+    check_integrity: bool,
+    buf: &mut Vec<u8>,
+) -> Result<()>
+where
+    R: Read,
+{
+    buf.clear();
+    buf.resize(len, 0);
+    reader.read_exact(buf)?;
+
     let expect_cksum = {
         let mut buf = [0; std::mem::size_of::<u32>()];
         reader.read_exact(&mut buf)?;
@@ -66,9 +133,9 @@ where
     };
 
     if check_integrity {
-        crate::utils::verify_checksum(&buf, expect_cksum)?;
+        crate::utils::verify_checksum(buf, expect_cksum, ChecksumKind::Data)?;
     }
-    Ok(buf)
+    Ok(())
 }
 
 /// Write the raw record bytes to a generic writer.
@@ -76,26 +143,129 @@ pub fn try_write_record<W>(writer: &mut W, bytes: Vec<u8>) -> Result<()>
 where
     W: Write,
 {
-    // write data size
-    {
-        let len = bytes.len();
-        let len_buf = len.to_le_bytes();
-        let cksum = crate::utils::checksum(&len_buf);
-        let cksum_buf = cksum.to_le_bytes();
+    try_write_record_slice(writer, &bytes)
+}
 
-        writer.write_all(&len_buf)?;
-        writer.write_all(&cksum_buf)?;
+/// Write the borrowed record bytes to a generic writer.
+///
+/// The header, data and footer are written together with vectored writes.
+pub fn try_write_record_slice<W>(writer: &mut W, bytes: &[u8]) -> Result<()>
+where
+    W: Write,
+{
+    let header = super::record_header(bytes.len());
+    let footer = super::record_footer(bytes);
+    let mut slices = [
+        IoSlice::new(&header),
+        IoSlice::new(bytes),
+        IoSlice::new(&footer),
+    ];
+    let mut slices = &mut slices[..];
+
+    while !slices.is_empty() {
+        match writer.write_vectored(slices) {
+            Ok(0) => return Err(io::Error::from(ErrorKind::WriteZero).into()),
+            Ok(len) => IoSlice::advance_slices(&mut slices, len),
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error.into()),
+        }
     }
+    Ok(())
+}
+
+/// A reader that skips corrupted bytes and resynchronizes to the next valid record.
+///
+/// The checksums are always verified. When the length or data checksum mismatches,
+/// it scans forward byte by byte for the next position where a record with valid
+/// checksums starts.
+#[derive(Debug)]
+pub struct RecoveryReader<R> {
+    reader: R,
+    buffer: RecoveryBuffer,
+}
 
-    // write data
+impl<R> RecoveryReader<R>
+where
+    R: Read,
+{
+    /// Wrap a generic reader.
+    ///
+    /// If `max_len` is set, records longer than the limit are treated as corrupted.
+    pub fn new(reader: R, max_len: Option<usize>) -> Self {
+        Self {
+            reader,
+            buffer: RecoveryBuffer::new(max_len),
+        }
+    }
+
+    /// Get the stream offset of the next unconsumed byte.
+    pub fn offset(&self) -> u64 {
+        self.buffer.offset()
+    }
+
+    /// Try to extract raw bytes of the next valid record.
+    ///
+    /// The `on_corrupted` callback is called with every skipped byte range.
+    /// If the end of file is reached, it returns `Ok(None)`.
+    pub fn try_read_record<F>(&mut self, on_corrupted: F) -> Result<Option<Vec<u8>>>
+    where
+        F: FnMut(CorruptedRange),
     {
-        let cksum = crate::utils::checksum(&bytes);
-        let cksum_buf = cksum.to_le_bytes();
+        let mut buf = vec![];
+        let offset = self.try_read_record_into(&mut buf, on_corrupted)?;
+        Ok(offset.map(|_| buf))
+    }
 
-        writer.write_all(bytes.as_slice())?;
-        writer.write_all(&cksum_buf)?;
+    /// Try to read raw bytes of the next valid record into a buffer.
+    ///
+    /// It returns the offset of the record if found. The previous content of the buffer
+    /// is discarded, while its allocation is reused.
+    pub fn try_read_record_into<F>(
+        &mut self,
+        buf: &mut Vec<u8>,
+        mut on_corrupted: F,
+    ) -> Result<Option<u64>>
+    where
+        F: FnMut(CorruptedRange),
+    {
+        loop {
+            match self.buffer.scan() {
+                Scan::NeedMore(len) => {
+                    if !self.fill(len)? {
+                        if let Some(range) = self.buffer.take_eof() {
+                            on_corrupted(range);
+                        }
+                        return Ok(None);
+                    }
+                }
+                Scan::Record(len) => {
+                    let (offset, skipped) = self.buffer.take_record(len, buf);
+                    if let Some(range) = skipped {
+                        on_corrupted(range);
+                    }
+                    return Ok(Some(offset));
+                }
+                Scan::Corrupted(kind) => self.buffer.mark_corrupted(kind),
+            }
+        }
+    }
+
+    fn fill(&mut self, min_len: usize) -> Result<bool> {
+        let mut filled = self.buffer.prepare_fill(min_len);
+        let result = loop {
+            if filled >= min_len {
+                break Ok(true);
+            }
+            match self.reader.read(&mut self.buffer.buf[filled..]) {
+                Ok(0) => break Ok(false),
+                Ok(n) => filled += n,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => break Err(error.into()),
+            }
+        };
+        self.buffer.buf.truncate(filled);
+        result
     }
-    Ok(())
 }
 
 fn try_read_exact<R, B>(reader: &mut R, mut buf: B) -> Result<Option<B>>
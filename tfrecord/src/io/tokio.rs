@@ -0,0 +1,274 @@
+pub use super::ReadState;
+use super::{
+    poll::{self, PollRead, PollSeek, PollWrite},
+    CorruptedRange, RecoveryBuffer,
+};
+use crate::error::Result;
+use ::tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+use futures::{future, ready};
+use std::{
+    io::{self, ErrorKind, IoSlice, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Try to extract raw bytes of a record from a tokio reader.
+///
+/// It reads the record length and data from a reader with tokio's [AsyncRead] trait,
+/// and verifies the checksum if requested.
+/// Records longer than [DEFAULT_MAX_RECORD_LEN](super::DEFAULT_MAX_RECORD_LEN) are rejected
+/// before allocation.
+/// If the end of file is reached, it returns `Ok(None)`.
+pub async fn try_read_record<R>(reader: &mut R, check_integrity: bool) -> Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    try_read_record_with_max_len(reader, check_integrity, Some(super::DEFAULT_MAX_RECORD_LEN)).await
+}
+
+/// Try to extract raw bytes of a record from a tokio reader with a custom length limit.
+///
+/// If `max_len` is set, records longer than the limit are rejected before allocation.
+/// It is not bounded if `max_len` is `None`.
+pub async fn try_read_record_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+) -> Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    poll::try_read_record_with_max_len(&mut TokioIo(reader), check_integrity, max_len).await
+}
+
+/// Try to read the record length from a tokio reader.
+///
+/// It is internally called by [try_read_record]. It returns `Ok(None)` if reaching the end of file.
+/// If the length exceeds [DEFAULT_MAX_RECORD_LEN](super::DEFAULT_MAX_RECORD_LEN), it returns
+/// [Error::RecordTooLarge](crate::Error::RecordTooLarge).
+pub async fn try_read_len<R>(reader: &mut R, check_integrity: bool) -> Result<Option<usize>>
+where
+    R: AsyncRead + Unpin,
+{
+    try_read_len_with_max_len(reader, check_integrity, Some(super::DEFAULT_MAX_RECORD_LEN)).await
+}
+
+/// Try to read the record length from a tokio reader with a custom length limit.
+///
+/// If `max_len` is set and the length exceeds the limit, it returns
+/// [Error::RecordTooLarge](crate::Error::RecordTooLarge).
+/// It is not bounded if `max_len` is `None`.
+pub async fn try_read_len_with_max_len<R>(
+    reader: &mut R,
+    check_integrity: bool,
+    max_len: Option<usize>,
+) -> Result<Option<usize>>
+where
+    R: AsyncRead + Unpin,
+{
+    poll::try_read_len_with_max_len(&mut TokioIo(reader), check_integrity, max_len, None).await
+}
+
+/// Read the record raw bytes with given length from a tokio reader.
+///
+/// It is internally called by [try_read_record].
+pub async fn try_read_record_data<R>(
+    reader: &mut R,
+    len: usize,
+    check_integrity: bool,
+) -> Result<Vec<u8>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut buf = vec![];
+    try_read_record_data_into(reader, len, check_integrity, &mut buf).await?;
+    Ok(buf)
+}
+
+/// Read the record raw bytes with given length into a buffer.
+///
+/// The previous content of the buffer is discarded, while its allocation is reused.
+pub async fn try_read_record_data_into<R>(
+    reader: &mut R,
+    len: usize,
+    check_integrity: bool,
+    buf: &mut Vec<u8>,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    poll::try_read_record_data_into(&mut TokioIo(reader), len, check_integrity, buf).await
+}
+
+/// Poll to read the next record into a buffer from a tokio reader.
+///
+/// The partial progress is kept in `state`, so the call can be resumed after
+/// it returns [Poll::Pending]. The same buffer must be given until the call
+/// completes. It returns the offset of the record, or `Ok(None)` if the end of
+/// file is reached. If `max_len` is set, records longer than the limit are
+/// rejected before allocation.
+pub fn poll_read_record_into<R>(
+    reader: &mut R,
+    cx: &mut Context<'_>,
+    state: &mut ReadState,
+    check_integrity: bool,
+    max_len: Option<usize>,
+    buf: &mut Vec<u8>,
+) -> Poll<Result<Option<u64>>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    state.poll_read_record(cx, check_integrity, max_len, buf, &mut TokioIo(reader))
+}
+
+/// Write the raw record bytes to a tokio writer.
+pub async fn try_write_record<W>(writer: &mut W, bytes: Vec<u8>) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    try_write_record_slice(writer, &bytes).await
+}
+
+/// Write the borrowed record bytes to a tokio writer.
+///
+/// The header, data and footer are written together with vectored writes.
+pub async fn try_write_record_slice<W>(writer: &mut W, bytes: &[u8]) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    poll::try_write_record_slice(&mut TokioIo(writer), bytes).await
+}
+
+/// A reader that skips corrupted bytes and resynchronizes to the next valid record.
+///
+/// It is the tokio counterpart of [RecoveryReader](super::sync::RecoveryReader).
+#[derive(Debug)]
+pub struct RecoveryReader<R> {
+    reader: R,
+    buffer: RecoveryBuffer,
+}
+
+impl<R> RecoveryReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Wrap a tokio reader.
+    ///
+    /// If `max_len` is set, records longer than the limit are treated as corrupted.
+    pub fn new(reader: R, max_len: Option<usize>) -> Self {
+        Self {
+            reader,
+            buffer: RecoveryBuffer::new(max_len),
+        }
+    }
+
+    /// Get the stream offset of the next unconsumed byte.
+    pub fn offset(&self) -> u64 {
+        self.buffer.offset()
+    }
+
+    /// Try to extract raw bytes of the next valid record.
+    ///
+    /// The `on_corrupted` callback is called with every skipped byte range.
+    /// If the end of file is reached, it returns `Ok(None)`.
+    pub async fn try_read_record<F>(&mut self, on_corrupted: F) -> Result<Option<Vec<u8>>>
+    where
+        F: FnMut(CorruptedRange),
+    {
+        let mut buf = vec![];
+        let offset = self.try_read_record_into(&mut buf, on_corrupted).await?;
+        Ok(offset.map(|_| buf))
+    }
+
+    /// Try to read raw bytes of the next valid record into a buffer.
+    ///
+    /// It returns the offset of the record if found. The previous content of the buffer
+    /// is discarded, while its allocation is reused.
+    pub async fn try_read_record_into<F>(
+        &mut self,
+        buf: &mut Vec<u8>,
+        mut on_corrupted: F,
+    ) -> Result<Option<u64>>
+    where
+        F: FnMut(CorruptedRange),
+    {
+        future::poll_fn(|cx| self.poll_read_record_into(cx, buf, &mut on_corrupted)).await
+    }
+
+    /// Poll to read raw bytes of the next valid record into a buffer.
+    ///
+    /// It is the poll-based counterpart of [try_read_record_into](RecoveryReader::try_read_record_into).
+    /// The progress is kept in the reader, so the call can be resumed after it returns
+    /// [Poll::Pending].
+    pub fn poll_read_record_into<F>(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<u8>,
+        on_corrupted: F,
+    ) -> Poll<Result<Option<u64>>>
+    where
+        F: FnMut(CorruptedRange),
+    {
+        let Self { reader, buffer } = self;
+        buffer.poll_read_record(cx, buf, on_corrupted, &mut TokioIo(reader))
+    }
+}
+
+/// Adapter of a reader or writer with tokio's I/O traits to the [poll-based I/O](super::poll).
+#[derive(Debug)]
+pub(crate) struct TokioIo<T>(pub(crate) T);
+
+impl<T> PollRead for TokioIo<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn poll_read_bytes(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let mut buf = ReadBuf::new(buf);
+        loop {
+            match Pin::new(&mut self.0).poll_read(cx, &mut buf) {
+                Poll::Ready(Ok(())) => return Poll::Ready(Ok(buf.filled().len())),
+                Poll::Ready(Err(error)) if error.kind() == ErrorKind::Interrupted => {}
+                Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> PollSeek for TokioIo<T>
+where
+    T: AsyncSeek + Unpin,
+{
+    fn poll_seek_to(
+        &mut self,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+        started: &mut bool,
+    ) -> Poll<io::Result<u64>> {
+        let mut stream = Pin::new(&mut self.0);
+        if !*started {
+            // a previous seek must complete before starting a new one
+            ready!(stream.as_mut().poll_complete(cx))?;
+            stream.as_mut().start_seek(pos)?;
+            *started = true;
+        }
+        stream.poll_complete(cx)
+    }
+}
+
+impl<T> PollWrite for TokioIo<T>
+where
+    T: AsyncWrite + Unpin,
+{
+    fn poll_write_slices(
+        &mut self,
+        cx: &mut Context<'_>,
+        slices: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write_vectored(cx, slices)
+    }
+
+    fn poll_flush_bytes(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+}
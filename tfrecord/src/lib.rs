@@ -4,10 +4,15 @@
 //!
 //! Optional features:
 //! - `full`: Enable all features.
-//! - `async`: Enable async/await feature.
+//! - `async`: Enable async/await feature with [async-std](https://crates.io/crates/async-std) file I/O.
+//! - `tokio`: Enable async/await feature with [tokio](https://crates.io/crates/tokio) file I/O.
+//!   The readers and writers work on tokio's I/O traits, such as `RecordTokioStream` and
+//!   `RecordTokioWriter`. It can be enabled independently of `async`, while the
+//!   `ShardedAsyncWriter` writes files with async-std and needs `async`.
+//! - `mmap`: Enable memory-mapped record reader.
 //!
 //! Third-party crate supports:
-//! - `with-serde`: Enable interoperability with [serde](https://crates.io/crates/serde) to serialize and deserialize example types.
+//! - `with-serde`: Enable interoperability with [serde](https://crates.io/crates/serde) to serialize and deserialize example types, and the JSON and bincode record wrappers.
 //! - `with-tch`: Enable [tch](https://crates.io/crates/tch) types support.
 //! - `with-image`: Enable [image](https://crates.io/crates/image) types support.
 //! - `with-ndarray`: Enable [ndarray](https://crates.io/crates/ndarray) types support.
@@ -48,20 +53,29 @@
 
 // mods
 
+pub mod compression;
+pub mod dataset;
+pub mod discovery;
 pub mod error;
 pub mod event;
 pub mod event_writer;
 pub mod indexer;
 pub mod io;
+pub mod pipeline;
 pub mod protobuf;
 pub mod protobuf_ext;
 pub mod record;
 pub mod record_reader;
 pub mod record_writer;
+pub mod repair;
+pub mod sharded_writer;
 mod utils;
+pub mod verify;
 
 // re-exports
 
+pub use compression::Compression;
+pub use dataset::*;
 pub use error::*;
 pub use event::*;
 pub use event_writer::*;
@@ -70,3 +84,4 @@ pub use protobuf_ext::*;
 pub use record::*;
 pub use record_reader::*;
 pub use record_writer::*;
+pub use sharded_writer::*;
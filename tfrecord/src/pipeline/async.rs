@@ -0,0 +1,293 @@
+use crate::error::Result;
+#[cfg(any(feature = "async", feature = "tokio"))]
+use futures::{
+    channel::{mpsc, oneshot},
+    future::FutureExt as _,
+    sink::SinkExt as _,
+};
+use futures::{
+    future::Future,
+    ready,
+    stream::{self, Stream, StreamExt as _},
+};
+use pin_project::pin_project;
+#[cfg(any(feature = "async", feature = "tokio"))]
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+use std::{
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Pipeline combinators for streams.
+pub trait PipelineStreamExt
+where
+    Self: Stream + Sized,
+{
+    /// Group items into batches of the size.
+    ///
+    /// It is the asynchronous counterpart of [batch](super::PipelineIterExt::batch).
+    ///
+    /// # Panics
+    /// It panics if `batch_size` is zero.
+    fn batch(self, batch_size: usize, drop_remainder: bool) -> BatchStream<Self> {
+        assert!(batch_size > 0, "batch_size must be positive");
+        BatchStream {
+            stream: self,
+            batch_size,
+            drop_remainder,
+            pending: vec![],
+            done: false,
+        }
+    }
+
+    /// Group the successful items into batches of the size.
+    ///
+    /// It is the asynchronous counterpart of [try_batch](super::PipelineIterExt::try_batch).
+    ///
+    /// # Panics
+    /// It panics if `batch_size` is zero.
+    fn try_batch<T>(self, batch_size: usize, drop_remainder: bool) -> TryBatchStream<Self, T>
+    where
+        Self: Stream<Item = Result<T>>,
+    {
+        assert!(batch_size > 0, "batch_size must be positive");
+        TryBatchStream {
+            stream: self,
+            batch_size,
+            drop_remainder,
+            pending: vec![],
+            done: false,
+        }
+    }
+
+    /// Read items ahead on a spawned async-std task.
+    ///
+    /// At most `buffer_size` items are buffered. The task stops after the stream is dropped.
+    /// If the upstream stream panics, the panic is resumed in the consuming task after the
+    /// buffered items are taken.
+    #[cfg(feature = "async")]
+    fn prefetch(self, buffer_size: usize) -> PrefetchStream<Self::Item>
+    where
+        Self: 'static + Send,
+        Self::Item: 'static + Send,
+    {
+        let (stream, forward) = PrefetchStream::new(self, buffer_size);
+        async_std::task::spawn(forward);
+        stream
+    }
+
+    /// Read items ahead on a spawned tokio task.
+    ///
+    /// At most `buffer_size` items are buffered. The task stops after the stream is dropped.
+    /// The upstream panics are resumed as in [prefetch](PipelineStreamExt::prefetch).
+    #[cfg(feature = "tokio")]
+    fn prefetch_tokio(self, buffer_size: usize) -> PrefetchStream<Self::Item>
+    where
+        Self: 'static + Send,
+        Self::Item: 'static + Send,
+    {
+        let (stream, forward) = PrefetchStream::new(self, buffer_size);
+        ::tokio::spawn(forward);
+        stream
+    }
+}
+
+impl<S> PipelineStreamExt for S where S: Stream {}
+
+/// Stream grouping items into batches, created by [batch](PipelineStreamExt::batch).
+#[pin_project]
+#[derive(Debug)]
+pub struct BatchStream<S>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    batch_size: usize,
+    drop_remainder: bool,
+    pending: Vec<S::Item>,
+    done: bool,
+}
+
+impl<S> Stream for BatchStream<S>
+where
+    S: Stream,
+{
+    type Item = Vec<S::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.done && this.pending.len() < *this.batch_size {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(item) => this.pending.push(item),
+                None => *this.done = true,
+            }
+        }
+
+        let incomplete = *this.drop_remainder && this.pending.len() < *this.batch_size;
+        if this.pending.is_empty() || incomplete {
+            return Poll::Ready(None);
+        }
+        Poll::Ready(Some(mem::take(this.pending)))
+    }
+}
+
+/// Stream grouping successful items into batches, created by
+/// [try_batch](PipelineStreamExt::try_batch).
+#[pin_project]
+#[derive(Debug)]
+pub struct TryBatchStream<S, T> {
+    #[pin]
+    stream: S,
+    batch_size: usize,
+    drop_remainder: bool,
+    pending: Vec<T>,
+    done: bool,
+}
+
+impl<S, T> Stream for TryBatchStream<S, T>
+where
+    S: Stream<Item = Result<T>>,
+{
+    type Item = Result<Vec<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.done && this.pending.len() < *this.batch_size {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(item)) => this.pending.push(item),
+                Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+                None => *this.done = true,
+            }
+        }
+
+        let incomplete = *this.drop_remainder && this.pending.len() < *this.batch_size;
+        if this.pending.is_empty() || incomplete {
+            return Poll::Ready(None);
+        }
+        Poll::Ready(Some(Ok(mem::take(this.pending))))
+    }
+}
+
+/// Stream reading items ahead on a spawned task, created by `prefetch` or `prefetch_tokio`.
+#[cfg(any(feature = "async", feature = "tokio"))]
+#[derive(Debug)]
+pub struct PrefetchStream<T> {
+    receiver: mpsc::Receiver<T>,
+    panic: Option<oneshot::Receiver<Box<dyn Any + Send>>>,
+}
+
+#[cfg(any(feature = "async", feature = "tokio"))]
+impl<T> PrefetchStream<T>
+where
+    T: 'static + Send,
+{
+    /// Build the stream and the future forwarding items from the upstream to be spawned.
+    fn new<S>(stream: S, buffer_size: usize) -> (Self, impl Future<Output = ()> + Send)
+    where
+        S: 'static + Stream<Item = T> + Send,
+    {
+        let (mut sender, receiver) = mpsc::channel(buffer_size);
+        let (panic_sender, panic_receiver) = oneshot::channel();
+        let forward = async move {
+            let mut stream = Box::pin(stream);
+            while let Some(item) = stream.next().await {
+                if sender.send(item).await.is_err() {
+                    break;
+                }
+            }
+        };
+        let forward = async move {
+            // the item sender is dropped before the panic is sent
+            let result = AssertUnwindSafe(forward).catch_unwind().await;
+            if let Err(payload) = result {
+                let _ = panic_sender.send(payload);
+            }
+        };
+        let stream = Self {
+            receiver,
+            panic: Some(panic_receiver),
+        };
+        (stream, forward)
+    }
+}
+
+#[cfg(any(feature = "async", feature = "tokio"))]
+impl<T> Stream for PrefetchStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(item) = ready!(self.receiver.poll_next_unpin(cx)) {
+            return Poll::Ready(Some(item));
+        }
+
+        // the sender is dropped, either at the end or by a panic
+        if let Some(panic) = &mut self.panic {
+            let result = ready!(panic.poll_unpin(cx));
+            self.panic = None;
+            if let Ok(payload) = result {
+                panic::resume_unwind(payload);
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+/// Repeat the items of a source for the number of epochs, or forever if `epochs` is `None`.
+///
+/// It is the asynchronous counterpart of [repeat](super::repeat). The `source` function
+/// builds the source stream for each epoch.
+pub fn repeat_async<F, Fut, S, T>(epochs: Option<usize>, source: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<S>>,
+    S: Stream<Item = Result<T>>,
+{
+    struct State<F, S> {
+        source: F,
+        epochs: Option<usize>,
+        epoch: usize,
+        current: Option<Pin<Box<S>>>,
+        yielded: bool,
+    }
+
+    let state: State<F, S> = State {
+        source,
+        epochs,
+        epoch: 0,
+        current: None,
+        yielded: true,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(stream) = &mut state.current {
+                if let Some(item) = stream.next().await {
+                    state.yielded = true;
+                    return Some((item, state));
+                }
+                state.current = None;
+            }
+
+            if !state.yielded || state.epochs.is_some_and(|epochs| state.epoch >= epochs) {
+                return None;
+            }
+            state.epoch += 1;
+            state.yielded = false;
+
+            match (state.source)().await {
+                Ok(stream) => state.current = Some(Box::pin(stream)),
+                Err(error) => {
+                    state.epochs = Some(state.epoch);
+                    return Some((Err(error), state));
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,42 @@
+//! Composable pipelines over record sources, similar to `tf.data`.
+//!
+//! Record sources, including [RecordIter](crate::RecordIter), the multi-file
+//! [ChainedRecordIter](crate::ChainedRecordIter) and the [indexer](crate::indexer), are plain
+//! iterators or streams. The map, filter, flat_map, take, skip, zip and enumerate combinators
+//! come from [Iterator] and futures' `StreamExt`. This module adds the missing ones:
+//!
+//! - `batch` and `try_batch` group items into batches, optionally dropping the remainder.
+//! - [repeat] and `repeat_async` rebuild the source for each epoch.
+//! - `prefetch` reads items ahead in the background.
+//!
+//! ```rust
+//! use tfrecord::{
+//!     pipeline::{self, PipelineIterExt as _},
+//!     BytesIter, BytesWriter,
+//! };
+//!
+//! let mut bytes = vec![];
+//! let mut writer = BytesWriter::from_writer(&mut bytes)?;
+//! for value in 0..5 {
+//!     writer.send(vec![value])?;
+//! }
+//! drop(writer);
+//!
+//! let batches: Vec<Vec<Vec<u8>>> = pipeline::repeat(Some(2), || {
+//!     Ok(BytesIter::from_reader(bytes.as_slice(), Default::default()))
+//! })
+//! .skip(1)
+//! .try_batch(4, true)
+//! .collect::<Result<_, _>>()?;
+//! assert_eq!(batches.len(), 2);
+//! assert_eq!(batches[0], vec![vec![1], vec![2], vec![3], vec![4]]);
+//! # Ok::<_, tfrecord::Error>(())
+//! ```
+
+mod sync;
+pub use sync::*;
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod r#async;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+pub use r#async::*;
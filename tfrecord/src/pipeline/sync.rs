@@ -0,0 +1,223 @@
+use crate::error::Result;
+use std::{
+    mem, panic,
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+/// Pipeline combinators for iterators.
+pub trait PipelineIterExt
+where
+    Self: Iterator + Sized,
+{
+    /// Group items into batches of the size.
+    ///
+    /// The last batch is smaller if the items run out, or it is dropped if `drop_remainder`
+    /// is set.
+    ///
+    /// # Panics
+    /// It panics if `batch_size` is zero.
+    fn batch(self, batch_size: usize, drop_remainder: bool) -> Batch<Self> {
+        assert!(batch_size > 0, "batch_size must be positive");
+        Batch {
+            iter: self,
+            batch_size,
+            drop_remainder,
+        }
+    }
+
+    /// Group the successful items into batches of the size.
+    ///
+    /// Errors are yielded as they come, while the collected items are kept for the next
+    /// batch. See [batch](PipelineIterExt::batch) for the remainder.
+    ///
+    /// # Panics
+    /// It panics if `batch_size` is zero.
+    fn try_batch<T>(self, batch_size: usize, drop_remainder: bool) -> TryBatch<Self, T>
+    where
+        Self: Iterator<Item = Result<T>>,
+    {
+        assert!(batch_size > 0, "batch_size must be positive");
+        TryBatch {
+            iter: self,
+            batch_size,
+            drop_remainder,
+            pending: vec![],
+        }
+    }
+
+    /// Read items ahead on a background thread.
+    ///
+    /// At most `buffer_size` items are buffered. The thread stops after the iterator is
+    /// dropped. If the upstream iterator panics, the panic is resumed on the consuming
+    /// thread after the buffered items are taken.
+    fn prefetch(self, buffer_size: usize) -> Prefetch<Self::Item>
+    where
+        Self: 'static + Send,
+        Self::Item: 'static + Send,
+    {
+        let (sender, receiver) = mpsc::sync_channel(buffer_size);
+        let handle = thread::spawn(move || {
+            for item in self {
+                if sender.send(item).is_err() {
+                    break;
+                }
+            }
+        });
+        Prefetch {
+            receiver,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl<I> PipelineIterExt for I where I: Iterator {}
+
+/// Iterator grouping items into batches, created by [batch](PipelineIterExt::batch).
+#[derive(Debug, Clone)]
+pub struct Batch<I> {
+    iter: I,
+    batch_size: usize,
+    drop_remainder: bool,
+}
+
+impl<I> Iterator for Batch<I>
+where
+    I: Iterator,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch: Vec<_> = self.iter.by_ref().take(self.batch_size).collect();
+        let incomplete = self.drop_remainder && batch.len() < self.batch_size;
+        (!batch.is_empty() && !incomplete).then_some(batch)
+    }
+}
+
+/// Iterator grouping successful items into batches, created by
+/// [try_batch](PipelineIterExt::try_batch).
+#[derive(Debug, Clone)]
+pub struct TryBatch<I, T> {
+    iter: I,
+    batch_size: usize,
+    drop_remainder: bool,
+    pending: Vec<T>,
+}
+
+impl<I, T> Iterator for TryBatch<I, T>
+where
+    I: Iterator<Item = Result<T>>,
+{
+    type Item = Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.len() < self.batch_size {
+            match self.iter.next() {
+                Some(Ok(item)) => self.pending.push(item),
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            }
+        }
+
+        let incomplete = self.drop_remainder && self.pending.len() < self.batch_size;
+        if self.pending.is_empty() || incomplete {
+            return None;
+        }
+        Some(Ok(mem::take(&mut self.pending)))
+    }
+}
+
+/// Iterator reading items ahead on a background thread, created by
+/// [prefetch](PipelineIterExt::prefetch).
+#[derive(Debug)]
+pub struct Prefetch<T> {
+    receiver: Receiver<T>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T> Iterator for Prefetch<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Ok(item) = self.receiver.recv() {
+            return Some(item);
+        }
+
+        // the sender is dropped, either at the end or by a panic
+        if let Some(Err(payload)) = self.handle.take().map(JoinHandle::join) {
+            panic::resume_unwind(payload);
+        }
+        None
+    }
+}
+
+/// Repeat the items of a source for the number of epochs, or forever if `epochs` is `None`.
+///
+/// The `source` function builds the source for each epoch, such as opening a
+/// [RecordIter](crate::RecordIter). It ends after an epoch yields nothing, or after
+/// failing to build the source.
+pub fn repeat<F, I, T>(epochs: Option<usize>, source: F) -> Repeat<F, I::IntoIter>
+where
+    F: FnMut() -> Result<I>,
+    I: IntoIterator<Item = Result<T>>,
+{
+    Repeat {
+        source,
+        epochs,
+        epoch: 0,
+        current: None,
+        yielded: true,
+    }
+}
+
+/// Iterator repeating a source for multiple epochs, created by [repeat].
+#[derive(Debug, Clone)]
+pub struct Repeat<F, I> {
+    source: F,
+    epochs: Option<usize>,
+    epoch: usize,
+    current: Option<I>,
+    /// Whether the current epoch yields any item.
+    yielded: bool,
+}
+
+impl<F, I> Repeat<F, I> {
+    /// Get the number of started epochs.
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+}
+
+impl<F, I, T> Iterator for Repeat<F, I::IntoIter>
+where
+    F: FnMut() -> Result<I>,
+    I: IntoIterator<Item = Result<T>>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(iter) = &mut self.current {
+                if let Some(item) = iter.next() {
+                    self.yielded = true;
+                    return Some(item);
+                }
+                self.current = None;
+            }
+
+            if !self.yielded || self.epochs.is_some_and(|epochs| self.epoch >= epochs) {
+                return None;
+            }
+            self.epoch += 1;
+            self.yielded = false;
+
+            match (self.source)() {
+                Ok(iter) => self.current = Some(iter.into_iter()),
+                Err(error) => {
+                    self.epochs = Some(self.epoch);
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
@@ -4,20 +4,72 @@ use crate::{
     error::Error,
     protobuf::{Event, Example},
 };
-use prost::Message as _;
+use std::ops::{Deref, DerefMut};
 
 /// Mark types the is serailized to or deserialized from TFRecord format.
+///
+/// Implementors provide [decode](Record::decode) and [encode](Record::encode), which work on
+/// borrowed buffers. The [from_bytes](Record::from_bytes) and [to_bytes](Record::to_bytes)
+/// methods are kept for compatibility, and each pair is implemented on top of the other.
+/// At least one method of each pair must be implemented.
+///
+/// Types implementing only [to_bytes](Record::to_bytes) are written by the owned `send`
+/// methods of writers, while the by-reference `send_ref` methods require
+/// [encode](Record::encode).
 pub trait Record
 where
     Self: Sized,
 {
-    /// Deserialze from bytes in TFRecord format.
-    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error>;
-    /// Serialze to bytes in TFRecord format.
-    fn to_bytes(record: Self) -> Result<Vec<u8>, Error>;
+    /// Deserialize from borrowed bytes in TFRecord format.
+    ///
+    /// It copies the bytes to call [from_bytes](Record::from_bytes) by default.
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes(bytes.to_vec())
+    }
+
+    /// Serialize to bytes in TFRecord format, which are appended to the buffer.
+    ///
+    /// It returns an error by default, since [to_bytes](Record::to_bytes) takes the
+    /// record by value.
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let _ = buf;
+        Err(Error::conversion(
+            "the record type does not implement encoding by reference",
+        ))
+    }
+
+    /// The hint of the serialized length in bytes, used to reserve the buffer.
+    fn encoded_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Deserialize from owned bytes in TFRecord format.
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        Self::decode(&bytes)
+    }
+
+    /// Serialize to owned bytes in TFRecord format.
+    fn to_bytes(record: Self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::with_capacity(record.encoded_len().unwrap_or(0));
+        record.encode(&mut bytes)?;
+        Ok(bytes)
+    }
 }
 
 impl Record for Vec<u8> {
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(bytes.to_vec())
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        buf.extend_from_slice(self);
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
     fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
         Ok(bytes)
     }
@@ -28,27 +80,153 @@ impl Record for Vec<u8> {
 }
 
 impl Record for Example {
-    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
-        let example = Example::decode(bytes.as_ref())?;
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let example = <Example as prost::Message>::decode(bytes)?;
         Ok(example)
     }
 
-    fn to_bytes(record: Self) -> Result<Vec<u8>, Error> {
-        let mut bytes = vec![];
-        Example::encode(&record, &mut bytes)?;
-        Ok(bytes)
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        prost::Message::encode(self, buf)?;
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(prost::Message::encoded_len(self))
     }
 }
 
 impl Record for Event {
-    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
-        let example = Event::decode(bytes.as_ref())?;
-        Ok(example)
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let event = <Event as prost::Message>::decode(bytes)?;
+        Ok(event)
     }
 
-    fn to_bytes(record: Self) -> Result<Vec<u8>, Error> {
-        let mut bytes = vec![];
-        Event::encode(&record, &mut bytes)?;
-        Ok(bytes)
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        prost::Message::encode(self, buf)?;
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(prost::Message::encoded_len(self))
+    }
+}
+
+macro_rules! impl_wrapper {
+    ($name:ident) => {
+        impl<T> $name<T> {
+            /// Unwrap the inner value.
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> From<T> for $name<T> {
+            fn from(value: T) -> Self {
+                Self(value)
+            }
+        }
+
+        impl<T> Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+    };
+}
+
+/// The record wrapper storing any [prost::Message] in protobuf wire format.
+///
+/// ```rust
+/// use tfrecord::{protobuf::SequenceExample, ProtoRecord, RecordIter, RecordWriter};
+///
+/// let mut writer = RecordWriter::from_writer(vec![])?;
+/// writer.send(ProtoRecord(SequenceExample::default()))?;
+/// let bytes = writer.close()?;
+///
+/// let records: Vec<ProtoRecord<SequenceExample>> =
+///     RecordIter::from_reader(bytes.as_slice(), Default::default()).collect::<Result<_, _>>()?;
+/// assert_eq!(records.len(), 1);
+/// # Ok::<_, tfrecord::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ProtoRecord<M>(pub M);
+
+impl_wrapper!(ProtoRecord);
+
+impl<M> Record for ProtoRecord<M>
+where
+    M: prost::Message + Default,
+{
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self(M::decode(bytes)?))
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        self.0.encode(buf)?;
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(self.0.encoded_len())
+    }
+}
+
+/// The record wrapper storing any serde type as a JSON document.
+#[cfg(feature = "with-serde")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct JsonRecord<T>(pub T);
+
+#[cfg(feature = "with-serde")]
+impl_wrapper!(JsonRecord);
+
+#[cfg(feature = "with-serde")]
+impl<T> Record for JsonRecord<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self(serde_json::from_slice(bytes)?))
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        serde_json::to_writer(buf, &self.0)?;
+        Ok(())
+    }
+}
+
+/// The record wrapper storing any serde type in [bincode](https://crates.io/crates/bincode) format.
+#[cfg(feature = "with-serde")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BincodeRecord<T>(pub T);
+
+#[cfg(feature = "with-serde")]
+impl_wrapper!(BincodeRecord);
+
+#[cfg(feature = "with-serde")]
+impl<T> Record for BincodeRecord<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self(bincode::deserialize(bytes)?))
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        bincode::serialize_into(buf, &self.0)?;
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        bincode::serialized_size(&self.0)
+            .ok()
+            .map(|len| len as usize)
     }
 }
@@ -1,17 +1,34 @@
-use super::RecordReaderConfig;
+use super::{
+    poll::{DecompressRead, PollReader, PollStream},
+    stream::FileRecordStream,
+    CorruptedRange, RecordReaderConfig,
+};
+#[cfg(feature = "async")]
+use super::{
+    ChainedRecordStream, FollowConfig, FollowStream, InterleaveConfig, InterleaveRecordStream,
+    ReaderCheckpoint,
+};
+#[cfg(feature = "async")]
+use crate::error::Error;
 use crate::{
-    error::{Error, Result},
+    compression::DecompressAsyncReader,
+    error::Result,
+    indexer::Position,
+    io::{poll::PollSeek, r#async::FuturesIo},
     protobuf::{Event, Example},
     record::Record,
 };
+#[cfg(feature = "async")]
 use async_std::{fs::File, io::BufReader, path::Path};
 use futures::{
-    io::AsyncRead,
-    stream::{BoxStream, Stream, StreamExt},
+    future::{BoxFuture, FutureExt as _},
+    io::{AsyncRead, AsyncSeek},
+    stream::Stream,
 };
-use pin_project::pin_project;
+#[cfg(feature = "async")]
+use std::time::Duration;
 use std::{
-    marker::PhantomData,
+    io::{self, SeekFrom},
     pin::Pin,
     task::{Context, Poll},
 };
@@ -20,44 +37,216 @@ pub type BytesStream<R> = RecordStream<Vec<u8>, R>;
 pub type ExampleStream<R> = RecordStream<Example, R>;
 pub type EventStream<R> = RecordStream<Event, R>;
 
+/// Asynchronous reader of raw record bytes from reader `R`.
+///
+/// It reads records into a caller-owned buffer using [read_next_into](RecordAsyncReader::read_next_into),
+/// which reuses the allocation across records.
+pub struct RecordAsyncReader<R>
+where
+    R: AsyncRead,
+{
+    reader: PollReader<FuturesIo<DecompressAsyncReader<R>>>,
+}
+
+impl<R> RecordAsyncReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Read records from a reader type with [AsyncRead] trait.
+    pub fn from_reader(reader: R, config: RecordReaderConfig) -> Self {
+        let reader = DecompressAsyncReader::new(reader, config.compression);
+        Self {
+            reader: PollReader::new(FuturesIo(reader), &config),
+        }
+    }
+
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    ///
+    /// It takes effect only if [skip_corrupted](RecordReaderConfig::skip_corrupted) is set.
+    pub fn on_corrupted<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(CorruptedRange) + Send,
+    {
+        self.reader.set_on_corrupted(callback);
+        self
+    }
+
+    /// Get the offset of the next record in the uncompressed stream.
+    pub fn offset(&self) -> u64 {
+        self.reader.offset()
+    }
+
+    /// Read the next record into a buffer asynchronously.
+    ///
+    /// It returns the offset of the record in the uncompressed stream, or `Ok(None)` if the end
+    /// of file is reached. The previous content of the buffer is discarded, while its allocation
+    /// is reused.
+    pub async fn read_next_into(&mut self, buf: &mut Vec<u8>) -> Result<Option<u64>> {
+        self.reader.read_next_into(buf).await
+    }
+
+    /// Poll to read the next record into a buffer.
+    ///
+    /// It is the poll-based counterpart of [read_next_into](RecordAsyncReader::read_next_into).
+    /// The progress of a partially read record is kept in the reader, so the same buffer must
+    /// be given until the call returns [Poll::Ready].
+    pub fn poll_read_next_into(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Poll<Result<Option<u64>>> {
+        self.reader.poll_read_next_into(cx, buf)
+    }
+
+    /// Read the next record into a new buffer asynchronously.
+    pub async fn read_next(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut buf = vec![];
+        let offset = self.read_next_into(&mut buf).await?;
+        Ok(offset.map(|_| buf))
+    }
+}
+
+impl<R> RecordAsyncReader<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Skip records by reading their headers only.
+    ///
+    /// It is the asynchronous counterpart of [RecordReader::skip_records](super::RecordReader::skip_records).
+    pub async fn skip_records(&mut self, count: u64) -> Result<u64> {
+        self.reader.skip_records(count).await
+    }
+
+    /// Move to the record at the ordinal from the start of stream.
+    ///
+    /// It is the asynchronous counterpart of [RecordReader::seek_to_record](super::RecordReader::seek_to_record).
+    pub async fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        self.reader.seek_to_record(index).await
+    }
+
+    /// Move to the record at the position, such as one returned by the [indexer](crate::indexer).
+    pub async fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        self.reader.seek_to_position(position).await
+    }
+}
+
+impl<R> DecompressRead for FuturesIo<DecompressAsyncReader<R>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    fn is_uncompressed(&self) -> bool {
+        matches!(self.0, DecompressAsyncReader::None(_))
+    }
+}
+
+impl<R> PollSeek for FuturesIo<DecompressAsyncReader<R>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    fn poll_seek_to(
+        &mut self,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+        started: &mut bool,
+    ) -> Poll<io::Result<u64>> {
+        match &mut self.0 {
+            DecompressAsyncReader::None(reader) => FuturesIo(reader).poll_seek_to(cx, pos, started),
+            _ => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "cannot seek compressed streams",
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl RecordAsyncReader<BufReader<File>> {
+    /// Read records from a file.
+    pub async fn open<P>(path: P, config: RecordReaderConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = BufReader::new(File::open(path).await?);
+        Ok(Self::from_reader(reader, config))
+    }
+}
+
 /// Stream of record `T` from reader `R`.
-#[pin_project]
+///
+/// The reader can be any type with [AsyncRead] trait, including borrowed ones. The errors
+/// are attached with the [context](crate::Error::context) locating the record.
 pub struct RecordStream<T, R>
 where
     T: Record,
     R: AsyncRead,
 {
-    #[pin]
-    stream: BoxStream<'static, Result<T, Error>>,
-    _phantom: PhantomData<R>,
+    stream: PollStream<T, FuturesIo<DecompressAsyncReader<R>>>,
 }
 
 impl<T, R> RecordStream<T, R>
 where
     T: Record,
-    R: AsyncRead,
+    R: AsyncRead + Unpin,
 {
     /// Load records from a reader type with [AsyncRead] trait.
-    pub fn from_reader(reader: R, config: RecordReaderConfig) -> Self
+    pub fn from_reader(reader: R, config: RecordReaderConfig) -> Self {
+        RecordAsyncReader::from_reader(reader, config).into()
+    }
+
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    ///
+    /// It takes effect only if [skip_corrupted](RecordReaderConfig::skip_corrupted) is set.
+    pub fn on_corrupted<F>(mut self, callback: F) -> Self
     where
-        R: 'static + Unpin + Send,
+        F: 'static + FnMut(CorruptedRange) + Send,
     {
-        let RecordReaderConfig { check_integrity } = config;
+        self.stream.set_on_corrupted(callback);
+        self
+    }
+}
 
-        let stream = futures::stream::try_unfold(reader, move |mut reader| async move {
-            let bytes = crate::io::r#async::try_read_record(&mut reader, check_integrity).await?;
-            let record = bytes.map(T::from_bytes).transpose()?;
-            Ok(record.map(|record| (record, reader)))
-        })
-        .boxed();
+impl<T, R> RecordStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Skip records without decoding them.
+    ///
+    /// See [RecordAsyncReader::skip_records].
+    pub async fn skip_records(&mut self, count: u64) -> Result<u64> {
+        self.stream.skip_records(count).await
+    }
 
-        Self {
-            stream,
-            _phantom: PhantomData,
-        }
+    /// Move to the record at the ordinal from the start of stream.
+    ///
+    /// See [RecordAsyncReader::seek_to_record].
+    pub async fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        self.stream.seek_to_record(index).await
+    }
+
+    /// Move to the record at the position.
+    ///
+    /// See [RecordAsyncReader::seek_to_position].
+    pub async fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        self.stream.seek_to_position(position).await
+    }
+}
+
+impl<T, R> FileRecordStream<T> for RecordStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + AsyncSeek + Unpin + Send,
+{
+    fn offset(&self) -> u64 {
+        self.stream.offset()
+    }
+
+    fn resume_at(&mut self, offset: u64, record_index: u64) -> BoxFuture<'_, Result<()>> {
+        self.stream.resume_at(offset, record_index).boxed()
     }
 }
 
+#[cfg(feature = "async")]
 impl<T> RecordStream<T, BufReader<File>>
 where
     T: Record,
@@ -65,23 +254,175 @@ where
     /// Load records from a file.
     pub async fn open<P>(path: P, config: RecordReaderConfig) -> Result<Self>
     where
-        T: Record,
         P: AsRef<Path>,
     {
-        let reader = BufReader::new(File::open(path).await?);
-        let reader = Self::from_reader(reader, config);
+        let path: &std::path::Path = path.as_ref().as_ref();
+        let file = File::open(path)
+            .await
+            .map_err(|error| Error::from(error).with_context(Some(path), None, None))?;
+        let mut reader = Self::from_reader(BufReader::new(file), config);
+        reader.stream.set_path(path.to_owned());
         Ok(reader)
     }
 }
 
+impl<T, R> From<RecordAsyncReader<R>> for RecordStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + Unpin,
+{
+    fn from(reader: RecordAsyncReader<R>) -> Self {
+        Self {
+            stream: reader.reader.into(),
+        }
+    }
+}
+
 impl<T, R> Stream for RecordStream<T, R>
 where
     T: Record,
-    R: AsyncRead,
+    R: AsyncRead + Unpin,
 {
     type Item = Result<T>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.project().stream.poll_next(cx)
+        self.get_mut().stream.poll_next(cx)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> ChainedRecordStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Load records from files in the given order.
+    pub fn from_paths<I, P>(paths: I, config: RecordReaderConfig) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<std::path::Path>,
+    {
+        Self::from_checkpoint(paths, config, Default::default())
+            .expect("the initial checkpoint is always valid")
     }
+
+    /// Resume loading records from files at the checkpoint.
+    ///
+    /// The files must be given in the same order as the stream the checkpoint is taken from.
+    pub fn from_checkpoint<I, P>(
+        paths: I,
+        config: RecordReaderConfig,
+        checkpoint: ReaderCheckpoint,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<std::path::Path>,
+    {
+        let paths: Vec<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_owned())
+            .collect();
+        Self::from_opener(paths, checkpoint, move |path| {
+            RecordStream::<T, _>::open(path, config.clone())
+        })
+    }
+
+    /// Load records from files specified by a glob pattern or shard spec.
+    ///
+    /// See [discovery](crate::discovery) for the accepted patterns.
+    pub async fn open_pattern(pattern: &str, config: RecordReaderConfig) -> Result<Self> {
+        let pattern = pattern.to_owned();
+        let paths =
+            async_std::task::spawn_blocking(move || crate::discovery::find_files(&pattern)).await?;
+        Ok(Self::from_paths(paths, config))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> InterleaveRecordStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Load records from files concurrently.
+    ///
+    /// The files enter the cycle in the given order.
+    pub fn from_paths<I, P>(
+        paths: I,
+        reader_config: RecordReaderConfig,
+        config: InterleaveConfig,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<std::path::Path>,
+    {
+        let paths: Vec<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_owned())
+            .collect();
+        Self::from_opener(paths, config, move |path| {
+            RecordStream::<T, _>::open(path, reader_config.clone())
+        })
+    }
+
+    /// Load records from files specified by a glob pattern or shard spec concurrently.
+    ///
+    /// See [discovery](crate::discovery) for the accepted patterns.
+    pub async fn open_pattern(
+        pattern: &str,
+        reader_config: RecordReaderConfig,
+        config: InterleaveConfig,
+    ) -> Result<Self> {
+        let pattern = pattern.to_owned();
+        let paths =
+            async_std::task::spawn_blocking(move || crate::discovery::find_files(&pattern)).await?;
+        Self::from_paths(paths, reader_config, config)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> FollowStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Follow records from a reader type with [AsyncRead] trait.
+    ///
+    /// Compression and [skip_corrupted](RecordReaderConfig::skip_corrupted) mode are not supported.
+    pub fn from_reader<R>(
+        reader: R,
+        reader_config: RecordReaderConfig,
+        config: FollowConfig,
+    ) -> Result<Self>
+    where
+        R: 'static + AsyncRead + Unpin + Send,
+    {
+        let reader = Box::new(FuturesIo(reader));
+        Self::with_runtime(reader, None, reader_config, config, follow_sleep)
+    }
+
+    /// Follow records from a file.
+    pub async fn open<P>(
+        path: P,
+        reader_config: RecordReaderConfig,
+        config: FollowConfig,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path: &std::path::Path = path.as_ref().as_ref();
+        let file = File::open(path)
+            .await
+            .map_err(|error| Error::from(error).with_context(Some(path), None, None))?;
+        let reader = Box::new(FuturesIo(file));
+        Self::with_runtime(
+            reader,
+            Some(path.to_owned()),
+            reader_config,
+            config,
+            follow_sleep,
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+fn follow_sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    Box::pin(async_std::task::sleep(duration))
 }
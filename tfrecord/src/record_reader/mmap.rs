@@ -0,0 +1,198 @@
+use super::RecordReaderConfig;
+use crate::{
+    compression::Compression,
+    error::{ensure_argument, ChecksumKind, Error, Result},
+    indexer::Position,
+    io::{FOOTER_SIZE, HEADER_SIZE},
+    record::Record,
+};
+use memmap2::Mmap;
+use std::{fs::File, ops::Range, path::Path};
+
+/// Memory-mapped TFRecord file reader.
+///
+/// The record framing is validated when the file is opened. Record data is
+/// given as slices borrowed from the mapped file without copying.
+///
+/// The file must not be modified or truncated while it is mapped.
+#[derive(Debug)]
+pub struct MmapReader {
+    mmap: Mmap,
+    positions: Vec<Position>,
+    check_integrity: bool,
+}
+
+impl MmapReader {
+    /// Map a file into memory and validate the record framing.
+    ///
+    /// If `check_integrity` is set, the checksums of all records are verified.
+    /// Compressed files and `skip_corrupted` mode are not supported.
+    pub fn open<P>(path: P, config: RecordReaderConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let RecordReaderConfig {
+            check_integrity,
+            compression,
+            skip_corrupted,
+            max_record_len,
+        } = config;
+        ensure_argument!(
+            compression == Compression::None,
+            "memory-mapped reader does not support compressed files"
+        );
+        ensure_argument!(
+            !skip_corrupted,
+            "memory-mapped reader does not support skip_corrupted mode"
+        );
+
+        let file = File::open(path)?;
+        // SAFETY: the mapped file is assumed not to be modified during the lifetime of the reader.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let positions = scan_positions(&mmap, check_integrity, max_record_len)?;
+
+        Ok(Self {
+            mmap,
+            positions,
+            check_integrity,
+        })
+    }
+
+    /// Get the number of records.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Check if the file has no records.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Get the positions of all records in the file.
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    /// Get the data of the record at `index`.
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        let Position { offset, len } = *self.positions.get(index)?;
+        let start = offset as usize;
+        Some(&self.mmap[start..(start + len)])
+    }
+
+    /// Decode the record at `index` from the mapped data without copying the raw bytes.
+    pub fn decode<T>(&self, index: usize) -> Option<Result<T>>
+    where
+        T: Record,
+    {
+        self.get(index).map(T::decode)
+    }
+
+    /// Get the record data at a position given by the [indexer](crate::indexer).
+    ///
+    /// The data checksum is verified if `check_integrity` is set.
+    pub fn read_at(&self, position: Position) -> Result<&[u8]> {
+        let Position { offset, len } = position;
+        let range = record_range(offset, len, self.mmap.len()).ok_or(Error::UnexpectedEof)?;
+        let footer = range.end;
+        let data = &self.mmap[range];
+
+        if self.check_integrity {
+            let expect = u32::from_le_bytes(
+                self.mmap[footer..(footer + FOOTER_SIZE)]
+                    .try_into()
+                    .unwrap(),
+            );
+            crate::utils::verify_checksum(data, expect, ChecksumKind::Data)?;
+        }
+        Ok(data)
+    }
+
+    /// Iterate over the data of all records.
+    pub fn iter(&self) -> MmapIter<'_> {
+        MmapIter {
+            mmap: &self.mmap,
+            positions: self.positions.iter(),
+        }
+    }
+}
+
+/// Compute the data range of a record, returning `None` if the record exceeds the file.
+fn record_range(offset: u64, len: usize, file_len: usize) -> Option<Range<usize>> {
+    let start = usize::try_from(offset).ok()?;
+    let end = start.checked_add(len)?;
+    let footer_end = end.checked_add(FOOTER_SIZE)?;
+    (footer_end <= file_len).then_some(start..end)
+}
+
+fn scan_positions(
+    bytes: &[u8],
+    check_integrity: bool,
+    max_record_len: Option<usize>,
+) -> Result<Vec<Position>> {
+    let mut positions = vec![];
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        let header = bytes
+            .get(offset..(offset + HEADER_SIZE))
+            .ok_or(Error::UnexpectedEof)?;
+        let (len_buf, cksum_buf) = header.split_at(std::mem::size_of::<u64>());
+
+        if check_integrity {
+            let expect = u32::from_le_bytes(cksum_buf.try_into().unwrap());
+            crate::utils::verify_checksum(len_buf, expect, ChecksumKind::Length)?;
+        }
+        let len = u64::from_le_bytes(len_buf.try_into().unwrap());
+        let len = crate::io::check_len(len, max_record_len, Some(offset as u64))?;
+
+        let data_offset = (offset + HEADER_SIZE) as u64;
+        let range = record_range(data_offset, len, bytes.len()).ok_or(Error::UnexpectedEof)?;
+
+        if check_integrity {
+            let footer = &bytes[range.end..(range.end + FOOTER_SIZE)];
+            let expect = u32::from_le_bytes(footer.try_into().unwrap());
+            crate::utils::verify_checksum(&bytes[range.clone()], expect, ChecksumKind::Data)?;
+        }
+
+        positions.push(Position {
+            offset: data_offset,
+            len,
+        });
+        offset = range.end + FOOTER_SIZE;
+    }
+
+    Ok(positions)
+}
+
+impl<'a> IntoIterator for &'a MmapReader {
+    type Item = &'a [u8];
+    type IntoIter = MmapIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator of record data borrowed from a [MmapReader].
+#[derive(Debug, Clone)]
+pub struct MmapIter<'a> {
+    mmap: &'a [u8],
+    positions: std::slice::Iter<'a, Position>,
+}
+
+impl<'a> Iterator for MmapIter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Position { offset, len } = *self.positions.next()?;
+        let start = offset as usize;
+        Some(&self.mmap[start..(start + len)])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+}
+
+impl ExactSizeIterator for MmapIter<'_> {}
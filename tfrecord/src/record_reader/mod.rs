@@ -3,25 +3,230 @@
 //! The [RecordIter] iterator reads records from a file.
 //!
 //! The [RecordStream] reads records from a file and can cooperated with future's [stream](futures::stream) API..
+//! The `RecordTokioStream` is its counterpart on tokio's I/O traits.
+//!
+//! The [ChainedRecordIter] and `ChainedRecordStream` read records from multiple files in order,
+//! which can be specified by a glob pattern or shard spec. See [discovery](crate::discovery).
+//!
+//! The [InterleaveRecordIter] and `InterleaveRecordStream` read multiple files concurrently
+//! and interleave their records, similar to `tf.data.Dataset.interleave`. See
+//! [InterleaveConfig].
+//!
+//! The multi-file readers report a [ReaderCheckpoint], which can be saved and used to resume
+//! reading where it stopped.
+//!
+//! The [FollowIter] and `FollowStream` follow a file that is still being written, waiting for
+//! more data at the end of file. See [FollowConfig].
+//!
+//! The `MmapReader` maps a file into memory and gives borrowed record data without copying.
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 mod r#async;
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 pub use r#async::*;
 
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use self::tokio::*;
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod poll;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod stream;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+pub use stream::*;
+
 mod sync;
 pub use sync::*;
 
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "mmap")]
+pub use mmap::*;
+
+pub use crate::io::{CorruptedRange, CorruptionKind};
+
+use crate::{
+    compression::Compression,
+    error::{ensure_argument, Error, Result},
+};
+use std::{
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
 /// Configuration for record reader.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RecordReaderConfig {
     pub check_integrity: bool,
+    /// The compression type of the input data.
+    pub compression: Compression,
+    /// If set, corrupted records are skipped instead of stopping the reader.
+    ///
+    /// Checksums are always verified in this mode. The reader scans forward byte by byte
+    /// to the next valid record, and reports the skipped bytes to the callback
+    /// registered by `on_corrupted`.
+    pub skip_corrupted: bool,
+    /// The maximum accepted record length in bytes.
+    ///
+    /// Records declaring a longer length are rejected before the data buffer is allocated.
+    /// It defaults to [DEFAULT_MAX_RECORD_LEN](crate::io::DEFAULT_MAX_RECORD_LEN), and is not
+    /// bounded if `None`.
+    pub max_record_len: Option<usize>,
 }
 
 impl Default for RecordReaderConfig {
     fn default() -> Self {
         Self {
             check_integrity: true,
+            compression: Compression::None,
+            skip_corrupted: false,
+            max_record_len: Some(crate::io::DEFAULT_MAX_RECORD_LEN),
+        }
+    }
+}
+
+type CorruptionCallback = Box<dyn FnMut(CorruptedRange) + Send>;
+
+/// The progress of a multi-file reader, from which reading can be resumed.
+///
+/// It is taken after the last consumed record. Restoring from it skips the consumed
+/// files and seeks the current file to the offset without reading the earlier records.
+/// Compressed files cannot be sought, so the consumed part of the current file is
+/// decompressed again and discarded.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReaderCheckpoint {
+    /// The ordinal of the current file in the file list.
+    pub file_index: usize,
+    /// The path of the current file, or `None` if all files are consumed.
+    pub path: Option<PathBuf>,
+    /// The offset of the next record in the uncompressed stream of the current file.
+    pub offset: u64,
+    /// The number of records consumed from the current file.
+    pub file_records: u64,
+    /// The total number of records consumed.
+    pub num_records: u64,
+}
+
+impl ReaderCheckpoint {
+    /// Move to the next file after the current file is consumed.
+    pub(crate) fn next_file(&mut self, path: Option<&Path>) {
+        *self = Self {
+            file_index: self.file_index + 1,
+            path: path.map(Path::to_owned),
+            offset: 0,
+            file_records: 0,
+            num_records: self.num_records,
+        };
+    }
+
+    /// Record a consumed record of the current file.
+    pub(crate) fn consume(&mut self, offset: u64) {
+        self.offset = offset;
+        self.file_records += 1;
+        self.num_records += 1;
+    }
+
+    /// Check that the checkpoint is taken from the reader of the paths, and fill in the
+    /// path of the current file.
+    pub(crate) fn restore(self, paths: &[PathBuf]) -> Result<Self> {
+        let path = paths.get(self.file_index);
+        let matched = match (&self.path, path) {
+            (Some(expect), Some(path)) => expect == path,
+            (None, None) => self.file_index == paths.len(),
+            (None, Some(_)) => self.offset == 0 && self.file_records == 0,
+            (Some(_), None) => false,
+        };
+        if !matched {
+            return Err(Error::invalid_argument(format!(
+                "the checkpoint at file {} ({:?}) does not match the file list",
+                self.file_index, self.path
+            )));
+        }
+        Ok(Self {
+            path: path.cloned(),
+            ..self
+        })
+    }
+}
+
+/// Configuration for interleaved reading of multiple files.
+///
+/// A cycle of files is read concurrently. In deterministic mode, the reader takes
+/// [block_length](InterleaveConfig::block_length) records from each file in the cycle in
+/// turn. When a file is exhausted, the next file in the list takes its place in the cycle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InterleaveConfig {
+    /// The number of files read concurrently. It defaults to the available parallelism.
+    pub cycle_length: Option<usize>,
+    /// The number of consecutive records taken from a file before moving to the next file.
+    pub block_length: usize,
+    /// If set, records are yielded in the cycle order. Otherwise, records are yielded as
+    /// soon as they are read from any file, which keeps the reader busy when some files
+    /// are slower than the others.
+    pub deterministic: bool,
+    /// The number of records read ahead for each file.
+    pub buffer_size: usize,
+}
+
+impl Default for InterleaveConfig {
+    fn default() -> Self {
+        Self {
+            cycle_length: None,
+            block_length: 1,
+            deterministic: true,
+            buffer_size: 16,
+        }
+    }
+}
+
+impl InterleaveConfig {
+    /// Check the configuration and get the cycle length for the number of files.
+    pub(crate) fn resolve_cycle_length(&self, num_files: usize) -> Result<usize> {
+        ensure_argument!(self.block_length > 0, "block_length must be positive");
+        ensure_argument!(
+            self.cycle_length != Some(0),
+            "cycle_length must be positive"
+        );
+        let cycle_length = self
+            .cycle_length
+            .or_else(|| thread::available_parallelism().ok().map(|num| num.get()))
+            .unwrap_or(1)
+            .clamp(1, num_files.max(1));
+        Ok(cycle_length)
+    }
+}
+
+/// Configuration for following files that are still being written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FollowConfig {
+    /// The interval to poll for new data at the end of file.
+    pub poll_interval: Duration,
+    /// Stop following if no new data arrives for this duration. It follows forever if `None`.
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for FollowConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(1),
+            idle_timeout: None,
         }
     }
 }
+
+/// Check that the reader configuration can be used in follow mode.
+fn check_follow_config(config: &RecordReaderConfig) -> Result<()> {
+    ensure_argument!(
+        config.compression == Compression::None,
+        "follow mode does not support compressed files"
+    );
+    ensure_argument!(
+        !config.skip_corrupted,
+        "follow mode does not support skip_corrupted mode"
+    );
+    Ok(())
+}
@@ -0,0 +1,343 @@
+use super::{CorruptedRange, CorruptionCallback, RecordReaderConfig};
+use crate::{
+    error::{Error, Result},
+    indexer::Position,
+    io::{
+        poll::{self, PollRead, PollSeek},
+        ReadState, RecoveryBuffer, FOOTER_SIZE, HEADER_SIZE,
+    },
+    record::Record,
+};
+use futures::{future, ready};
+use std::{
+    io::SeekFrom,
+    marker::PhantomData,
+    path::PathBuf,
+    task::{Context, Poll},
+};
+
+/// A decompressing reader which can be sought if the stream is not compressed.
+///
+/// It is implemented by the decompressing readers of each runtime to support seeking.
+pub(crate) trait DecompressRead: PollRead + PollSeek {
+    /// Check if the stream is not compressed, in which case the offsets are the same as those
+    /// of the underlying reader.
+    fn is_uncompressed(&self) -> bool;
+}
+
+/// Reader of raw record bytes from a decompressing reader `D`.
+///
+/// It implements the asynchronous record readers of all runtimes.
+pub(crate) struct PollReader<D> {
+    reader: D,
+    mode: ReadMode,
+    check_integrity: bool,
+    max_record_len: Option<usize>,
+    on_corrupted: Option<CorruptionCallback>,
+}
+
+enum ReadMode {
+    Strict(ReadState),
+    Recovery(RecoveryBuffer),
+}
+
+impl<D> PollReader<D> {
+    /// Read records from a decompressing reader built with the compression in `config`.
+    pub(crate) fn new(reader: D, config: &RecordReaderConfig) -> Self {
+        let RecordReaderConfig {
+            check_integrity,
+            skip_corrupted,
+            max_record_len,
+            ..
+        } = *config;
+        let mode = if skip_corrupted {
+            ReadMode::Recovery(RecoveryBuffer::new(max_record_len))
+        } else {
+            ReadMode::Strict(ReadState::default())
+        };
+
+        Self {
+            reader,
+            mode,
+            check_integrity,
+            max_record_len,
+            on_corrupted: None,
+        }
+    }
+
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    pub(crate) fn set_on_corrupted<F>(&mut self, callback: F)
+    where
+        F: 'static + FnMut(CorruptedRange) + Send,
+    {
+        self.on_corrupted = Some(Box::new(callback));
+    }
+
+    /// Get the offset of the next record in the uncompressed stream.
+    pub(crate) fn offset(&self) -> u64 {
+        match &self.mode {
+            ReadMode::Strict(state) => state.offset(),
+            ReadMode::Recovery(buffer) => buffer.offset(),
+        }
+    }
+}
+
+impl<D> PollReader<D>
+where
+    D: PollRead,
+{
+    /// Read the next record into a buffer.
+    pub(crate) async fn read_next_into(&mut self, buf: &mut Vec<u8>) -> Result<Option<u64>> {
+        future::poll_fn(|cx| self.poll_read_next_into(cx, buf)).await
+    }
+
+    /// Poll to read the next record into a buffer.
+    pub(crate) fn poll_read_next_into(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Poll<Result<Option<u64>>> {
+        let Self {
+            reader,
+            mode,
+            check_integrity,
+            max_record_len,
+            on_corrupted,
+        } = self;
+
+        match mode {
+            ReadMode::Strict(state) => {
+                state.poll_read_record(cx, *check_integrity, *max_record_len, buf, reader)
+            }
+            ReadMode::Recovery(buffer) => buffer.poll_read_record(
+                cx,
+                buf,
+                |range| {
+                    if let Some(callback) = on_corrupted {
+                        callback(range);
+                    }
+                },
+                reader,
+            ),
+        }
+    }
+}
+
+impl<D> PollReader<D>
+where
+    D: DecompressRead,
+{
+    /// Skip records by reading their headers only.
+    pub(crate) async fn skip_records(&mut self, count: u64) -> Result<u64> {
+        let Self {
+            reader,
+            mode,
+            check_integrity,
+            max_record_len,
+            ..
+        } = self;
+        let state = seekable_state(reader, mode)?;
+        let start = (
+            poll::seek(reader, SeekFrom::Current(0)).await?,
+            state.offset(),
+        );
+
+        let result = async {
+            let mut offset = start.1;
+            for skipped in 0..count {
+                let len = poll::try_read_len_with_max_len(
+                    reader,
+                    *check_integrity,
+                    *max_record_len,
+                    Some(offset),
+                )
+                .await?;
+                let Some(len) = len else {
+                    return Ok(skipped);
+                };
+                poll::seek(reader, SeekFrom::Current((len + FOOTER_SIZE) as i64)).await?;
+                offset += crate::io::record_size(len);
+                *state = ReadState::at(offset);
+            }
+            Ok(count)
+        }
+        .await;
+
+        if result.is_err() {
+            // the header of the failed record is partially consumed
+            poll::seek(reader, SeekFrom::Start(start.0)).await?;
+            *state = ReadState::at(start.1);
+        }
+        result
+    }
+
+    /// Move to the record at the ordinal from the start of stream.
+    pub(crate) async fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        self.seek_to_offset(0).await?;
+        let skipped = self.skip_records(index).await?;
+        if skipped < index {
+            return Err(Error::invalid_argument(format!(
+                "cannot seek to record {index}, while the stream has {skipped} records"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Move to the record at the position, such as one returned by the [indexer](crate::indexer).
+    pub(crate) async fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        let offset = position
+            .offset
+            .checked_sub(HEADER_SIZE as u64)
+            .ok_or_else(|| Error::invalid_argument("the position is not a record position"))?;
+        self.seek_to_offset(offset).await
+    }
+
+    /// Move to the record header at the offset.
+    async fn seek_to_offset(&mut self, offset: u64) -> Result<()> {
+        let state = seekable_state(&self.reader, &mut self.mode)?;
+        poll::seek(&mut self.reader, SeekFrom::Start(offset)).await?;
+        *state = ReadState::at(offset);
+        Ok(())
+    }
+
+    /// Move to the record header at the offset, reading through the preceding records if the
+    /// stream cannot be sought.
+    pub(crate) async fn resume_at(&mut self, offset: u64, buf: &mut Vec<u8>) -> Result<()> {
+        if seekable_state(&self.reader, &mut self.mode).is_ok() {
+            return self.seek_to_offset(offset).await;
+        }
+        while self.offset() < offset && self.read_next_into(buf).await?.is_some() {}
+        if self.offset() != offset {
+            return Err(Error::invalid_argument(format!(
+                "no record starts at offset {offset}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Get the read state if the stream can be sought.
+fn seekable_state<'a, D>(reader: &D, mode: &'a mut ReadMode) -> Result<&'a mut ReadState>
+where
+    D: DecompressRead,
+{
+    match mode {
+        ReadMode::Strict(state) if reader.is_uncompressed() => Ok(state),
+        _ => Err(Error::invalid_argument(
+            "seeking is not supported on compressed streams or in skip_corrupted mode",
+        )),
+    }
+}
+
+/// Stream of record `T` from a decompressing reader `D`.
+///
+/// It implements the asynchronous record streams of all runtimes.
+pub(crate) struct PollStream<T, D> {
+    reader: PollReader<D>,
+    buf: Vec<u8>,
+    path: Option<PathBuf>,
+    /// The ordinal of the next record, which is unknown after seeking to a position.
+    num_records: Option<u64>,
+    done: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, D> PollStream<T, D> {
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    pub(crate) fn set_on_corrupted<F>(&mut self, callback: F)
+    where
+        F: 'static + FnMut(CorruptedRange) + Send,
+    {
+        self.reader.set_on_corrupted(callback);
+    }
+
+    /// Set the file path attached to the errors.
+    pub(crate) fn set_path(&mut self, path: PathBuf) {
+        self.path = Some(path);
+    }
+
+    /// Get the offset of the next record in the uncompressed stream.
+    pub(crate) fn offset(&self) -> u64 {
+        self.reader.offset()
+    }
+}
+
+impl<T, D> From<PollReader<D>> for PollStream<T, D> {
+    fn from(reader: PollReader<D>) -> Self {
+        Self {
+            reader,
+            buf: vec![],
+            path: None,
+            num_records: Some(0),
+            done: false,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, D> PollStream<T, D>
+where
+    T: Record,
+    D: PollRead,
+{
+    /// Poll to decode the next record.
+    ///
+    /// The stream ends after the first error.
+    pub(crate) fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        let start = self.reader.offset();
+        let (record, offset) = match ready!(self.reader.poll_read_next_into(cx, &mut self.buf)) {
+            Ok(Some(offset)) => (T::decode(&self.buf), offset),
+            Ok(None) => {
+                self.done = true;
+                return Poll::Ready(None);
+            }
+            Err(error) => (Err(error), start),
+        };
+        let record_index = self.num_records;
+        self.num_records = record_index.map(|num| num + 1);
+        self.done = record.is_err();
+        let record = record
+            .map_err(|error| error.with_context(self.path.as_deref(), record_index, Some(offset)));
+        Poll::Ready(Some(record))
+    }
+}
+
+impl<T, D> PollStream<T, D>
+where
+    D: DecompressRead,
+{
+    /// Skip records without decoding them.
+    pub(crate) async fn skip_records(&mut self, count: u64) -> Result<u64> {
+        let skipped = self.reader.skip_records(count).await?;
+        self.num_records = self.num_records.map(|num| num + skipped);
+        Ok(skipped)
+    }
+
+    /// Move to the record at the ordinal from the start of stream.
+    pub(crate) async fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        self.done = false;
+        self.num_records = None;
+        self.reader.seek_to_record(index).await?;
+        self.num_records = Some(index);
+        Ok(())
+    }
+
+    /// Move to the record at the position.
+    pub(crate) async fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        self.done = false;
+        self.num_records = None;
+        self.reader.seek_to_position(position).await
+    }
+
+    /// Move to the record at the offset, which is the record of the ordinal.
+    pub(crate) async fn resume_at(&mut self, offset: u64, record_index: u64) -> Result<()> {
+        self.num_records = None;
+        self.reader.resume_at(offset, &mut self.buf).await?;
+        self.num_records = Some(record_index);
+        Ok(())
+    }
+}
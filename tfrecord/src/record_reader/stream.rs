@@ -0,0 +1,513 @@
+use super::{FollowConfig, InterleaveConfig, ReaderCheckpoint, RecordReaderConfig};
+use crate::{
+    error::{Error, Result},
+    io::{
+        poll::{self, PollRead},
+        TailBuffer,
+    },
+    record::Record,
+};
+use futures::{
+    future::{BoxFuture, Future, FutureExt as _, TryFutureExt as _},
+    stream::{BoxStream, Stream, StreamExt as _, TryStreamExt as _},
+};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+    vec,
+};
+
+/// A record stream of a single file that can resume at a record offset.
+///
+/// It is implemented by the record streams of each runtime to build the multi-file streams.
+pub(crate) trait FileRecordStream<T>: Stream<Item = Result<T>> + Unpin + Send {
+    /// Get the offset of the next record in the uncompressed stream.
+    fn offset(&self) -> u64;
+
+    /// Move to the record at the offset, which is the record of the ordinal.
+    fn resume_at(&mut self, offset: u64, record_index: u64) -> BoxFuture<'_, Result<()>>;
+}
+
+/// Stream of record `T` chained from multiple files.
+///
+/// The files are opened one at a time in the given order. The progress can be saved by
+/// [checkpoint](ChainedRecordStream::checkpoint) and restored by `from_checkpoint`.
+pub struct ChainedRecordStream<T>
+where
+    T: Record,
+{
+    stream: BoxStream<'static, Result<T>>,
+    checkpoint: Arc<Mutex<ReaderCheckpoint>>,
+}
+
+impl<T> ChainedRecordStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Build the stream from the file list, resuming at the checkpoint.
+    ///
+    /// The `open` function opens the record stream of a file.
+    pub(crate) fn from_opener<S, F, Fut>(
+        paths: Vec<PathBuf>,
+        checkpoint: ReaderCheckpoint,
+        open: F,
+    ) -> Result<Self>
+    where
+        S: 'static + FileRecordStream<T>,
+        F: 'static + Fn(PathBuf) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<S>> + Send,
+    {
+        let checkpoint = checkpoint.restore(&paths)?;
+        let ReaderCheckpoint {
+            file_index: start,
+            offset,
+            file_records,
+            ..
+        } = checkpoint;
+        let paths = Arc::new(paths);
+        let shared = Arc::new(Mutex::new(checkpoint));
+
+        let stream = futures::stream::iter(start..paths.len())
+            .then({
+                let shared = shared.clone();
+                move |file_index| {
+                    let paths = paths.clone();
+                    let shared = shared.clone();
+                    let path = paths[file_index].clone();
+                    let open = open(path.clone());
+                    let resume = (file_index == start && offset > 0).then_some(offset);
+
+                    async move {
+                        let stream = async {
+                            let mut stream = open.await?;
+                            if let Some(offset) = resume {
+                                stream
+                                    .resume_at(offset, file_records)
+                                    .await
+                                    .map_err(|error| {
+                                        error.with_context(
+                                            Some(&path),
+                                            Some(file_records),
+                                            Some(offset),
+                                        )
+                                    })?;
+                            }
+                            Result::<_, Error>::Ok(stream)
+                        };
+                        match stream.await {
+                            Ok(stream) => Ok(track_progress(stream, file_index, paths, shared)),
+                            Err(error) => {
+                                // the file is skipped after failing to open it
+                                let next_path = paths.get(file_index + 1);
+                                shared
+                                    .lock()
+                                    .unwrap()
+                                    .next_file(next_path.map(PathBuf::as_path));
+                                Err(error)
+                            }
+                        }
+                    }
+                }
+            })
+            .try_flatten()
+            .boxed();
+
+        Ok(Self {
+            stream,
+            checkpoint: shared,
+        })
+    }
+
+    /// Get the progress after the last consumed record.
+    pub fn checkpoint(&self) -> ReaderCheckpoint {
+        self.checkpoint.lock().unwrap().clone()
+    }
+}
+
+/// Update the checkpoint on each record of a file stream.
+fn track_progress<T, S>(
+    stream: S,
+    file_index: usize,
+    paths: Arc<Vec<PathBuf>>,
+    checkpoint: Arc<Mutex<ReaderCheckpoint>>,
+) -> impl Stream<Item = Result<T>>
+where
+    S: FileRecordStream<T>,
+{
+    futures::stream::unfold(Some(stream), move |stream| {
+        let paths = paths.clone();
+        let checkpoint = checkpoint.clone();
+        async move {
+            let mut stream = stream?;
+            match stream.next().await {
+                Some(record) => {
+                    checkpoint.lock().unwrap().consume(stream.offset());
+                    Some((record, Some(stream)))
+                }
+                None => {
+                    let next_path = paths.get(file_index + 1);
+                    checkpoint
+                        .lock()
+                        .unwrap()
+                        .next_file(next_path.map(PathBuf::as_path));
+                    None
+                }
+            }
+        }
+    })
+}
+
+impl<T> Stream for ChainedRecordStream<T>
+where
+    T: Record,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+type FileStream<T> = BoxStream<'static, Result<T>>;
+type OpenFn<T> = Box<dyn Fn(PathBuf) -> BoxFuture<'static, Result<FileStream<T>>> + Send>;
+
+/// Stream of record `T` interleaved from multiple files read concurrently.
+///
+/// The files in the cycle are polled concurrently by the task polling the stream, each
+/// reading ahead up to [buffer_size](InterleaveConfig::buffer_size) records. See
+/// [InterleaveConfig] for the ordering of records.
+pub struct InterleaveRecordStream<T>
+where
+    T: Record,
+{
+    paths: vec::IntoIter<PathBuf>,
+    open: OpenFn<T>,
+    /// The files in the cycle, or `None` if no file is left.
+    slots: Vec<Option<InterleaveSlot<T>>>,
+    block_length: usize,
+    buffer_size: usize,
+    deterministic: bool,
+    cycle_index: usize,
+    block_index: usize,
+}
+
+// the buffered records are never pinned
+impl<T> Unpin for InterleaveRecordStream<T> where T: Record {}
+
+/// A file in the cycle of [InterleaveRecordStream].
+struct InterleaveSlot<T> {
+    state: SlotState<T>,
+    buffer: VecDeque<Result<T>>,
+}
+
+enum SlotState<T> {
+    Opening(BoxFuture<'static, Result<FileStream<T>>>),
+    Reading(FileStream<T>),
+    Done,
+}
+
+impl<T> InterleaveSlot<T> {
+    /// Read records ahead until the buffer is full or no record is ready.
+    fn poll_fill(&mut self, cx: &mut Context<'_>, capacity: usize) {
+        while self.buffer.len() < capacity {
+            match &mut self.state {
+                SlotState::Opening(open) => match open.poll_unpin(cx) {
+                    Poll::Ready(Ok(stream)) => self.state = SlotState::Reading(stream),
+                    Poll::Ready(Err(error)) => {
+                        self.buffer.push_back(Err(error));
+                        self.state = SlotState::Done;
+                    }
+                    Poll::Pending => break,
+                },
+                SlotState::Reading(stream) => match stream.poll_next_unpin(cx) {
+                    Poll::Ready(Some(record)) => self.buffer.push_back(record),
+                    Poll::Ready(None) => self.state = SlotState::Done,
+                    Poll::Pending => break,
+                },
+                SlotState::Done => break,
+            }
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        matches!(self.state, SlotState::Done) && self.buffer.is_empty()
+    }
+}
+
+impl<T> InterleaveRecordStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Build the stream from the file list.
+    ///
+    /// The `open` function opens the record stream of a file.
+    pub(crate) fn from_opener<S, F, Fut>(
+        paths: Vec<PathBuf>,
+        config: InterleaveConfig,
+        open: F,
+    ) -> Result<Self>
+    where
+        S: 'static + Stream<Item = Result<T>> + Send,
+        F: 'static + Fn(PathBuf) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<S>> + Send,
+    {
+        let cycle_length = config.resolve_cycle_length(paths.len())?;
+        let InterleaveConfig {
+            block_length,
+            deterministic,
+            buffer_size,
+            ..
+        } = config;
+
+        let mut stream = Self {
+            paths: paths.into_iter(),
+            open: Box::new(move |path| open(path).map_ok(|stream| stream.boxed()).boxed()),
+            slots: Vec::with_capacity(cycle_length),
+            block_length,
+            buffer_size,
+            deterministic,
+            cycle_index: 0,
+            block_index: 0,
+        };
+        for _ in 0..cycle_length {
+            let slot = stream.start_file();
+            stream.slots.push(slot);
+        }
+        Ok(stream)
+    }
+
+    /// Start opening the next file, or return `None` if no file is left.
+    fn start_file(&mut self) -> Option<InterleaveSlot<T>> {
+        let path = self.paths.next()?;
+        Some(InterleaveSlot {
+            state: SlotState::Opening((self.open)(path)),
+            buffer: VecDeque::new(),
+        })
+    }
+}
+
+impl<T> InterleaveRecordStream<T>
+where
+    T: Record,
+{
+    fn poll_fill(&mut self, cx: &mut Context<'_>) {
+        let capacity = self.buffer_size.max(1);
+        for slot in self.slots.iter_mut().flatten() {
+            slot.poll_fill(cx, capacity);
+        }
+    }
+
+    fn advance(&mut self) {
+        self.cycle_index = (self.cycle_index + 1) % self.slots.len();
+        self.block_index = 0;
+    }
+
+    /// Take a record from the file at the index and move on after a full block.
+    fn take_record(&mut self, index: usize) -> Option<Result<T>> {
+        let record = self.slots[index].as_mut()?.buffer.pop_front()?;
+        if index != self.cycle_index {
+            self.cycle_index = index;
+            self.block_index = 0;
+        }
+        self.block_index += 1;
+        if self.block_index == self.block_length {
+            self.advance();
+        }
+        Some(record)
+    }
+}
+
+impl<T> Stream for InterleaveRecordStream<T>
+where
+    T: 'static + Record + Send,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if this.slots.iter().all(Option::is_none) {
+                return Poll::Ready(None);
+            }
+            this.poll_fill(cx);
+
+            if this.deterministic {
+                let index = this.cycle_index;
+                if let Some(record) = this.take_record(index) {
+                    return Poll::Ready(Some(record));
+                }
+                match &this.slots[index] {
+                    Some(slot) if !slot.is_exhausted() => return Poll::Pending,
+                    Some(_) => {
+                        // the file is exhausted and the next file takes its place
+                        this.slots[index] = this.start_file();
+                    }
+                    None => {}
+                }
+                this.advance();
+            } else {
+                // the exhausted files are replaced and the new files are polled first
+                let mut replaced = false;
+                for index in 0..this.slots.len() {
+                    if this.slots[index]
+                        .as_ref()
+                        .is_some_and(InterleaveSlot::is_exhausted)
+                    {
+                        this.slots[index] = this.start_file();
+                        replaced = true;
+                    }
+                }
+                if replaced {
+                    continue;
+                }
+
+                // take from the current file, or the next file with a ready record
+                let len = this.slots.len();
+                let record =
+                    (0..len).find_map(|offset| this.take_record((this.cycle_index + offset) % len));
+                return match record {
+                    Some(record) => Poll::Ready(Some(record)),
+                    None => Poll::Pending,
+                };
+            }
+        }
+    }
+}
+
+/// The function to sleep on an async runtime.
+pub(crate) type SleepFn = fn(Duration) -> BoxFuture<'static, ()>;
+
+/// Stream of record `T` following a file that is still being written, similar to `tail -f`.
+///
+/// When it reaches an incomplete record at the end of file, it keeps the partial bytes and
+/// polls for more data in the [poll interval](FollowConfig::poll_interval). It ends if no
+/// data arrives within the [idle timeout](FollowConfig::idle_timeout), yielding
+/// [Error::UnexpectedEof] first if an incomplete record is left. It also ends after
+/// the first error. The errors carry the [context](Error::context) of the failed record.
+pub struct FollowStream<T>
+where
+    T: Record,
+{
+    stream: BoxStream<'static, Result<T>>,
+}
+
+impl<T> FollowStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Build the stream reading bytes from `reader` and waiting for more data with `sleep`.
+    pub(crate) fn with_runtime(
+        reader: Box<dyn PollRead + Send>,
+        path: Option<PathBuf>,
+        reader_config: RecordReaderConfig,
+        config: FollowConfig,
+        sleep: SleepFn,
+    ) -> Result<Self> {
+        super::check_follow_config(&reader_config)?;
+        let RecordReaderConfig {
+            check_integrity,
+            max_record_len,
+            ..
+        } = reader_config;
+
+        let state = FollowState {
+            reader,
+            buffer: TailBuffer::new(check_integrity, max_record_len),
+            buf: vec![],
+            config,
+            sleep,
+            path,
+            num_records: 0,
+            done: false,
+        };
+        let stream = futures::stream::unfold(state, |mut state| async move {
+            if state.done {
+                return None;
+            }
+            let start = state.buffer.offset();
+            let (result, offset) = match state.read_next().await? {
+                Ok(offset) => (T::decode(&state.buf), offset),
+                Err(error) => (Err(error), start),
+            };
+            let record_index = state.num_records;
+            state.num_records += 1;
+            state.done = result.is_err();
+            let result = result.map_err(|error| {
+                error.with_context(state.path.as_deref(), Some(record_index), Some(offset))
+            });
+            Some((result, state))
+        })
+        .boxed();
+
+        Ok(Self { stream })
+    }
+}
+
+impl<T> Stream for FollowStream<T>
+where
+    T: Record,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+struct FollowState {
+    reader: Box<dyn PollRead + Send>,
+    buffer: TailBuffer,
+    buf: Vec<u8>,
+    config: FollowConfig,
+    sleep: SleepFn,
+    path: Option<PathBuf>,
+    num_records: u64,
+    done: bool,
+}
+
+impl FollowState {
+    /// Read the next record into the buffer and return its offset.
+    async fn read_next(&mut self) -> Option<Result<u64>> {
+        let mut last_active = Instant::now();
+
+        loop {
+            match self.buffer.take_record_into(&mut self.buf) {
+                Ok(Some(offset)) => return Some(Ok(offset)),
+                Ok(None) => {}
+                Err(error) => return Some(Err(error)),
+            }
+
+            let (filled, buf) = self.buffer.prepare_fill();
+            let result = poll::read(&mut *self.reader, buf).await;
+            let num_read = match result {
+                Ok(num_read) => num_read,
+                Err(error) => {
+                    self.buffer.finish_fill(filled);
+                    return Some(Err(error.into()));
+                }
+            };
+            self.buffer.finish_fill(filled + num_read);
+            if num_read > 0 {
+                last_active = Instant::now();
+                continue;
+            }
+
+            let FollowConfig {
+                poll_interval,
+                idle_timeout,
+            } = self.config;
+            if idle_timeout.is_some_and(|timeout| last_active.elapsed() >= timeout) {
+                return self
+                    .buffer
+                    .has_partial()
+                    .then_some(Err(Error::UnexpectedEof));
+            }
+            (self.sleep)(poll_interval).await;
+        }
+    }
+}
@@ -1,28 +1,287 @@
-use super::RecordReaderConfig;
+use super::{
+    CorruptedRange, CorruptionCallback, FollowConfig, InterleaveConfig, ReaderCheckpoint,
+    RecordReaderConfig,
+};
 use crate::{
-    error::Result,
+    compression::DecompressReader,
+    error::{Error, Result},
+    indexer::Position,
+    io::{sync::RecoveryReader, TailBuffer, FOOTER_SIZE, HEADER_SIZE},
     protobuf::{Event, Example},
     record::Record,
 };
 use std::{
     fs::File,
-    io::{prelude::*, BufReader},
+    io::ErrorKind,
+    io::{self, prelude::*, BufReader, SeekFrom},
     marker::PhantomData,
-    path::Path,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender, SyncSender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+    vec,
 };
 
 pub type BytesIter<R> = RecordIter<Vec<u8>, R>;
 pub type ExampleIter<R> = RecordIter<Example, R>;
 pub type EventIter<R> = RecordIter<Event, R>;
 
+/// Reader of raw record bytes from reader `R`.
+///
+/// It reads records into a caller-owned buffer using [read_next_into](RecordReader::read_next_into),
+/// which reuses the allocation across records.
+pub struct RecordReader<R>
+where
+    R: Read,
+{
+    reader: ReaderKind<R>,
+    check_integrity: bool,
+    max_record_len: Option<usize>,
+    offset: u64,
+    on_corrupted: Option<CorruptionCallback>,
+}
+
+enum ReaderKind<R>
+where
+    R: Read,
+{
+    Strict(DecompressReader<R>),
+    Recovery(RecoveryReader<DecompressReader<R>>),
+}
+
+impl<R> RecordReader<R>
+where
+    R: Read,
+{
+    /// Read records from a reader implementing [Read](std::io::Read).
+    pub fn from_reader(reader: R, config: RecordReaderConfig) -> Self {
+        let RecordReaderConfig {
+            check_integrity,
+            compression,
+            skip_corrupted,
+            max_record_len,
+        } = config;
+        let reader = DecompressReader::new(reader, compression);
+        let reader = if skip_corrupted {
+            ReaderKind::Recovery(RecoveryReader::new(reader, max_record_len))
+        } else {
+            ReaderKind::Strict(reader)
+        };
+
+        Self {
+            reader,
+            check_integrity,
+            max_record_len,
+            offset: 0,
+            on_corrupted: None,
+        }
+    }
+
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    ///
+    /// It takes effect only if [skip_corrupted](RecordReaderConfig::skip_corrupted) is set.
+    pub fn on_corrupted<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(CorruptedRange) + Send,
+    {
+        self.on_corrupted = Some(Box::new(callback));
+        self
+    }
+
+    /// Get the offset of the next record in the uncompressed stream.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Read the next record into a buffer.
+    ///
+    /// It returns the offset of the record in the uncompressed stream, or `Ok(None)` if the end
+    /// of file is reached. The previous content of the buffer is discarded, while its allocation
+    /// is reused.
+    pub fn read_next_into(&mut self, buf: &mut Vec<u8>) -> Result<Option<u64>> {
+        let offset = self.offset;
+
+        match &mut self.reader {
+            ReaderKind::Strict(reader) => {
+                let len = crate::io::sync::try_read_len_at(
+                    reader,
+                    self.check_integrity,
+                    self.max_record_len,
+                    Some(offset),
+                )?;
+                let Some(len) = len else {
+                    return Ok(None);
+                };
+
+                // the record is consumed even if the checksum mismatches
+                let result = crate::io::sync::try_read_record_data_into(
+                    reader,
+                    len,
+                    self.check_integrity,
+                    buf,
+                );
+                self.offset += crate::io::record_size(len);
+                result?;
+                Ok(Some(offset))
+            }
+            ReaderKind::Recovery(reader) => {
+                let on_corrupted = &mut self.on_corrupted;
+                let offset = reader.try_read_record_into(buf, |range| {
+                    if let Some(callback) = on_corrupted {
+                        callback(range);
+                    }
+                })?;
+                self.offset = reader.offset();
+                Ok(offset)
+            }
+        }
+    }
+
+    /// Read the next record into a new buffer.
+    pub fn read_next(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut buf = vec![];
+        let offset = self.read_next_into(&mut buf)?;
+        Ok(offset.map(|_| buf))
+    }
+}
+
+impl<R> RecordReader<R>
+where
+    R: Read + Seek,
+{
+    /// Skip records by reading their headers only.
+    ///
+    /// The record data is skipped by seeking without being read or checked, so truncated
+    /// records at the end of file are not detected. It works only on uncompressed streams
+    /// read without [skip_corrupted](RecordReaderConfig::skip_corrupted). It returns the
+    /// number of skipped records, which is less than `count` if the end of file is reached.
+    /// If it fails, the stream is moved back to where it was before the call.
+    pub fn skip_records(&mut self, count: u64) -> Result<u64> {
+        let Self {
+            reader,
+            check_integrity,
+            max_record_len,
+            offset,
+            ..
+        } = self;
+        let reader = seekable_reader(reader)?;
+        let start = (reader.stream_position()?, *offset);
+
+        let result = (|| {
+            for skipped in 0..count {
+                let len = crate::io::sync::try_read_len_at(
+                    reader,
+                    *check_integrity,
+                    *max_record_len,
+                    Some(*offset),
+                )?;
+                let Some(len) = len else {
+                    return Ok(skipped);
+                };
+                reader.seek_relative((len + FOOTER_SIZE) as i64)?;
+                *offset += crate::io::record_size(len);
+            }
+            Ok(count)
+        })();
+
+        if result.is_err() {
+            // the header of the failed record is partially consumed
+            reader.seek(SeekFrom::Start(start.0))?;
+            *offset = start.1;
+        }
+        result
+    }
+
+    /// Move to the record at the ordinal from the start of stream.
+    ///
+    /// It rewinds the stream and [skips](RecordReader::skip_records) the preceding records.
+    /// Seeking to the end of stream is allowed, while seeking beyond it is an error.
+    pub fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        seekable_reader(&mut self.reader)?.seek(SeekFrom::Start(0))?;
+        self.offset = 0;
+        let skipped = self.skip_records(index)?;
+        if skipped < index {
+            return Err(Error::invalid_argument(format!(
+                "cannot seek to record {index}, while the stream has {skipped} records"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Move to the record at the position, such as one returned by the [indexer](crate::indexer).
+    pub fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        let offset = position
+            .offset
+            .checked_sub(HEADER_SIZE as u64)
+            .ok_or_else(|| Error::invalid_argument("the position is not a record position"))?;
+        self.seek_to_offset(offset)
+    }
+
+    /// Move to the record header at the offset.
+    fn seek_to_offset(&mut self, offset: u64) -> Result<()> {
+        seekable_reader(&mut self.reader)?.seek(SeekFrom::Start(offset))?;
+        self.offset = offset;
+        Ok(())
+    }
+
+    /// Move to the record header at the offset, reading through the preceding records if the
+    /// stream cannot be sought.
+    fn resume_at(&mut self, offset: u64, buf: &mut Vec<u8>) -> Result<()> {
+        if seekable_reader(&mut self.reader).is_ok() {
+            return self.seek_to_offset(offset);
+        }
+        while self.offset < offset && self.read_next_into(buf)?.is_some() {}
+        if self.offset != offset {
+            return Err(Error::invalid_argument(format!(
+                "no record starts at offset {offset}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Get the underlying reader if the stream can be sought.
+fn seekable_reader<R>(reader: &mut ReaderKind<R>) -> Result<&mut R>
+where
+    R: Read,
+{
+    match reader {
+        ReaderKind::Strict(DecompressReader::None(reader)) => Ok(reader),
+        _ => Err(Error::invalid_argument(
+            "seeking is not supported on compressed streams or in skip_corrupted mode",
+        )),
+    }
+}
+
+impl RecordReader<BufReader<File>> {
+    /// Read records from a file.
+    pub fn open<P>(path: P, config: RecordReaderConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = BufReader::new(File::open(path.as_ref())?);
+        Ok(Self::from_reader(reader, config))
+    }
+}
+
 /// Iterator of record `T` from reader `R`.
+///
+/// The errors are attached with the [context](Error::context) locating the record.
 pub struct RecordIter<T, R>
 where
     T: Record,
     R: Read,
 {
-    reader: Option<R>,
-    check_integrity: bool,
+    reader: RecordReader<R>,
+    buf: Vec<u8>,
+    path: Option<PathBuf>,
+    /// The ordinal of the next record, which is unknown after seeking to a position.
+    num_records: Option<u64>,
+    done: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -33,13 +292,66 @@ where
 {
     /// Read records from a reader implementing [Read](std::io::Read).
     pub fn from_reader(reader: R, config: RecordReaderConfig) -> Self {
-        let RecordReaderConfig { check_integrity } = config;
+        RecordReader::from_reader(reader, config).into()
+    }
 
-        Self {
-            reader: Some(reader),
-            check_integrity,
-            _phantom: PhantomData,
-        }
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    ///
+    /// It takes effect only if [skip_corrupted](RecordReaderConfig::skip_corrupted) is set.
+    pub fn on_corrupted<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(CorruptedRange) + Send,
+    {
+        self.reader = self.reader.on_corrupted(callback);
+        self
+    }
+
+    /// Attach the location to an error of the record at the ordinal and offset.
+    fn locate(&self, error: Error, record_index: Option<u64>, offset: u64) -> Error {
+        error.with_context(self.path.as_deref(), record_index, Some(offset))
+    }
+}
+
+impl<T, R> RecordIter<T, R>
+where
+    T: Record,
+    R: Read + Seek,
+{
+    /// Skip records without decoding them.
+    ///
+    /// See [RecordReader::skip_records].
+    pub fn skip_records(&mut self, count: u64) -> Result<u64> {
+        let skipped = self.reader.skip_records(count)?;
+        self.num_records = self.num_records.map(|num| num + skipped);
+        Ok(skipped)
+    }
+
+    /// Move to the record at the ordinal from the start of stream.
+    ///
+    /// See [RecordReader::seek_to_record].
+    pub fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        self.done = false;
+        self.num_records = None;
+        self.reader.seek_to_record(index)?;
+        self.num_records = Some(index);
+        Ok(())
+    }
+
+    /// Move to the record at the position.
+    ///
+    /// See [RecordReader::seek_to_position].
+    pub fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        self.done = false;
+        self.num_records = None;
+        self.reader.seek_to_position(position)
+    }
+
+    /// Move to the record at the offset, which is the record of the ordinal.
+    fn resume_at(&mut self, offset: u64, record_index: u64) -> Result<()> {
+        self.num_records = None;
+        self.reader.resume_at(offset, &mut self.buf)?;
+        self.num_records = Some(record_index);
+        Ok(())
     }
 }
 
@@ -52,12 +364,34 @@ where
     where
         P: AsRef<Path>,
     {
-        let reader = BufReader::new(File::open(path.as_ref())?);
-        let record_reader = Self::from_reader(reader, config);
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|error| Error::from(error).with_context(Some(path), None, None))?;
+        let record_reader = Self {
+            path: Some(path.to_owned()),
+            ..Self::from_reader(BufReader::new(file), config)
+        };
         Ok(record_reader)
     }
 }
 
+impl<T, R> From<RecordReader<R>> for RecordIter<T, R>
+where
+    T: Record,
+    R: Read,
+{
+    fn from(reader: RecordReader<R>) -> Self {
+        Self {
+            reader,
+            buf: vec![],
+            path: None,
+            num_records: Some(0),
+            done: false,
+            _phantom: PhantomData,
+        }
+    }
+}
+
 impl<T, R> Iterator for RecordIter<T, R>
 where
     T: Record,
@@ -66,14 +400,598 @@ where
     type Item = Result<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let reader = self.reader.as_mut()?;
-        let bytes: Option<Result<_>> =
-            crate::io::sync::try_read_record(reader, self.check_integrity).transpose();
+        if self.done {
+            return None;
+        }
+        let start = self.reader.offset();
+        let (result, offset) = match self.reader.read_next_into(&mut self.buf) {
+            Ok(Some(offset)) => (T::decode(&self.buf), offset),
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(error) => (Err(error), start),
+        };
+        let record_index = self.num_records;
+        self.num_records = record_index.map(|num| num + 1);
+        Some(result.map_err(|error| self.locate(error, record_index, offset)))
+    }
+}
+
+/// Iterator of record `T` chained from multiple files.
+///
+/// The files are opened one at a time in the given order. The progress can be saved by
+/// [checkpoint](ChainedRecordIter::checkpoint) and restored by
+/// [from_checkpoint](ChainedRecordIter::from_checkpoint).
+pub struct ChainedRecordIter<T>
+where
+    T: Record,
+{
+    paths: Vec<PathBuf>,
+    config: RecordReaderConfig,
+    checkpoint: ReaderCheckpoint,
+    current: Option<RecordIter<T, BufReader<File>>>,
+}
+
+impl<T> ChainedRecordIter<T>
+where
+    T: Record,
+{
+    /// Read records from files in the given order.
+    pub fn from_paths<I, P>(paths: I, config: RecordReaderConfig) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: Vec<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_owned())
+            .collect();
+        let checkpoint = ReaderCheckpoint {
+            path: paths.first().cloned(),
+            ..Default::default()
+        };
+        Self {
+            paths,
+            config,
+            checkpoint,
+            current: None,
+        }
+    }
+
+    /// Resume reading records from files at the checkpoint.
+    ///
+    /// The files must be given in the same order as the reader the checkpoint is taken from.
+    pub fn from_checkpoint<I, P>(
+        paths: I,
+        config: RecordReaderConfig,
+        checkpoint: ReaderCheckpoint,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let iter = Self::from_paths(paths, config);
+        let checkpoint = checkpoint.restore(&iter.paths)?;
+        Ok(Self { checkpoint, ..iter })
+    }
+
+    /// Read records from files specified by a glob pattern or shard spec.
+    ///
+    /// See [discovery](crate::discovery) for the accepted patterns.
+    pub fn open_pattern(pattern: &str, config: RecordReaderConfig) -> Result<Self> {
+        let paths = crate::discovery::find_files(pattern)?;
+        Ok(Self::from_paths(paths, config))
+    }
+
+    /// Get the progress after the last consumed record.
+    pub fn checkpoint(&self) -> ReaderCheckpoint {
+        self.checkpoint.clone()
+    }
+
+    /// Open the current file and move to the checkpoint offset.
+    fn open_current(&self, path: &Path) -> Result<RecordIter<T, BufReader<File>>> {
+        let ReaderCheckpoint {
+            offset,
+            file_records,
+            ..
+        } = self.checkpoint;
+        let mut iter = RecordIter::open(path, self.config.clone())?;
+        if offset > 0 {
+            iter.resume_at(offset, file_records).map_err(|error| {
+                error.with_context(Some(path), Some(file_records), Some(offset))
+            })?;
+        }
+        Ok(iter)
+    }
+}
+
+impl<T> Iterator for ChainedRecordIter<T>
+where
+    T: Record,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(iter) = &mut self.current {
+                if let Some(record) = iter.next() {
+                    self.checkpoint.consume(iter.reader.offset());
+                    return Some(record);
+                }
+                self.current = None;
+                let next_path = self.paths.get(self.checkpoint.file_index + 1);
+                self.checkpoint.next_file(next_path.map(PathBuf::as_path));
+            }
+
+            let path = self.paths.get(self.checkpoint.file_index)?;
+            match self.open_current(path) {
+                Ok(iter) => self.current = Some(iter),
+                Err(error) => {
+                    // the file is skipped after failing to open it
+                    let next_path = self.paths.get(self.checkpoint.file_index + 1);
+                    self.checkpoint.next_file(next_path.map(PathBuf::as_path));
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+/// Iterator of record `T` interleaved from multiple files read in parallel.
+///
+/// Each file in the cycle is read on a worker thread, which reads ahead up to
+/// [buffer_size](InterleaveConfig::buffer_size) records. The threads are reused for
+/// the following files. See [InterleaveConfig] for the ordering of records. A panic
+/// while reading a file is yielded as an error, and the file is considered finished.
+pub struct InterleaveRecordIter<T>
+where
+    T: Record,
+{
+    paths: vec::IntoIter<PathBuf>,
+    reader_config: RecordReaderConfig,
+    buffer_size: usize,
+    // the channels are dropped before the pool joins the workers blocked on them
+    order: InterleaveOrder<T>,
+    pool: WorkerPool,
+}
+
+enum InterleaveOrder<T> {
+    Deterministic {
+        /// The record channels of the files in the cycle, or `None` if no file is left.
+        slots: Vec<Option<Receiver<Result<T>>>>,
+        block_length: usize,
+        cycle_index: usize,
+        block_index: usize,
+    },
+    NonDeterministic {
+        /// The shared channel, where `None` marks the end of a file.
+        sender: SyncSender<Option<Result<T>>>,
+        receiver: Receiver<Option<Result<T>>>,
+        num_active: usize,
+    },
+}
+
+impl<T> InterleaveRecordIter<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Read records from files in parallel.
+    ///
+    /// The files enter the cycle in the given order.
+    pub fn from_paths<I, P>(
+        paths: I,
+        reader_config: RecordReaderConfig,
+        config: InterleaveConfig,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: Vec<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_owned())
+            .collect();
+        let cycle_length = config.resolve_cycle_length(paths.len())?;
+        let InterleaveConfig {
+            block_length,
+            deterministic,
+            buffer_size,
+            ..
+        } = config;
+
+        let mut paths = paths.into_iter();
+        let pool = WorkerPool::new(cycle_length);
+        let order = if deterministic {
+            let slots = (0..cycle_length)
+                .map(|_| start_file(&mut paths, &pool, &reader_config, buffer_size))
+                .collect();
+            InterleaveOrder::Deterministic {
+                slots,
+                block_length,
+                cycle_index: 0,
+                block_index: 0,
+            }
+        } else {
+            let (sender, receiver) = mpsc::sync_channel(buffer_size * cycle_length);
+            let num_active = (0..cycle_length)
+                .take_while(|_| start_shared_file(&mut paths, &pool, &reader_config, &sender))
+                .count();
+            InterleaveOrder::NonDeterministic {
+                sender,
+                receiver,
+                num_active,
+            }
+        };
+
+        Ok(Self {
+            paths,
+            reader_config,
+            buffer_size,
+            order,
+            pool,
+        })
+    }
+
+    /// Read records from files specified by a glob pattern or shard spec in parallel.
+    ///
+    /// See [discovery](crate::discovery) for the accepted patterns.
+    pub fn open_pattern(
+        pattern: &str,
+        reader_config: RecordReaderConfig,
+        config: InterleaveConfig,
+    ) -> Result<Self> {
+        let paths = crate::discovery::find_files(pattern)?;
+        Self::from_paths(paths, reader_config, config)
+    }
+}
+
+impl<T> Iterator for InterleaveRecordIter<T>
+where
+    T: 'static + Record + Send,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.order {
+            InterleaveOrder::Deterministic {
+                slots,
+                block_length,
+                cycle_index,
+                block_index,
+            } => loop {
+                if slots.iter().all(Option::is_none) {
+                    return None;
+                }
+
+                let slot = &mut slots[*cycle_index];
+                if let Some(receiver) = slot {
+                    match receiver.recv() {
+                        Ok(record) => {
+                            *block_index += 1;
+                            if *block_index == *block_length {
+                                *cycle_index = (*cycle_index + 1) % slots.len();
+                                *block_index = 0;
+                            }
+                            return Some(record);
+                        }
+                        Err(_) => {
+                            // the file is exhausted and the next file takes its place
+                            *slot = start_file(
+                                &mut self.paths,
+                                &self.pool,
+                                &self.reader_config,
+                                self.buffer_size,
+                            );
+                        }
+                    }
+                }
+                *cycle_index = (*cycle_index + 1) % slots.len();
+                *block_index = 0;
+            },
+            InterleaveOrder::NonDeterministic {
+                sender,
+                receiver,
+                num_active,
+            } => {
+                while *num_active > 0 {
+                    match receiver.recv().ok()? {
+                        Some(record) => return Some(record),
+                        None => {
+                            // the file is exhausted and the next file is started
+                            let started = start_shared_file(
+                                &mut self.paths,
+                                &self.pool,
+                                &self.reader_config,
+                                sender,
+                            );
+                            if !started {
+                                *num_active -= 1;
+                            }
+                        }
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Start reading the next file into its own channel, or return `None` if no file is left.
+fn start_file<T>(
+    paths: &mut vec::IntoIter<PathBuf>,
+    pool: &WorkerPool,
+    config: &RecordReaderConfig,
+    buffer_size: usize,
+) -> Option<Receiver<Result<T>>>
+where
+    T: 'static + Record + Send,
+{
+    let path = paths.next()?;
+    let config = config.clone();
+    let (sender, receiver) = mpsc::sync_channel(buffer_size);
+    pool.execute(move || read_file(path, config, |record| sender.send(record).is_ok()));
+    Some(receiver)
+}
+
+/// Start reading the next file into the shared channel, or return false if no file is left.
+fn start_shared_file<T>(
+    paths: &mut vec::IntoIter<PathBuf>,
+    pool: &WorkerPool,
+    config: &RecordReaderConfig,
+    sender: &SyncSender<Option<Result<T>>>,
+) -> bool
+where
+    T: 'static + Record + Send,
+{
+    let Some(path) = paths.next() else {
+        return false;
+    };
+    let config = config.clone();
+    let sender = sender.clone();
+    pool.execute(move || {
+        read_file(path, config, |record| sender.send(Some(record)).is_ok());
+        let _ = sender.send(None);
+    });
+    true
+}
+
+/// Read the records of a file and pass them to `send` until it returns false.
+///
+/// If reading panics, the panic is passed to `send` as an error.
+fn read_file<T, F>(path: PathBuf, config: RecordReaderConfig, mut send: F)
+where
+    T: Record,
+    F: FnMut(Result<T>) -> bool,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| read_records(&path, config, &mut send)));
+    if let Err(payload) = result {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        let error = io::Error::other(format!("the reader panicked: {message}"));
+        send(Err(Error::from(error).with_context(
+            Some(&path),
+            None,
+            None,
+        )));
+    }
+}
 
-        if bytes.is_none() {
-            self.reader = None;
+fn read_records<T, F>(path: &Path, config: RecordReaderConfig, mut send: F)
+where
+    T: Record,
+    F: FnMut(Result<T>) -> bool,
+{
+    let iter = match RecordIter::open(path, config) {
+        Ok(iter) => iter,
+        Err(error) => {
+            send(Err(error));
+            return;
+        }
+    };
+    for record in iter {
+        if !send(record) {
+            return;
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed set of threads running jobs from a shared queue.
+///
+/// Dropping the pool closes the queue and joins the threads after the running jobs finish.
+struct WorkerPool {
+    sender: Option<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    fn new(num_threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = (0..num_threads)
+            .map(|_| {
+                let receiver = receiver.clone();
+                thread::spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    let Ok(job) = job else {
+                        break;
+                    };
+                    job();
+                })
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            threads,
+        }
+    }
+
+    fn execute<F>(&self, job: F)
+    where
+        F: 'static + FnOnce() + Send,
+    {
+        // the workers live as long as the pool
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(Box::new(job));
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        self.sender = None;
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Iterator of record `T` following a file that is still being written, similar to `tail -f`.
+///
+/// When it reaches an incomplete record at the end of file, it keeps the partial bytes and
+/// polls for more data in the [poll interval](FollowConfig::poll_interval), blocking the
+/// current thread. It ends if no data arrives within the [idle timeout](FollowConfig::idle_timeout),
+/// yielding [Error::UnexpectedEof] first if an incomplete record is left. It also ends after
+/// the first error. The errors carry the [context](Error::context) of the failed record.
+pub struct FollowIter<T, R>
+where
+    T: Record,
+    R: Read,
+{
+    reader: R,
+    buffer: TailBuffer,
+    buf: Vec<u8>,
+    config: FollowConfig,
+    path: Option<PathBuf>,
+    num_records: u64,
+    done: bool,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, R> FollowIter<T, R>
+where
+    T: Record,
+    R: Read,
+{
+    /// Follow records from a reader implementing [Read](std::io::Read).
+    ///
+    /// Compression and [skip_corrupted](RecordReaderConfig::skip_corrupted) mode are not supported.
+    pub fn from_reader(
+        reader: R,
+        reader_config: RecordReaderConfig,
+        config: FollowConfig,
+    ) -> Result<Self> {
+        super::check_follow_config(&reader_config)?;
+        let RecordReaderConfig {
+            check_integrity,
+            max_record_len,
+            ..
+        } = reader_config;
+
+        Ok(Self {
+            reader,
+            buffer: TailBuffer::new(check_integrity, max_record_len),
+            buf: vec![],
+            config,
+            path: None,
+            num_records: 0,
+            done: false,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Read the next record into the buffer and return its offset.
+    fn read_next(&mut self) -> Option<Result<u64>> {
+        let mut last_active = Instant::now();
+
+        loop {
+            match self.buffer.take_record_into(&mut self.buf) {
+                Ok(Some(offset)) => return Some(Ok(offset)),
+                Ok(None) => {}
+                Err(error) => return Some(Err(error)),
+            }
+
+            let (filled, buf) = self.buffer.prepare_fill();
+            let result = self.reader.read(buf);
+            let num_read = match result {
+                Ok(num_read) => num_read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => 0,
+                Err(error) => {
+                    self.buffer.finish_fill(filled);
+                    return Some(Err(error.into()));
+                }
+            };
+            self.buffer.finish_fill(filled + num_read);
+            if num_read > 0 {
+                last_active = Instant::now();
+                continue;
+            }
+
+            let FollowConfig {
+                poll_interval,
+                idle_timeout,
+            } = self.config;
+            if idle_timeout.is_some_and(|timeout| last_active.elapsed() >= timeout) {
+                return self
+                    .buffer
+                    .has_partial()
+                    .then_some(Err(Error::UnexpectedEof));
+            }
+            std::thread::sleep(poll_interval);
+        }
+    }
+}
+
+impl<T> FollowIter<T, File>
+where
+    T: Record,
+{
+    /// Follow records from a file.
+    pub fn open<P>(path: P, reader_config: RecordReaderConfig, config: FollowConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|error| Error::from(error).with_context(Some(path), None, None))?;
+        let iter = Self {
+            path: Some(path.to_owned()),
+            ..Self::from_reader(file, reader_config, config)?
+        };
+        Ok(iter)
+    }
+}
+
+impl<T, R> Iterator for FollowIter<T, R>
+where
+    T: Record,
+    R: Read,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
         }
-        let record = bytes?.and_then(T::from_bytes);
-        Some(record)
+        let start = self.buffer.offset();
+        let (result, offset) = match self.read_next() {
+            Some(Ok(offset)) => (T::decode(&self.buf), offset),
+            Some(Err(error)) => (Err(error), start),
+            None => {
+                self.done = true;
+                return None;
+            }
+        };
+        let record_index = self.num_records;
+        self.num_records += 1;
+        self.done = result.is_err();
+        Some(result.map_err(|error| {
+            error.with_context(self.path.as_deref(), Some(record_index), Some(offset))
+        }))
     }
 }
@@ -0,0 +1,419 @@
+use super::{
+    poll::{DecompressRead, PollReader, PollStream},
+    stream::FileRecordStream,
+    ChainedRecordStream, CorruptedRange, FollowConfig, FollowStream, InterleaveConfig,
+    InterleaveRecordStream, ReaderCheckpoint, RecordReaderConfig,
+};
+use crate::{
+    compression::DecompressTokioReader,
+    error::{Error, Result},
+    indexer::Position,
+    io::{poll::PollSeek, tokio::TokioIo},
+    protobuf::{Event, Example},
+    record::Record,
+};
+use ::tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncSeek, BufReader},
+};
+use futures::{
+    future::{BoxFuture, FutureExt as _},
+    stream::Stream,
+};
+use std::{
+    io::{self, SeekFrom},
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+pub type BytesTokioStream<R> = RecordTokioStream<Vec<u8>, R>;
+pub type ExampleTokioStream<R> = RecordTokioStream<Example, R>;
+pub type EventTokioStream<R> = RecordTokioStream<Event, R>;
+
+/// Reader of raw record bytes from reader `R` with tokio's [AsyncRead] trait.
+///
+/// It is the tokio counterpart of [RecordAsyncReader](super::RecordAsyncReader).
+pub struct RecordTokioReader<R>
+where
+    R: AsyncRead,
+{
+    reader: PollReader<TokioIo<DecompressTokioReader<R>>>,
+}
+
+impl<R> RecordTokioReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Read records from a reader type with tokio's [AsyncRead] trait.
+    pub fn from_reader(reader: R, config: RecordReaderConfig) -> Self {
+        let reader = DecompressTokioReader::new(reader, config.compression);
+        Self {
+            reader: PollReader::new(TokioIo(reader), &config),
+        }
+    }
+
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    ///
+    /// It takes effect only if [skip_corrupted](RecordReaderConfig::skip_corrupted) is set.
+    pub fn on_corrupted<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(CorruptedRange) + Send,
+    {
+        self.reader.set_on_corrupted(callback);
+        self
+    }
+
+    /// Get the offset of the next record in the uncompressed stream.
+    pub fn offset(&self) -> u64 {
+        self.reader.offset()
+    }
+
+    /// Read the next record into a buffer.
+    ///
+    /// It returns the offset of the record in the uncompressed stream, or `Ok(None)` if the end
+    /// of file is reached. The previous content of the buffer is discarded, while its allocation
+    /// is reused.
+    pub async fn read_next_into(&mut self, buf: &mut Vec<u8>) -> Result<Option<u64>> {
+        self.reader.read_next_into(buf).await
+    }
+
+    /// Poll to read the next record into a buffer.
+    ///
+    /// It is the poll-based counterpart of [read_next_into](RecordTokioReader::read_next_into).
+    /// The progress of a partially read record is kept in the reader, so the same buffer must
+    /// be given until the call returns [Poll::Ready].
+    pub fn poll_read_next_into(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Poll<Result<Option<u64>>> {
+        self.reader.poll_read_next_into(cx, buf)
+    }
+
+    /// Read the next record into a new buffer.
+    pub async fn read_next(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut buf = vec![];
+        let offset = self.read_next_into(&mut buf).await?;
+        Ok(offset.map(|_| buf))
+    }
+}
+
+impl<R> RecordTokioReader<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Skip records by reading their headers only.
+    ///
+    /// It is the tokio counterpart of [RecordReader::skip_records](super::RecordReader::skip_records).
+    pub async fn skip_records(&mut self, count: u64) -> Result<u64> {
+        self.reader.skip_records(count).await
+    }
+
+    /// Move to the record at the ordinal from the start of stream.
+    ///
+    /// It is the tokio counterpart of [RecordReader::seek_to_record](super::RecordReader::seek_to_record).
+    pub async fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        self.reader.seek_to_record(index).await
+    }
+
+    /// Move to the record at the position, such as one returned by the [indexer](crate::indexer).
+    pub async fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        self.reader.seek_to_position(position).await
+    }
+}
+
+impl<R> DecompressRead for TokioIo<DecompressTokioReader<R>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    fn is_uncompressed(&self) -> bool {
+        matches!(self.0, DecompressTokioReader::None(_))
+    }
+}
+
+impl<R> PollSeek for TokioIo<DecompressTokioReader<R>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    fn poll_seek_to(
+        &mut self,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+        started: &mut bool,
+    ) -> Poll<io::Result<u64>> {
+        match &mut self.0 {
+            DecompressTokioReader::None(reader) => TokioIo(reader).poll_seek_to(cx, pos, started),
+            _ => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "cannot seek compressed streams",
+            ))),
+        }
+    }
+}
+
+impl RecordTokioReader<BufReader<File>> {
+    /// Read records from a file using the tokio runtime.
+    pub async fn open<P>(path: P, config: RecordReaderConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = BufReader::new(File::open(path).await?);
+        Ok(Self::from_reader(reader, config))
+    }
+}
+
+/// Stream of record `T` from reader `R` with tokio's [AsyncRead] trait.
+///
+/// It is the tokio counterpart of [RecordStream](super::RecordStream). The errors
+/// are attached with the [context](Error::context) locating the record.
+pub struct RecordTokioStream<T, R>
+where
+    T: Record,
+    R: AsyncRead,
+{
+    stream: PollStream<T, TokioIo<DecompressTokioReader<R>>>,
+}
+
+impl<T, R> RecordTokioStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + Unpin,
+{
+    /// Load records from a reader type with tokio's [AsyncRead] trait.
+    pub fn from_reader(reader: R, config: RecordReaderConfig) -> Self {
+        RecordTokioReader::from_reader(reader, config).into()
+    }
+
+    /// Set the callback that receives byte ranges skipped due to corruption.
+    ///
+    /// It takes effect only if [skip_corrupted](RecordReaderConfig::skip_corrupted) is set.
+    pub fn on_corrupted<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(CorruptedRange) + Send,
+    {
+        self.stream.set_on_corrupted(callback);
+        self
+    }
+}
+
+impl<T, R> RecordTokioStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Skip records without decoding them.
+    ///
+    /// See [RecordTokioReader::skip_records].
+    pub async fn skip_records(&mut self, count: u64) -> Result<u64> {
+        self.stream.skip_records(count).await
+    }
+
+    /// Move to the record at the ordinal from the start of stream.
+    ///
+    /// See [RecordTokioReader::seek_to_record].
+    pub async fn seek_to_record(&mut self, index: u64) -> Result<()> {
+        self.stream.seek_to_record(index).await
+    }
+
+    /// Move to the record at the position.
+    ///
+    /// See [RecordTokioReader::seek_to_position].
+    pub async fn seek_to_position(&mut self, position: Position) -> Result<()> {
+        self.stream.seek_to_position(position).await
+    }
+}
+
+impl<T, R> FileRecordStream<T> for RecordTokioStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + AsyncSeek + Unpin + Send,
+{
+    fn offset(&self) -> u64 {
+        self.stream.offset()
+    }
+
+    fn resume_at(&mut self, offset: u64, record_index: u64) -> BoxFuture<'_, Result<()>> {
+        self.stream.resume_at(offset, record_index).boxed()
+    }
+}
+
+impl<T> RecordTokioStream<T, BufReader<File>>
+where
+    T: Record,
+{
+    /// Load records from a file using the tokio runtime.
+    pub async fn open<P>(path: P, config: RecordReaderConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .await
+            .map_err(|error| Error::from(error).with_context(Some(path), None, None))?;
+        let mut reader = Self::from_reader(BufReader::new(file), config);
+        reader.stream.set_path(path.to_owned());
+        Ok(reader)
+    }
+}
+
+impl<T, R> From<RecordTokioReader<R>> for RecordTokioStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + Unpin,
+{
+    fn from(reader: RecordTokioReader<R>) -> Self {
+        Self {
+            stream: reader.reader.into(),
+        }
+    }
+}
+
+impl<T, R> Stream for RecordTokioStream<T, R>
+where
+    T: Record,
+    R: AsyncRead + Unpin,
+{
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().stream.poll_next(cx)
+    }
+}
+
+impl<T> ChainedRecordStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Load records from files in the given order using the tokio runtime.
+    pub fn from_paths_tokio<I, P>(paths: I, config: RecordReaderConfig) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        Self::from_checkpoint_tokio(paths, config, Default::default())
+            .expect("the initial checkpoint is always valid")
+    }
+
+    /// Resume loading records from files at the checkpoint using the tokio runtime.
+    ///
+    /// The files must be given in the same order as the stream the checkpoint is taken from.
+    pub fn from_checkpoint_tokio<I, P>(
+        paths: I,
+        config: RecordReaderConfig,
+        checkpoint: ReaderCheckpoint,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: Vec<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_owned())
+            .collect();
+        Self::from_opener(paths, checkpoint, move |path| {
+            RecordTokioStream::<T, _>::open(path, config.clone())
+        })
+    }
+
+    /// Load records from files specified by a glob pattern or shard spec using the tokio runtime.
+    ///
+    /// See [discovery](crate::discovery) for the accepted patterns.
+    pub async fn open_pattern_tokio(pattern: &str, config: RecordReaderConfig) -> Result<Self> {
+        let pattern = pattern.to_owned();
+        let paths = ::tokio::task::spawn_blocking(move || crate::discovery::find_files(&pattern))
+            .await
+            .map_err(std::io::Error::from)??;
+        Ok(Self::from_paths_tokio(paths, config))
+    }
+}
+
+impl<T> InterleaveRecordStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Load records from files concurrently using the tokio runtime.
+    ///
+    /// The files enter the cycle in the given order.
+    pub fn from_paths_tokio<I, P>(
+        paths: I,
+        reader_config: RecordReaderConfig,
+        config: InterleaveConfig,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: Vec<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_owned())
+            .collect();
+        Self::from_opener(paths, config, move |path| {
+            RecordTokioStream::<T, _>::open(path, reader_config.clone())
+        })
+    }
+
+    /// Load records from files specified by a glob pattern or shard spec concurrently
+    /// using the tokio runtime.
+    ///
+    /// See [discovery](crate::discovery) for the accepted patterns.
+    pub async fn open_pattern_tokio(
+        pattern: &str,
+        reader_config: RecordReaderConfig,
+        config: InterleaveConfig,
+    ) -> Result<Self> {
+        let pattern = pattern.to_owned();
+        let paths = ::tokio::task::spawn_blocking(move || crate::discovery::find_files(&pattern))
+            .await
+            .map_err(std::io::Error::from)??;
+        Self::from_paths_tokio(paths, reader_config, config)
+    }
+}
+
+impl<T> FollowStream<T>
+where
+    T: 'static + Record + Send,
+{
+    /// Follow records from a reader type with tokio's [AsyncRead] trait.
+    ///
+    /// Compression and [skip_corrupted](RecordReaderConfig::skip_corrupted) mode are not supported.
+    pub fn from_tokio_reader<R>(
+        reader: R,
+        reader_config: RecordReaderConfig,
+        config: FollowConfig,
+    ) -> Result<Self>
+    where
+        R: 'static + AsyncRead + Unpin + Send,
+    {
+        let reader = Box::new(TokioIo(reader));
+        Self::with_runtime(reader, None, reader_config, config, follow_sleep)
+    }
+
+    /// Follow records from a file using the tokio runtime.
+    pub async fn open_tokio<P>(
+        path: P,
+        reader_config: RecordReaderConfig,
+        config: FollowConfig,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .await
+            .map_err(|error| Error::from(error).with_context(Some(path), None, None))?;
+        let reader = Box::new(TokioIo(file));
+        Self::with_runtime(
+            reader,
+            Some(path.to_owned()),
+            reader_config,
+            config,
+            follow_sleep,
+        )
+    }
+}
+
+fn follow_sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    Box::pin(::tokio::time::sleep(duration))
+}
@@ -1,15 +1,25 @@
+#[cfg(feature = "async")]
+use super::AppendInfo;
+use super::{poll::PollWriter, CountWriter, Finalizer, RecordWriterConfig, Tracker, WriterStats};
 use crate::{
+    compression::CompressAsyncWriter,
     error::{Error, Result},
+    indexer::Position,
+    io::r#async::FuturesIo,
     protobuf::Example,
     record::Record,
 };
-use async_std::{fs::File, io::BufWriter, path::Path};
+#[cfg(feature = "async")]
+use async_std::{
+    fs::{File, OpenOptions},
+    io::BufWriter,
+    path::Path,
+};
 use futures::{
     io::{AsyncWrite, AsyncWriteExt as _},
     sink,
     sink::Sink,
 };
-use std::marker::PhantomData;
 
 /// Alias to [RecordAsyncWriter] which input record type [Vec<u8>](Vec).
 pub type BytesAsyncWriter<W> = RecordAsyncWriter<Vec<u8>, W>;
@@ -18,15 +28,20 @@ pub type BytesAsyncWriter<W> = RecordAsyncWriter<Vec<u8>, W>;
 pub type ExampleAsyncWriter<W> = RecordAsyncWriter<Example, W>;
 
 /// The record writer.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// If the writer is configured with compression, [close](RecordAsyncWriter::close)
+/// must be called to finalize the compressed stream. Files created with the
+/// [durability](super::DurabilityConfig) options are also finalized by it.
+#[derive(Debug)]
 pub struct RecordAsyncWriter<T, W>
 where
     T: Record,
+    W: AsyncWrite,
 {
-    writer: W,
-    _phantom: PhantomData<T>,
+    writer: PollWriter<T, FuturesIo<CompressAsyncWriter<CountWriter<W>>>>,
 }
 
+#[cfg(feature = "async")]
 impl<T> RecordAsyncWriter<T, BufWriter<File>>
 where
     T: Record,
@@ -36,8 +51,51 @@ where
     where
         P: AsRef<Path>,
     {
+        Self::create_with_config(path, Default::default()).await
+    }
+
+    /// Build a writer writing to a new file with custom configuration.
+    pub async fn create_with_config<P>(path: P, config: RecordWriterConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let (path, finalizer, tracker) = super::prepare_create(path.as_ref().as_ref(), &config)?;
         let writer = BufWriter::new(File::create(path).await?);
-        Self::from_writer(writer)
+        let writer = Self::from_writer_with_config(writer, config)?;
+        Ok(writer.with_file(finalizer, tracker))
+    }
+
+    /// Build a writer appending to an existing file.
+    ///
+    /// The framing of the existing records is validated and a torn trailing record,
+    /// such as one left by a crashed writer, is truncated. The file is created if it
+    /// does not exist.
+    pub async fn open_append<P>(path: P) -> Result<(Self, AppendInfo)>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_append_with_config(path, Default::default()).await
+    }
+
+    /// Build a writer appending to an existing file with custom configuration.
+    ///
+    /// Compression is not supported in append mode.
+    pub async fn open_append_with_config<P>(
+        path: P,
+        config: RecordWriterConfig,
+    ) -> Result<(Self, AppendInfo)>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_owned();
+        let (info, finalizer, tracker) = {
+            let path = path.clone();
+            let config = config.clone();
+            blocking::unblock(move || super::prepare_append(path.as_ref(), &config)).await?
+        };
+        let writer = BufWriter::new(OpenOptions::new().append(true).open(path).await?);
+        let writer = Self::from_writer_with_config(writer, config)?;
+        Ok((writer.with_file(finalizer, tracker), info))
     }
 }
 
@@ -48,28 +106,84 @@ where
 {
     /// Build a writer from a writer with [AsyncWrite] trait.
     pub fn from_writer(writer: W) -> Result<Self> {
+        Self::from_writer_with_config(writer, Default::default())
+    }
+
+    /// Build a writer from a writer with [AsyncWrite] trait and custom configuration.
+    pub fn from_writer_with_config(writer: W, config: RecordWriterConfig) -> Result<Self> {
+        let RecordWriterConfig {
+            compression,
+            compression_level,
+            ..
+        } = config;
+
+        let writer =
+            CompressAsyncWriter::new(CountWriter::new(writer), compression, compression_level)?;
         Ok(Self {
-            writer,
-            _phantom: PhantomData,
+            writer: PollWriter::new(FuturesIo(writer)),
         })
     }
 
+    /// Set the finalization and the position tracker of the written file.
+    pub(super) fn with_file(self, finalizer: Option<Finalizer>, tracker: Tracker) -> Self {
+        Self {
+            writer: self.writer.with_file(finalizer, tracker),
+        }
+    }
+
     /// Write a record.
     pub async fn send(&mut self, record: T) -> Result<()> {
-        let bytes = T::to_bytes(record)?;
-        crate::io::r#async::try_write_record(&mut self.writer, bytes).await?;
+        self.send_with_position(record).await?;
         Ok(())
     }
 
+    /// Write a record and return its position.
+    ///
+    /// The position is counted in the uncompressed stream. For files opened in
+    /// append mode, it is relative to the start of file. The owned bytes of the record
+    /// are written without copying into the scratch buffer.
+    pub async fn send_with_position(&mut self, record: T) -> Result<Position> {
+        self.writer.send_with_position(record).await
+    }
+
+    /// Write a record by reference and return its position.
+    ///
+    /// The record is encoded into a scratch buffer reused across calls.
+    pub async fn send_ref(&mut self, record: &T) -> Result<Position> {
+        self.writer.send_ref(record).await
+    }
+
+    /// Write a record from borrowed raw bytes and return its position.
+    ///
+    /// The bytes are written as is without copying.
+    pub async fn send_bytes(&mut self, bytes: &[u8]) -> Result<Position> {
+        self.writer.send_bytes(bytes).await
+    }
+
+    /// Get the running counters.
+    pub fn stats(&self) -> WriterStats {
+        let count = self.writer.get_ref().0.get_ref().count();
+        self.writer.tracker().stats(count)
+    }
+
     /// Flush the output stream asynchronously.
     pub async fn flush(&mut self) -> Result<()> {
-        self.writer.flush().await?;
-        Ok(())
+        self.writer.flush().await
     }
 
     /// Closes the inner writer.
+    ///
+    /// It finalizes the compressed stream if compression is enabled, and finalizes the
+    /// file according to the durability options. The index sidecar file is saved if it
+    /// is configured.
     pub async fn close(&mut self) -> Result<()> {
-        self.writer.close().await?;
+        let writer = &mut self.writer.get_mut().0;
+        writer.close().await?;
+        // async-std's file does not flush its write cache on close
+        writer.get_mut().flush().await?;
+        if let Some((finalizer, index)) = self.writer.take_finalizer() {
+            blocking::unblock(move || finalizer.commit(index)).await?;
+        }
         Ok(())
     }
 
@@ -16,11 +16,482 @@
 //! | [BytesAsyncWriter](async::BytesAsyncWriter)           | [Vec<u8>](Vec)                  |
 //! | [ExampleAsyncWriter](async::ExampleAsyncWriter)       | [Example](crate::Example)       |
 //! | [RecordAsyncWriter](async::RecordAsyncWriter)         | Type that implements [Record](crate::record::Record) |
+//!
+//! The tokio counterparts are named in `TokioWriter` suffix, such as `RecordTokioWriter`.
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 mod r#async;
-#[cfg(feature = "async")]
+#[cfg(feature = "futures-io")]
 pub use r#async::*;
 
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use self::tokio::*;
+
 mod sync;
 pub use sync::*;
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod poll;
+
+use crate::{
+    compression::Compression,
+    error::{ensure_argument, Result},
+    indexer::{IndexFormat, IndexWriter, Position},
+    io::{CorruptionKind, HEADER_SIZE},
+    verify::VerifyConfig,
+};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, prelude::*, BufReader, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// Configuration for record writer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct RecordWriterConfig {
+    /// The compression type of the output data.
+    pub compression: Compression,
+    /// The compression level in range `0..=9`. The codec default is used if it is `None`.
+    pub compression_level: Option<u32>,
+    /// The finalization of files created by the writer. It has no effect on writers built from
+    /// an existing writer.
+    pub durability: DurabilityConfig,
+    /// If set, an index sidecar file of the given format is saved along with the created file
+    /// on close. It requires uncompressed output.
+    ///
+    /// The index entries are streamed to a temporary `<sidecar>.tmp` file as records are
+    /// written, which is moved to the sidecar path on close and left behind otherwise.
+    pub index: Option<IndexFormat>,
+}
+
+/// Running counters of a record writer.
+///
+/// The counters cover records written by the writer, excluding records already present
+/// in a file opened in append mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WriterStats {
+    /// The number of written records.
+    pub num_records: usize,
+    /// The total payload bytes of written records.
+    pub payload_bytes: u64,
+    /// The framed bytes in the uncompressed stream, including record headers and footers.
+    pub stream_bytes: u64,
+    /// The bytes passed to the inner writer, which are compressed if compression is enabled.
+    ///
+    /// The compressor and the inner writer may hold buffered data until flushed.
+    pub written_bytes: u64,
+}
+
+/// Options on how files created by writers are finalized on close.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct DurabilityConfig {
+    /// Write to a temporary sibling file, which is atomically renamed to the target path on close.
+    ///
+    /// The temporary file is named `.<file_name>.tmp` and is left behind if the writer is not
+    /// closed.
+    pub atomic: bool,
+    /// Sync the file data to disk on close.
+    pub sync_data: bool,
+    /// Sync the parent directory on close, making the creation or rename of the file durable.
+    pub sync_dir: bool,
+}
+
+/// The pending finalization of a file created by a writer.
+#[derive(Debug)]
+pub(crate) struct Finalizer {
+    path: PathBuf,
+    tmp_path: Option<PathBuf>,
+    sync_data: bool,
+    sync_dir: bool,
+    index: Option<IndexFormat>,
+}
+
+impl Finalizer {
+    /// Plan the finalization of the file at `path`.
+    ///
+    /// It returns the path to be written and the finalizer, which is `None` if nothing
+    /// has to be done on close.
+    pub(crate) fn new(path: &Path, config: &RecordWriterConfig) -> Result<(PathBuf, Option<Self>)> {
+        let RecordWriterConfig {
+            compression,
+            ref durability,
+            index,
+            ..
+        } = *config;
+        let DurabilityConfig {
+            atomic,
+            sync_data,
+            sync_dir,
+        } = *durability;
+        ensure_argument!(
+            index.is_none() || compression == Compression::None,
+            "index sidecar files require uncompressed output"
+        );
+
+        let tmp_path = atomic.then(|| {
+            let mut file_name = OsString::from(".");
+            file_name.push(path.file_name().unwrap_or_default());
+            file_name.push(".tmp");
+            path.with_file_name(file_name)
+        });
+        let write_path = tmp_path.clone().unwrap_or_else(|| path.to_owned());
+        let finalizer = (atomic || sync_data || sync_dir || index.is_some()).then(|| Self {
+            path: path.to_owned(),
+            tmp_path,
+            sync_data,
+            sync_dir,
+            index,
+        });
+        Ok((write_path, finalizer))
+    }
+
+    /// Start the index sidecar file if it is configured.
+    fn create_index(&self) -> Result<Option<IndexWriter>> {
+        self.index
+            .map(|format| IndexWriter::create(format.sidecar_path(&self.path), format))
+            .transpose()
+    }
+
+    /// Sync and rename the file, and complete the index sidecar file.
+    ///
+    /// The writer must be flushed before.
+    pub(crate) fn commit(self, index: Option<IndexWriter>) -> Result<()> {
+        let Self {
+            path,
+            tmp_path,
+            sync_data,
+            sync_dir,
+            ..
+        } = self;
+
+        if sync_data {
+            // fsync() flushes the file rather than the descriptor, so a new handle does the job
+            let written_path = tmp_path.as_deref().unwrap_or(&path);
+            OpenOptions::new()
+                .write(true)
+                .open(written_path)?
+                .sync_all()?;
+        }
+        if let Some(tmp_path) = &tmp_path {
+            fs::rename(tmp_path, &path)?;
+        }
+        if let Some(index) = index {
+            index.finish(&path)?;
+        }
+        if sync_dir {
+            sync_parent_dir(&path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plan the finalization of a new file and start tracking its records.
+///
+/// It returns the path to be written, the finalizer and the tracker.
+pub(crate) fn prepare_create(
+    path: &Path,
+    config: &RecordWriterConfig,
+) -> Result<(PathBuf, Option<Finalizer>, Tracker)> {
+    let (write_path, finalizer) = Finalizer::new(path, config)?;
+    let index = finalizer
+        .as_ref()
+        .map(Finalizer::create_index)
+        .transpose()?;
+    Ok((write_path, finalizer, Tracker::new(0, index.flatten())))
+}
+
+/// The tracker of record positions and counters of a writer.
+#[derive(Debug, Default)]
+pub(crate) struct Tracker {
+    base_offset: u64,
+    stats: WriterStats,
+    index: Option<IndexWriter>,
+}
+
+impl Tracker {
+    /// Start tracking at `base_offset`, optionally streaming the positions to an index file.
+    pub(crate) fn new(base_offset: u64, index: Option<IndexWriter>) -> Self {
+        Self {
+            base_offset,
+            index,
+            ..Default::default()
+        }
+    }
+
+    /// Count a record of `len` payload bytes and return its position.
+    ///
+    /// It fails if the position cannot be written to the index file.
+    pub(crate) fn push(&mut self, len: usize) -> Result<Position> {
+        let stats = &mut self.stats;
+        let position = Position {
+            offset: self.base_offset + stats.stream_bytes + HEADER_SIZE as u64,
+            len,
+        };
+        stats.num_records += 1;
+        stats.payload_bytes += len as u64;
+        stats.stream_bytes += crate::io::record_size(len);
+        if let Some(index) = &mut self.index {
+            index.push(position)?;
+        }
+        Ok(position)
+    }
+
+    /// Get the counters with the bytes written to the inner writer.
+    pub(crate) fn stats(&self, written_bytes: u64) -> WriterStats {
+        WriterStats {
+            written_bytes,
+            ..self.stats
+        }
+    }
+
+    /// Take the index file, leaving no positions written.
+    pub(crate) fn take_index(&mut self) -> Option<IndexWriter> {
+        self.index.take()
+    }
+}
+
+/// A writer counting the bytes written to the inner writer.
+#[derive(Debug)]
+#[cfg_attr(
+    any(feature = "futures-io", feature = "tokio"),
+    pin_project::pin_project
+)]
+pub(crate) struct CountWriter<W> {
+    #[cfg_attr(any(feature = "futures-io", feature = "tokio"), pin)]
+    inner: W,
+    count: u64,
+}
+
+impl<W> CountWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    pub(crate) fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub(crate) fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W> Write for CountWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.count += len as u64;
+        Ok(len)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let len = self.inner.write_vectored(bufs)?;
+        self.count += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<W> futures::io::AsyncWrite for CountWriter<W>
+where
+    W: futures::io::AsyncWrite,
+{
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.inner.poll_write(cx, buf);
+        if let std::task::Poll::Ready(Ok(len)) = poll {
+            *this.count += len as u64;
+        }
+        poll
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> std::task::Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.inner.poll_write_vectored(cx, bufs);
+        if let std::task::Poll::Ready(Ok(len)) = poll {
+            *this.count += len as u64;
+        }
+        poll
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W> ::tokio::io::AsyncWrite for CountWriter<W>
+where
+    W: ::tokio::io::AsyncWrite,
+{
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.inner.poll_write(cx, buf);
+        if let std::task::Poll::Ready(Ok(len)) = poll {
+            *this.count += len as u64;
+        }
+        poll
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> std::task::Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.inner.poll_write_vectored(cx, bufs);
+        if let std::task::Poll::Ready(Ok(len)) = poll {
+            *this.count += len as u64;
+        }
+        poll
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.project().inner.poll_shutdown(cx)
+    }
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    fs::File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    // directories cannot be opened for syncing on this platform
+    Ok(())
+}
+
+/// The state of an existing file opened in append mode.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AppendInfo {
+    /// The number of records already present in the file.
+    pub num_records: usize,
+    /// The file offset where new records are appended.
+    pub offset: u64,
+    /// The number of bytes of the torn trailing record removed from the file.
+    pub truncated_bytes: u64,
+}
+
+/// Validate an existing file and truncate the torn trailing record for appending.
+///
+/// The file is created if it does not exist. Corruptions other than a torn trailing
+/// record after at least one valid record are rejected, leaving the file untouched. The
+/// positions of existing records are written to the index sidecar if it is requested.
+fn prepare_append(
+    path: &Path,
+    config: &RecordWriterConfig,
+) -> Result<(AppendInfo, Option<Finalizer>, Tracker)> {
+    ensure_argument!(
+        config.compression == Compression::None,
+        "append mode does not support compressed files"
+    );
+    ensure_argument!(
+        !config.durability.atomic,
+        "append mode does not support atomic creation"
+    );
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    let report = crate::verify::verify_reader(io::BufReader::new(&file), &VerifyConfig::default())?;
+
+    let offset = match *report.corruptions.as_slice() {
+        [] => report.stream_len,
+        [range]
+            if range.end == report.stream_len
+                && range.kind == CorruptionKind::Truncated
+                && report.num_records > 0 =>
+        {
+            file.set_len(range.start)?;
+            file.sync_all()?;
+            range.start
+        }
+        [ref range, ..] => {
+            let err = io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "cannot append to {}: corrupted record at offset {}",
+                    path.display(),
+                    range.start
+                ),
+            );
+            return Err(err.into());
+        }
+    };
+
+    let (_, finalizer) = Finalizer::new(path, config)?;
+    let index = finalizer
+        .as_ref()
+        .map(Finalizer::create_index)
+        .transpose()?;
+    let index = match index.flatten() {
+        Some(mut index) => {
+            let reader = BufReader::new(File::open(path)?);
+            for position in crate::indexer::load_reader(reader, Default::default()) {
+                index.push(position?)?;
+            }
+            Some(index)
+        }
+        None => None,
+    };
+
+    let info = AppendInfo {
+        num_records: report.num_records,
+        offset,
+        truncated_bytes: report.stream_len - offset,
+    };
+    Ok((info, finalizer, Tracker::new(offset, index)))
+}
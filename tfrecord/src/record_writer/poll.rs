@@ -0,0 +1,92 @@
+use super::{Finalizer, Tracker};
+use crate::{
+    error::Result,
+    indexer::{IndexWriter, Position},
+    io::poll::{self, PollWrite},
+    record::Record,
+};
+use std::marker::PhantomData;
+
+/// Writer of record `T` to a compressing writer `W`.
+///
+/// It implements the asynchronous record writers of all runtimes.
+#[derive(Debug)]
+pub(crate) struct PollWriter<T, W> {
+    writer: W,
+    finalizer: Option<Finalizer>,
+    tracker: Tracker,
+    buf: Vec<u8>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, W> PollWriter<T, W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            finalizer: None,
+            tracker: Tracker::default(),
+            buf: vec![],
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Set the finalization and the position tracker of the written file.
+    pub(crate) fn with_file(self, finalizer: Option<Finalizer>, tracker: Tracker) -> Self {
+        Self {
+            finalizer,
+            tracker,
+            ..self
+        }
+    }
+
+    pub(crate) fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub(crate) fn tracker(&self) -> &Tracker {
+        &self.tracker
+    }
+
+    /// Take the finalization of the written file and the index file to be completed by it.
+    pub(crate) fn take_finalizer(&mut self) -> Option<(Finalizer, Option<IndexWriter>)> {
+        let finalizer = self.finalizer.take()?;
+        Some((finalizer, self.tracker.take_index()))
+    }
+}
+
+impl<T, W> PollWriter<T, W>
+where
+    T: Record,
+    W: PollWrite,
+{
+    /// Write a record and return its position.
+    pub(crate) async fn send_with_position(&mut self, record: T) -> Result<Position> {
+        let bytes = T::to_bytes(record)?;
+        self.send_bytes(&bytes).await
+    }
+
+    /// Write a record by reference and return its position.
+    pub(crate) async fn send_ref(&mut self, record: &T) -> Result<Position> {
+        self.buf.clear();
+        self.buf.reserve(record.encoded_len().unwrap_or(0));
+        record.encode(&mut self.buf)?;
+        poll::try_write_record_slice(&mut self.writer, &self.buf).await?;
+        self.tracker.push(self.buf.len())
+    }
+
+    /// Write a record from borrowed raw bytes and return its position.
+    pub(crate) async fn send_bytes(&mut self, bytes: &[u8]) -> Result<Position> {
+        poll::try_write_record_slice(&mut self.writer, bytes).await?;
+        self.tracker.push(bytes.len())
+    }
+
+    /// Flush the output stream.
+    pub(crate) async fn flush(&mut self) -> Result<()> {
+        poll::flush(&mut self.writer).await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
-use crate::{error::Result, protobuf::Example, record::Record};
+use super::{AppendInfo, CountWriter, Finalizer, RecordWriterConfig, Tracker, WriterStats};
+use crate::{
+    compression::CompressWriter, error::Result, indexer::Position, protobuf::Example,
+    record::Record,
+};
 use std::{
-    fs::File,
+    fs::{File, OpenOptions},
     io::{BufWriter, Write},
     marker::PhantomData,
     path::Path,
@@ -13,12 +17,21 @@ pub type BytesWriter<W> = RecordWriter<Vec<u8>, W>;
 pub type ExampleWriter<W> = RecordWriter<Example, W>;
 
 /// The record writer.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// If the writer is configured with compression, the compressed stream
+/// is finalized when the writer is dropped. Files created with the
+/// [durability](super::DurabilityConfig) options are only finalized by
+/// [close](RecordWriter::close).
+#[derive(Debug)]
 pub struct RecordWriter<T, W>
 where
     T: Record,
+    W: Write,
 {
-    writer: W,
+    writer: CompressWriter<CountWriter<W>>,
+    finalizer: Option<Finalizer>,
+    tracker: Tracker,
+    buf: Vec<u8>,
     _phantom: PhantomData<T>,
 }
 
@@ -31,8 +44,54 @@ where
     where
         P: AsRef<Path>,
     {
+        Self::create_with_config(path, Default::default())
+    }
+
+    /// Build a writer writing to a new file with custom configuration.
+    pub fn create_with_config<P>(path: P, config: RecordWriterConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let (path, finalizer, tracker) = super::prepare_create(path.as_ref(), &config)?;
         let writer = BufWriter::new(File::create(path)?);
-        Self::from_writer(writer)
+        Ok(Self {
+            finalizer,
+            tracker,
+            ..Self::from_writer_with_config(writer, config)?
+        })
+    }
+
+    /// Build a writer appending to an existing file.
+    ///
+    /// The framing of the existing records is validated and a torn trailing record,
+    /// such as one left by a crashed writer, is truncated. The file is created if it
+    /// does not exist.
+    pub fn open_append<P>(path: P) -> Result<(Self, AppendInfo)>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_append_with_config(path, Default::default())
+    }
+
+    /// Build a writer appending to an existing file with custom configuration.
+    ///
+    /// Compression is not supported in append mode.
+    pub fn open_append_with_config<P>(
+        path: P,
+        config: RecordWriterConfig,
+    ) -> Result<(Self, AppendInfo)>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let (info, finalizer, tracker) = super::prepare_append(path, &config)?;
+        let writer = BufWriter::new(OpenOptions::new().append(true).open(path)?);
+        let writer = Self {
+            finalizer,
+            tracker,
+            ..Self::from_writer_with_config(writer, config)?
+        };
+        Ok((writer, info))
     }
 }
 
@@ -43,8 +102,22 @@ where
 {
     /// Build a writer from a writer with [Write] trait.
     pub fn from_writer(writer: W) -> Result<Self> {
+        Self::from_writer_with_config(writer, Default::default())
+    }
+
+    /// Build a writer from a writer with [Write] trait and custom configuration.
+    pub fn from_writer_with_config(writer: W, config: RecordWriterConfig) -> Result<Self> {
+        let RecordWriterConfig {
+            compression,
+            compression_level,
+            ..
+        } = config;
+
         Ok(Self {
-            writer,
+            writer: CompressWriter::new(CountWriter::new(writer), compression, compression_level)?,
+            finalizer: None,
+            tracker: Tracker::default(),
+            buf: vec![],
             _phantom: PhantomData,
         })
     }
@@ -53,14 +126,72 @@ where
     ///
     /// The method is enabled if the underlying writer implements [Write].
     pub fn send(&mut self, record: T) -> Result<()> {
-        let bytes = T::to_bytes(record)?;
-        crate::io::sync::try_write_record(&mut self.writer, bytes)?;
+        self.send_with_position(record)?;
         Ok(())
     }
 
+    /// Write a record and return its position.
+    ///
+    /// The position is counted in the uncompressed stream. For files opened in
+    /// append mode, it is relative to the start of file. The owned bytes of the record
+    /// are written without copying into the scratch buffer.
+    pub fn send_with_position(&mut self, record: T) -> Result<Position> {
+        let bytes = T::to_bytes(record)?;
+        self.send_bytes(&bytes)
+    }
+
+    /// Write a record by reference and return its position.
+    ///
+    /// The record is encoded into a scratch buffer reused across calls.
+    pub fn send_ref(&mut self, record: &T) -> Result<Position> {
+        self.buf.clear();
+        self.buf.reserve(record.encoded_len().unwrap_or(0));
+        record.encode(&mut self.buf)?;
+        self.send_buf()
+    }
+
+    /// Write a record from borrowed raw bytes and return its position.
+    ///
+    /// The bytes are written as is without copying.
+    pub fn send_bytes(&mut self, bytes: &[u8]) -> Result<Position> {
+        crate::io::sync::try_write_record_slice(&mut self.writer, bytes)?;
+        self.tracker.push(bytes.len())
+    }
+
+    /// Write the record encoded in the scratch buffer.
+    fn send_buf(&mut self) -> Result<Position> {
+        crate::io::sync::try_write_record_slice(&mut self.writer, &self.buf)?;
+        self.tracker.push(self.buf.len())
+    }
+
+    /// Get the running counters.
+    pub fn stats(&self) -> WriterStats {
+        self.tracker.stats(self.writer.get_ref().count())
+    }
+
     /// Flush the output stream.
     pub fn flush(&mut self) -> Result<()> {
         self.writer.flush()?;
         Ok(())
     }
+
+    /// Finalize the output and return the inner writer.
+    ///
+    /// It finalizes the compressed stream if compression is enabled, flushes the
+    /// inner writer, and finalizes the file according to the durability options. The index
+    /// sidecar file is saved if it is configured.
+    pub fn close(self) -> Result<W> {
+        let Self {
+            writer,
+            finalizer,
+            mut tracker,
+            ..
+        } = self;
+        let mut writer = writer.finish()?.into_inner();
+        writer.flush()?;
+        if let Some(finalizer) = finalizer {
+            finalizer.commit(tracker.take_index())?;
+        }
+        Ok(writer)
+    }
 }
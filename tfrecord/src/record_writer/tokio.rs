@@ -0,0 +1,192 @@
+use super::{
+    poll::PollWriter, AppendInfo, CountWriter, Finalizer, RecordWriterConfig, Tracker, WriterStats,
+};
+use crate::{
+    compression::CompressTokioWriter,
+    error::{Error, Result},
+    indexer::Position,
+    io::tokio::TokioIo,
+    protobuf::Example,
+    record::Record,
+};
+use ::tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncWrite, AsyncWriteExt as _, BufWriter},
+    task,
+};
+use futures::{sink, sink::Sink};
+use std::{io, path::Path};
+
+/// Alias to [RecordTokioWriter] which input record type [Vec<u8>](Vec).
+pub type BytesTokioWriter<W> = RecordTokioWriter<Vec<u8>, W>;
+
+/// Alias to [RecordTokioWriter] which input record type [Example].
+pub type ExampleTokioWriter<W> = RecordTokioWriter<Example, W>;
+
+/// The record writer on writers with tokio's [AsyncWrite] trait.
+///
+/// It is the tokio counterpart of [RecordAsyncWriter](super::RecordAsyncWriter).
+/// If the writer is configured with compression, [close](RecordTokioWriter::close)
+/// must be called to finalize the compressed stream. Files created with the
+/// [durability](super::DurabilityConfig) options are also finalized by it.
+#[derive(Debug)]
+pub struct RecordTokioWriter<T, W>
+where
+    T: Record,
+    W: AsyncWrite,
+{
+    writer: PollWriter<T, TokioIo<CompressTokioWriter<CountWriter<W>>>>,
+}
+
+impl<T> RecordTokioWriter<T, BufWriter<File>>
+where
+    T: Record,
+{
+    /// Build a writer writing to a new file using the tokio runtime.
+    pub async fn create<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::create_with_config(path, Default::default()).await
+    }
+
+    /// Build a writer writing to a new file with custom configuration using the tokio runtime.
+    pub async fn create_with_config<P>(path: P, config: RecordWriterConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let (path, finalizer, tracker) = super::prepare_create(path.as_ref(), &config)?;
+        let writer = BufWriter::new(File::create(path).await?);
+        let writer = Self::from_writer_with_config(writer, config)?;
+        Ok(writer.with_file(finalizer, tracker))
+    }
+
+    /// Build a writer appending to an existing file using the tokio runtime.
+    ///
+    /// See [open_append](crate::RecordWriter::open_append) for details.
+    pub async fn open_append<P>(path: P) -> Result<(Self, AppendInfo)>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_append_with_config(path, Default::default()).await
+    }
+
+    /// Build a writer appending to an existing file with custom configuration using the tokio runtime.
+    ///
+    /// Compression is not supported in append mode.
+    pub async fn open_append_with_config<P>(
+        path: P,
+        config: RecordWriterConfig,
+    ) -> Result<(Self, AppendInfo)>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_owned();
+        let (info, finalizer, tracker) = {
+            let path = path.clone();
+            let config = config.clone();
+            task::spawn_blocking(move || super::prepare_append(&path, &config))
+                .await
+                .map_err(io::Error::from)??
+        };
+        let writer = BufWriter::new(OpenOptions::new().append(true).open(path).await?);
+        let writer = Self::from_writer_with_config(writer, config)?;
+        Ok((writer.with_file(finalizer, tracker), info))
+    }
+}
+
+impl<T, W> RecordTokioWriter<T, W>
+where
+    T: Record,
+    W: AsyncWrite + Unpin,
+{
+    /// Build a writer from a writer with tokio's [AsyncWrite] trait.
+    pub fn from_writer(writer: W) -> Result<Self> {
+        Self::from_writer_with_config(writer, Default::default())
+    }
+
+    /// Build a writer from a writer with tokio's [AsyncWrite] trait and custom configuration.
+    pub fn from_writer_with_config(writer: W, config: RecordWriterConfig) -> Result<Self> {
+        let RecordWriterConfig {
+            compression,
+            compression_level,
+            ..
+        } = config;
+
+        let writer =
+            CompressTokioWriter::new(CountWriter::new(writer), compression, compression_level)?;
+        Ok(Self {
+            writer: PollWriter::new(TokioIo(writer)),
+        })
+    }
+
+    /// Set the finalization and the position tracker of the written file.
+    fn with_file(self, finalizer: Option<Finalizer>, tracker: Tracker) -> Self {
+        Self {
+            writer: self.writer.with_file(finalizer, tracker),
+        }
+    }
+
+    /// Write a record.
+    pub async fn send(&mut self, record: T) -> Result<()> {
+        self.send_with_position(record).await?;
+        Ok(())
+    }
+
+    /// Write a record and return its position.
+    ///
+    /// The position is counted in the uncompressed stream. For files opened in
+    /// append mode, it is relative to the start of file. The owned bytes of the record
+    /// are written without copying into the scratch buffer.
+    pub async fn send_with_position(&mut self, record: T) -> Result<Position> {
+        self.writer.send_with_position(record).await
+    }
+
+    /// Write a record by reference and return its position.
+    ///
+    /// The record is encoded into a scratch buffer reused across calls.
+    pub async fn send_ref(&mut self, record: &T) -> Result<Position> {
+        self.writer.send_ref(record).await
+    }
+
+    /// Write a record from borrowed raw bytes and return its position.
+    ///
+    /// The bytes are written as is without copying.
+    pub async fn send_bytes(&mut self, bytes: &[u8]) -> Result<Position> {
+        self.writer.send_bytes(bytes).await
+    }
+
+    /// Get the running counters.
+    pub fn stats(&self) -> WriterStats {
+        let count = self.writer.get_ref().0.get_ref().count();
+        self.writer.tracker().stats(count)
+    }
+
+    /// Flush the output stream.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await
+    }
+
+    /// Shut down the inner writer.
+    ///
+    /// It finalizes the compressed stream if compression is enabled, and finalizes the
+    /// file according to the durability options. The index sidecar file is saved if it
+    /// is configured.
+    pub async fn close(&mut self) -> Result<()> {
+        self.writer.get_mut().0.shutdown().await?;
+        if let Some((finalizer, index)) = self.writer.take_finalizer() {
+            task::spawn_blocking(move || finalizer.commit(index))
+                .await
+                .map_err(io::Error::from)??;
+        }
+        Ok(())
+    }
+
+    /// Convert into a [Sink].
+    pub fn into_sink(self) -> impl Sink<T, Error = Error> {
+        sink::unfold(self, |mut writer, record| async move {
+            writer.send(record).await?;
+            Ok(writer)
+        })
+    }
+}
@@ -0,0 +1,209 @@
+//! Repair of damaged TFRecord files.
+//!
+//! A writer that crashes in the middle of a record leaves a torn record at the end of
+//! file. The [truncate_file] function cuts the file right after the last valid record,
+//! while [salvage_file] copies every valid record to a new file, skipping corrupted spans.
+//!
+//! ```rust,no_run
+//! use tfrecord::repair;
+//!
+//! let report = repair::truncate_file("events.tfrecord", Default::default())?;
+//! println!("dropped {} bytes", report.dropped_bytes());
+//! # Ok::<_, tfrecord::Error>(())
+//! ```
+
+use crate::{
+    compression::{Compression, DecompressReader},
+    error::{ensure_argument, Result},
+    io::{sync::RecoveryReader, CorruptedRange},
+    record_writer::{BytesWriter, RecordWriterConfig},
+};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, prelude::*, BufReader, BufWriter},
+    path::Path,
+};
+
+/// Configuration for repair functions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct RepairConfig {
+    /// The compression type of the input data.
+    ///
+    /// Only uncompressed files can be truncated in place.
+    pub compression: Compression,
+    /// Records longer than the limit are treated as corrupted.
+    pub max_record_len: Option<usize>,
+    /// The writer configuration of the salvaged output.
+    pub writer: RecordWriterConfig,
+}
+
+/// The summary of a repair.
+///
+/// Offsets are positions in the uncompressed input stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct RepairReport {
+    /// The number of records kept.
+    pub num_records: usize,
+    /// The byte ranges dropped from the input in stream order.
+    pub dropped: Vec<CorruptedRange>,
+    /// The length of the uncompressed input stream.
+    pub input_len: u64,
+    /// The length of the repaired file.
+    pub output_len: u64,
+}
+
+impl RepairReport {
+    /// Check if nothing is dropped.
+    pub fn is_clean(&self) -> bool {
+        self.dropped.is_empty()
+    }
+
+    /// Get the total number of dropped bytes.
+    pub fn dropped_bytes(&self) -> u64 {
+        self.dropped
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+}
+
+/// Truncate a file right after the last valid record before the first corruption.
+///
+/// Valid records after the first corruption are dropped as well. Use [salvage_file] to
+/// keep them. The file is left untouched if no corruption is found.
+///
+/// It refuses to truncate a file without a valid record before the first corruption,
+/// such as a compressed file or a file not in TFRecord format, which would be emptied.
+pub fn truncate_file<P>(path: P, config: RepairConfig) -> Result<RepairReport>
+where
+    P: AsRef<Path>,
+{
+    let RepairConfig {
+        compression,
+        max_record_len,
+        ..
+    } = config;
+    ensure_argument!(
+        compression == Compression::None,
+        "compressed files cannot be truncated in place"
+    );
+
+    let path = path.as_ref();
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let input_len = file.metadata()?.len();
+
+    let mut reader = RecoveryReader::new(BufReader::new(&mut file), max_record_len);
+    let mut buf = vec![];
+    let mut num_records = 0;
+    let mut first_corruption = None;
+
+    while first_corruption.is_none() {
+        let offset = reader.try_read_record_into(&mut buf, |range| {
+            first_corruption.get_or_insert(range);
+        })?;
+        if offset.is_none() {
+            break;
+        }
+        if first_corruption.is_none() {
+            num_records += 1;
+        }
+    }
+    drop(reader);
+
+    let dropped: Vec<_> = first_corruption
+        .map(|range| CorruptedRange {
+            end: input_len,
+            ..range
+        })
+        .into_iter()
+        .collect();
+
+    let output_len = match dropped.first() {
+        Some(range) if num_records == 0 => {
+            let err = io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "refuse to truncate {}: no valid record before the corruption at offset {}",
+                    path.display(),
+                    range.start
+                ),
+            );
+            return Err(err.into());
+        }
+        Some(range) => {
+            file.set_len(range.start)?;
+            file.sync_all()?;
+            range.start
+        }
+        None => input_len,
+    };
+
+    Ok(RepairReport {
+        num_records,
+        dropped,
+        input_len,
+        output_len,
+    })
+}
+
+/// Copy all valid records from a file to a new file, skipping corrupted spans.
+///
+/// The output must be a different file from the input.
+pub fn salvage_file<P, Q>(input: P, output: Q, config: RepairConfig) -> Result<RepairReport>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let input = input.as_ref();
+    let output = output.as_ref();
+    // the output is truncated on creation before the input is read
+    if output.exists() {
+        ensure_argument!(
+            fs::canonicalize(input)? != fs::canonicalize(output)?,
+            "the output file {} is the input file",
+            output.display()
+        );
+    }
+
+    let reader = BufReader::new(File::open(input)?);
+    let writer = BufWriter::new(File::create(output)?);
+    let (report, writer) = salvage_reader(reader, writer, config)?;
+
+    let file = writer.into_inner().map_err(|err| err.into_error())?;
+    file.sync_all()?;
+    Ok(RepairReport {
+        output_len: file.metadata()?.len(),
+        ..report
+    })
+}
+
+/// Copy all valid records from a reader to a writer, skipping corrupted spans.
+///
+/// It returns the report and the flushed writer. The `output_len` of the report is the
+/// length of the uncompressed output stream.
+pub fn salvage_reader<R, W>(reader: R, writer: W, config: RepairConfig) -> Result<(RepairReport, W)>
+where
+    R: Read,
+    W: Write,
+{
+    let RepairConfig {
+        compression,
+        max_record_len,
+        writer: writer_config,
+    } = config;
+
+    let reader = DecompressReader::new(reader, compression);
+    let mut reader = RecoveryReader::new(reader, max_record_len);
+    let mut writer = BytesWriter::from_writer_with_config(writer, writer_config)?;
+    let mut report = RepairReport::default();
+
+    while let Some(bytes) = reader.try_read_record(|range| report.dropped.push(range))? {
+        report.num_records += 1;
+        report.output_len += crate::io::record_size(bytes.len());
+        writer.send(bytes)?;
+    }
+    report.input_len = reader.offset();
+
+    let writer = writer.close()?;
+    Ok((report, writer))
+}
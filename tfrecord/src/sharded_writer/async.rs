@@ -0,0 +1,105 @@
+use super::{Selection, ShardManifest, ShardState, ShardedWriterConfig};
+use crate::{
+    error::Result,
+    record::Record,
+    record_writer::{BytesAsyncWriter, RecordWriterConfig},
+};
+use async_std::{fs::File, io::BufWriter, path::Path};
+use std::marker::PhantomData;
+
+/// The asynchronous writer that splits records of type `T` into shard files.
+///
+/// The shards written with a rotating [policy](super::ShardPolicy) are named with a
+/// temporary suffix until [close](ShardedAsyncWriter::close) is called, which renames them
+/// to the final `-NNNNN-of-NNNNN` names.
+#[derive(Debug)]
+pub struct ShardedAsyncWriter<T>
+where
+    T: Record,
+{
+    state: ShardState,
+    writers: Vec<Option<BytesAsyncWriter<BufWriter<File>>>>,
+    config: RecordWriterConfig,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ShardedAsyncWriter<T>
+where
+    T: Record,
+{
+    /// Build a writer writing shards to files specified by a path prefix.
+    pub async fn create<P>(prefix: P, config: ShardedWriterConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let ShardedWriterConfig {
+            policy,
+            suffix,
+            record_writer: config,
+        } = config;
+        let state = ShardState::new(prefix.as_ref().as_ref(), suffix, policy)?;
+        let mut writers = Vec::with_capacity(state.shards.len());
+        for shard in &state.shards {
+            let writer = BytesAsyncWriter::create_with_config(&shard.path, config.clone()).await?;
+            writers.push(Some(writer));
+        }
+
+        Ok(Self {
+            state,
+            writers,
+            config,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Write a record asynchronously.
+    pub async fn send(&mut self, record: T) -> Result<()> {
+        let bytes = T::to_bytes(record)?;
+        let size = crate::io::record_size(bytes.len());
+
+        let index = match self.state.select(size) {
+            Selection::Shard(index) => index,
+            Selection::NewShard(path) => {
+                // the shard is added after its file is created and the previous shard is
+                // finalized last, so a failure leaves the writers in sync with the shards
+                let writer =
+                    BytesAsyncWriter::create_with_config(&path, self.config.clone()).await?;
+                let prev = self.writers.last_mut().and_then(Option::take);
+                self.writers.push(Some(writer));
+                let index = self.state.push_shard(path);
+                if let Some(mut prev) = prev {
+                    prev.close().await?;
+                }
+                index
+            }
+        };
+
+        let writer = self.writers[index].as_mut().unwrap();
+        writer.send_bytes(&bytes).await?;
+        self.state.count(index, size);
+        Ok(())
+    }
+
+    /// Flush the output streams of open shards asynchronously.
+    pub async fn flush(&mut self) -> Result<()> {
+        for writer in self.writers.iter_mut().flatten() {
+            writer.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Finalize all shards and return the manifest.
+    pub async fn close(self) -> Result<ShardManifest> {
+        for mut writer in self.writers.into_iter().flatten() {
+            writer.close().await?;
+        }
+        let (renames, manifest) = self.state.finish();
+        for (from, to) in renames {
+            async_std::fs::rename(&from, &to).await?;
+            if let Some(format) = self.config.index {
+                async_std::fs::rename(format.sidecar_path(&from), format.sidecar_path(&to)).await?;
+            }
+        }
+        Ok(manifest)
+    }
+}
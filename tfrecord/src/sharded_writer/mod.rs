@@ -0,0 +1,243 @@
+//! Writers that split records into multiple shard files.
+//!
+//! The shards are named in TensorFlow style, for example `train-00000-of-00064.tfrecord`,
+//! where `train` is the path prefix and `.tfrecord` is the file name suffix.
+//!
+//! | Writer                                           | Record type                     |
+//! | -------------------------------------------------|---------------------------------|
+//! | [ShardedWriter](sync::ShardedWriter)             | Type that implements [Record](crate::record::Record) |
+//! | [ShardedAsyncWriter](async::ShardedAsyncWriter)  | Type that implements [Record](crate::record::Record) |
+//!
+//! The `ShardedAsyncWriter` creates and renames the shard files with async-std, so it needs the
+//! `async` feature and is not available with the `tokio` feature alone.
+
+#[cfg(feature = "async")]
+mod r#async;
+#[cfg(feature = "async")]
+pub use r#async::*;
+
+mod sync;
+pub use sync::*;
+
+use crate::{
+    error::{ensure_argument, Result},
+    record_writer::RecordWriterConfig,
+};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+/// The policy to distribute records across shards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShardPolicy {
+    /// Start a new shard after the given number of records are written.
+    MaxRecords(usize),
+    /// Start a new shard if the record would make the shard exceed the given number of bytes.
+    ///
+    /// The size is measured on the uncompressed record framing. A record larger than
+    /// the limit is written to a shard of its own.
+    MaxBytes(u64),
+    /// Distribute records round-robin across a fixed number of shards.
+    RoundRobin(usize),
+}
+
+impl Default for ShardPolicy {
+    fn default() -> Self {
+        Self::MaxBytes(128 * 1024 * 1024)
+    }
+}
+
+/// Configuration for sharded writers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShardedWriterConfig {
+    /// The policy to distribute records across shards.
+    pub policy: ShardPolicy,
+    /// The file name suffix following the shard numbering.
+    pub suffix: String,
+    /// The configuration of the writer for each shard.
+    pub record_writer: RecordWriterConfig,
+}
+
+impl Default for ShardedWriterConfig {
+    fn default() -> Self {
+        Self {
+            policy: Default::default(),
+            suffix: ".tfrecord".into(),
+            record_writer: Default::default(),
+        }
+    }
+}
+
+/// The summary of a finished shard.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShardInfo {
+    /// The path to the shard file.
+    pub path: PathBuf,
+    /// The number of records in the shard.
+    pub num_records: usize,
+    /// The number of uncompressed bytes in the shard.
+    pub num_bytes: u64,
+}
+
+/// The list of shards written by a sharded writer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ShardManifest {
+    pub shards: Vec<ShardInfo>,
+}
+
+impl ShardManifest {
+    /// Get the total number of records.
+    pub fn num_records(&self) -> usize {
+        self.shards.iter().map(|shard| shard.num_records).sum()
+    }
+
+    /// Iterate over the shard paths.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.shards.iter().map(|shard| shard.path.as_path())
+    }
+}
+
+/// Build the TensorFlow-style path of a shard.
+pub fn shard_path<P>(prefix: P, index: usize, num_shards: usize, suffix: &str) -> PathBuf
+where
+    P: AsRef<Path>,
+{
+    let mut path = OsString::from(prefix.as_ref());
+    path.push(format!("-{index:05}-of-{num_shards:05}{suffix}"));
+    path.into()
+}
+
+/// The path of a shard which total number of shards is not known yet.
+fn temporary_shard_path(prefix: &Path, index: usize, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(prefix);
+    path.push(format!("-{index:05}{suffix}.tmp"));
+    path.into()
+}
+
+/// The shard selected for the next record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selection {
+    /// Write to the existing shard at the index.
+    Shard(usize),
+    /// Start a new shard at the path and finalize the last one.
+    NewShard(PathBuf),
+}
+
+/// Shard bookkeeping shared by sync and async sharded writers.
+#[derive(Debug)]
+struct ShardState {
+    prefix: PathBuf,
+    suffix: String,
+    policy: ShardPolicy,
+    shards: Vec<ShardInfo>,
+    cursor: usize,
+}
+
+impl ShardState {
+    fn new(prefix: &Path, suffix: String, policy: ShardPolicy) -> Result<Self> {
+        let (num_shards, is_temporary) = match policy {
+            ShardPolicy::MaxRecords(max) => {
+                ensure_argument!(max > 0, "the maximum number of records must be positive");
+                (1, true)
+            }
+            ShardPolicy::MaxBytes(max) => {
+                ensure_argument!(max > 0, "the maximum number of bytes must be positive");
+                (1, true)
+            }
+            ShardPolicy::RoundRobin(num_shards) => {
+                ensure_argument!(num_shards > 0, "the number of shards must be positive");
+                (num_shards, false)
+            }
+        };
+
+        let shards = (0..num_shards)
+            .map(|index| {
+                let path = if is_temporary {
+                    temporary_shard_path(prefix, index, &suffix)
+                } else {
+                    shard_path(prefix, index, num_shards, &suffix)
+                };
+                ShardInfo {
+                    path,
+                    num_records: 0,
+                    num_bytes: 0,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            prefix: prefix.to_owned(),
+            suffix,
+            policy,
+            shards,
+            cursor: 0,
+        })
+    }
+
+    /// Select the shard for a record of `size` bytes.
+    ///
+    /// The state is left unchanged, so the selection is repeated if writing the record fails.
+    fn select(&self, size: u64) -> Selection {
+        let last = self.shards.last().unwrap();
+        let rotate = match self.policy {
+            ShardPolicy::RoundRobin(_) => return Selection::Shard(self.cursor),
+            ShardPolicy::MaxRecords(max) => last.num_records >= max,
+            ShardPolicy::MaxBytes(max) => last.num_records > 0 && last.num_bytes + size > max,
+        };
+
+        if rotate {
+            let path = temporary_shard_path(&self.prefix, self.shards.len(), &self.suffix);
+            Selection::NewShard(path)
+        } else {
+            Selection::Shard(self.shards.len() - 1)
+        }
+    }
+
+    /// Add a shard whose file is created, returning its index.
+    fn push_shard(&mut self, path: PathBuf) -> usize {
+        self.shards.push(ShardInfo {
+            path,
+            num_records: 0,
+            num_bytes: 0,
+        });
+        self.shards.len() - 1
+    }
+
+    /// Count a record of `size` bytes written to the shard.
+    fn count(&mut self, index: usize, size: u64) {
+        let shard = &mut self.shards[index];
+        shard.num_records += 1;
+        shard.num_bytes += size;
+        if let ShardPolicy::RoundRobin(num_shards) = self.policy {
+            self.cursor = (index + 1) % num_shards;
+        }
+    }
+
+    /// Assign the final shard names, returning the files to be renamed and the manifest.
+    fn finish(self) -> (Vec<(PathBuf, PathBuf)>, ShardManifest) {
+        let Self {
+            prefix,
+            suffix,
+            policy,
+            mut shards,
+            ..
+        } = self;
+
+        let renames = match policy {
+            ShardPolicy::RoundRobin(_) => vec![],
+            ShardPolicy::MaxRecords(_) | ShardPolicy::MaxBytes(_) => {
+                let num_shards = shards.len();
+                shards
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(index, shard)| {
+                        let path = shard_path(&prefix, index, num_shards, &suffix);
+                        (std::mem::replace(&mut shard.path, path.clone()), path)
+                    })
+                    .collect()
+            }
+        };
+        (renames, ShardManifest { shards })
+    }
+}
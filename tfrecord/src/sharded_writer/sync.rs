@@ -0,0 +1,108 @@
+use super::{Selection, ShardManifest, ShardState, ShardedWriterConfig};
+use crate::{
+    error::Result,
+    record::Record,
+    record_writer::{BytesWriter, RecordWriterConfig},
+};
+use std::{fs::File, io::BufWriter, marker::PhantomData, path::Path};
+
+/// The writer that splits records of type `T` into shard files.
+///
+/// The shards written with a rotating [policy](super::ShardPolicy) are named with a
+/// temporary suffix until [close](ShardedWriter::close) is called, which renames them
+/// to the final `-NNNNN-of-NNNNN` names.
+#[derive(Debug)]
+pub struct ShardedWriter<T>
+where
+    T: Record,
+{
+    state: ShardState,
+    writers: Vec<Option<BytesWriter<BufWriter<File>>>>,
+    config: RecordWriterConfig,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ShardedWriter<T>
+where
+    T: Record,
+{
+    /// Build a writer writing shards to files specified by a path prefix.
+    pub fn create<P>(prefix: P, config: ShardedWriterConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let ShardedWriterConfig {
+            policy,
+            suffix,
+            record_writer: config,
+        } = config;
+        let state = ShardState::new(prefix.as_ref(), suffix, policy)?;
+        let writers = state
+            .shards
+            .iter()
+            .map(|shard| {
+                Ok(Some(BytesWriter::create_with_config(
+                    &shard.path,
+                    config.clone(),
+                )?))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            state,
+            writers,
+            config,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Write a record.
+    pub fn send(&mut self, record: T) -> Result<()> {
+        let bytes = T::to_bytes(record)?;
+        let size = crate::io::record_size(bytes.len());
+
+        let index = match self.state.select(size) {
+            Selection::Shard(index) => index,
+            Selection::NewShard(path) => {
+                // the shard is added after its file is created and the previous shard is
+                // finalized last, so a failure leaves the writers in sync with the shards
+                let writer = BytesWriter::create_with_config(&path, self.config.clone())?;
+                let prev = self.writers.last_mut().and_then(Option::take);
+                self.writers.push(Some(writer));
+                let index = self.state.push_shard(path);
+                if let Some(prev) = prev {
+                    prev.close()?;
+                }
+                index
+            }
+        };
+
+        let writer = self.writers[index].as_mut().unwrap();
+        writer.send_bytes(&bytes)?;
+        self.state.count(index, size);
+        Ok(())
+    }
+
+    /// Flush the output streams of open shards.
+    pub fn flush(&mut self) -> Result<()> {
+        for writer in self.writers.iter_mut().flatten() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Finalize all shards and return the manifest.
+    pub fn close(self) -> Result<ShardManifest> {
+        for writer in self.writers.into_iter().flatten() {
+            writer.close()?;
+        }
+        let (renames, manifest) = self.state.finish();
+        for (from, to) in renames {
+            std::fs::rename(&from, &to)?;
+            if let Some(format) = self.config.index {
+                std::fs::rename(format.sidecar_path(&from), format.sidecar_path(&to))?;
+            }
+        }
+        Ok(manifest)
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf, MAIN_SEPARATOR},
 };
 
-use crate::error::Error;
+use crate::error::{ChecksumKind, Error};
 use crc::Crc;
 
 pub fn checksum(buf: &[u8]) -> u32 {
@@ -13,12 +13,16 @@ pub fn checksum(buf: &[u8]) -> u32 {
     ((cksum >> 15) | (cksum << 17)).wrapping_add(0xa282ead8u32)
 }
 
-pub fn verify_checksum(buf: &[u8], expect: u32) -> Result<(), Error> {
+pub fn verify_checksum(buf: &[u8], expect: u32, kind: ChecksumKind) -> Result<(), Error> {
     let found = checksum(buf);
     if expect == found {
         Ok(())
     } else {
-        Err(Error::ChecksumMismatch { expect, found })
+        Err(Error::ChecksumMismatch {
+            kind,
+            expect,
+            found,
+        })
     }
 }
 
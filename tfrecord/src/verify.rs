@@ -0,0 +1,183 @@
+//! Integrity verification of TFRecord files.
+//!
+//! The verification scans all records, checks the length and data checksums, and
+//! resynchronizes after corrupted bytes so that every corruption point is reported.
+//!
+//! ```rust,no_run
+//! use tfrecord::verify;
+//!
+//! for report in verify::verify_pattern("data/train@64", Default::default())? {
+//!     if !report.is_ok() {
+//!         println!("{:?}: {:?}", report.path, report.corruptions);
+//!     }
+//! }
+//! # Ok::<_, tfrecord::Error>(())
+//! ```
+
+use crate::{
+    compression::{Compression, DecompressReader},
+    error::Result,
+    io::{sync::RecoveryReader, CorruptedRange},
+};
+use std::{
+    fs::File,
+    io::{prelude::*, BufReader},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// Configuration for verification functions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifyConfig {
+    /// The compression type of the input data.
+    pub compression: Compression,
+    /// Records longer than the limit are reported as corrupted.
+    ///
+    /// It defaults to [DEFAULT_MAX_RECORD_LEN](crate::io::DEFAULT_MAX_RECORD_LEN), and is not
+    /// bounded if `None`.
+    pub max_record_len: Option<usize>,
+    /// The number of threads to verify multiple files. It defaults to the available parallelism.
+    pub num_threads: Option<usize>,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            compression: Compression::None,
+            max_record_len: Some(crate::io::DEFAULT_MAX_RECORD_LEN),
+            num_threads: None,
+        }
+    }
+}
+
+/// The verification result of a file or reader.
+///
+/// Offsets are positions in the uncompressed stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct VerifyReport {
+    /// The path to the file, or `None` if a reader is verified.
+    pub path: Option<PathBuf>,
+    /// The number of valid records.
+    pub num_records: usize,
+    /// The total payload bytes of valid records.
+    pub total_bytes: u64,
+    /// The length of the shortest valid record.
+    pub min_record_len: Option<usize>,
+    /// The length of the longest valid record.
+    pub max_record_len: Option<usize>,
+    /// The corrupted byte ranges in stream order.
+    pub corruptions: Vec<CorruptedRange>,
+    /// The length of the uncompressed stream.
+    pub stream_len: u64,
+    /// Whether the stream ends right after a valid record, or is empty.
+    pub clean_end: bool,
+}
+
+impl VerifyReport {
+    /// Check if no corruption is found.
+    pub fn is_ok(&self) -> bool {
+        self.corruptions.is_empty()
+    }
+
+    /// Get the first corrupted byte range.
+    pub fn first_corruption(&self) -> Option<&CorruptedRange> {
+        self.corruptions.first()
+    }
+
+    /// Get the mean payload length of valid records.
+    pub fn mean_record_len(&self) -> Option<f64> {
+        (self.num_records > 0).then(|| self.total_bytes as f64 / self.num_records as f64)
+    }
+}
+
+/// Verify records from a reader.
+pub fn verify_reader<R>(reader: R, config: &VerifyConfig) -> Result<VerifyReport>
+where
+    R: Read,
+{
+    let reader = DecompressReader::new(reader, config.compression);
+    let mut reader = RecoveryReader::new(reader, config.max_record_len);
+    let mut report = VerifyReport::default();
+    let mut buf = vec![];
+
+    while reader
+        .try_read_record_into(&mut buf, |range| report.corruptions.push(range))?
+        .is_some()
+    {
+        let len = buf.len();
+        report.num_records += 1;
+        report.total_bytes += len as u64;
+        report.min_record_len = Some(report.min_record_len.map_or(len, |min| min.min(len)));
+        report.max_record_len = Some(report.max_record_len.map_or(len, |max| max.max(len)));
+    }
+
+    report.stream_len = reader.offset();
+    report.clean_end = report.corruptions.last().map(|range| range.end) != Some(report.stream_len);
+    Ok(report)
+}
+
+/// Verify records in a file.
+pub fn verify_file<P>(path: P, config: &VerifyConfig) -> Result<VerifyReport>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let reader = BufReader::new(File::open(path)?);
+    let report = verify_reader(reader, config)?;
+    Ok(VerifyReport {
+        path: Some(path.to_owned()),
+        ..report
+    })
+}
+
+/// Verify records in multiple files in parallel.
+///
+/// The reports are returned in the order of the given paths.
+pub fn verify_paths<I, P>(paths: I, config: VerifyConfig) -> Result<Vec<VerifyReport>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .map(|path| path.as_ref().to_owned())
+        .collect();
+    let num_threads = config
+        .num_threads
+        .or_else(|| thread::available_parallelism().ok().map(|num| num.get()))
+        .unwrap_or(1)
+        .clamp(1, paths.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let results: Vec<_> = paths.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..num_threads {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let result = verify_file(path, &config);
+                *results[index].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap().unwrap())
+        .collect()
+}
+
+/// Verify records in files specified by a glob pattern or shard spec in parallel.
+///
+/// See [discovery](crate::discovery) for the accepted patterns.
+pub fn verify_pattern(pattern: &str, config: VerifyConfig) -> Result<Vec<VerifyReport>> {
+    let paths = crate::discovery::find_files(pattern)?;
+    verify_paths(paths, config)
+}
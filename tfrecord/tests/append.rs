@@ -0,0 +1,109 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{BytesIter, BytesWriter, Compression, RecordWriterConfig};
+
+fn read_all(path: &std::path::Path) -> Result<Vec<Vec<u8>>> {
+    let records: Vec<_> = BytesIter::open(path, Default::default())?.collect::<Result<_, _>>()?;
+    Ok(records)
+}
+
+#[test]
+fn append_test() -> Result<()> {
+    let path = DATA_DIR.join("append.tfrecord");
+    let _ = fs::remove_file(&path);
+
+    // a missing file is created
+    {
+        let (mut writer, info) = BytesWriter::open_append(&path)?;
+        ensure!(info.num_records == 0 && info.offset == 0 && info.truncated_bytes == 0);
+        writer.send(vec![0; 10])?;
+        writer.send(vec![1; 10])?;
+        writer.flush()?;
+    }
+
+    // resume a clean file
+    {
+        let (mut writer, info) = BytesWriter::open_append(&path)?;
+        ensure!(info.num_records == 2 && info.offset == 52 && info.truncated_bytes == 0);
+        writer.send(vec![2; 10])?;
+        writer.flush()?;
+    }
+    ensure!(read_all(&path)? == [vec![0; 10], vec![1; 10], vec![2; 10]]);
+
+    // resume a file with a torn trailing record
+    {
+        let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+        BytesWriter::from_writer(&mut file)?.send(vec![9; 10])?;
+        file.set_len(78 + 20)?;
+    }
+    {
+        let (mut writer, info) = BytesWriter::open_append(&path)?;
+        ensure!(info.num_records == 3 && info.offset == 78 && info.truncated_bytes == 20);
+        writer.send(vec![3; 10])?;
+        writer.flush()?;
+    }
+    ensure!(read_all(&path)? == [vec![0; 10], vec![1; 10], vec![2; 10], vec![3; 10]]);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn append_rejects_corrupted_file_test() -> Result<()> {
+    let path = DATA_DIR.join("append_corrupted.tfrecord");
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for index in 0..3 {
+            writer.send(vec![index; 10])?;
+        }
+        writer.flush()?;
+    }
+    let mut bytes = fs::read(&path)?;
+    bytes[26 + 12] ^= 0xff;
+    fs::write(&path, &bytes)?;
+
+    ensure!(BytesWriter::open_append(&path).is_err());
+    ensure!(fs::read(&path)? == bytes);
+
+    let config = RecordWriterConfig {
+        compression: Compression::Gzip,
+        ..Default::default()
+    };
+    ensure!(BytesWriter::open_append_with_config(&path, config).is_err());
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn append_rejects_foreign_file_test() -> Result<()> {
+    // a compressed file
+    let path = DATA_DIR.join("append_foreign.tfrecord.gz");
+    {
+        let config = RecordWriterConfig {
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let mut writer = BytesWriter::create_with_config(&path, config)?;
+        for index in 0..10 {
+            writer.send(vec![index; 64])?;
+        }
+        writer.close()?;
+    }
+    let bytes = fs::read(&path)?;
+    ensure!(BytesWriter::open_append(&path).is_err());
+    ensure!(fs::read(&path)? == bytes);
+    fs::remove_file(&path)?;
+
+    // a file which is not in TFRecord format
+    let path = DATA_DIR.join("append_foreign.txt");
+    let bytes = b"this is not a record file\n".repeat(10);
+    fs::write(&path, &bytes)?;
+    ensure!(BytesWriter::open_append(&path).is_err());
+    ensure!(fs::read(&path)? == bytes);
+    fs::remove_file(&path)?;
+
+    Ok(())
+}
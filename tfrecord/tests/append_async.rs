@@ -0,0 +1,40 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::TryStreamExt as _;
+use std::fs;
+use tfrecord::{BytesAsyncWriter, BytesStream};
+
+#[async_std::test]
+async fn async_append_test() -> Result<()> {
+    let path = DATA_DIR.join("append_async.tfrecord");
+    {
+        let mut writer = BytesAsyncWriter::create(&path).await?;
+        writer.send(vec![0; 10]).await?;
+        writer.send(vec![1; 10]).await?;
+        writer.close().await?;
+    }
+    let len = fs::metadata(&path)?.len();
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&path)?
+        .set_len(len - 3)?;
+
+    {
+        let (mut writer, info) = BytesAsyncWriter::open_append(&path).await?;
+        ensure!(info.num_records == 1 && info.offset == 26 && info.truncated_bytes == 23);
+        writer.send(vec![2; 10]).await?;
+        writer.close().await?;
+    }
+
+    let records: Vec<_> = BytesStream::open(&path, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    ensure!(records == [vec![0; 10], vec![2; 10]]);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
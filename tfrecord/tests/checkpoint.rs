@@ -0,0 +1,131 @@
+pub mod common;
+
+use common::*;
+use std::{fs, path::PathBuf};
+use tfrecord::{
+    BytesWriter, ChainedRecordIter, Compression, ReaderCheckpoint, RecordReaderConfig,
+    RecordWriterConfig,
+};
+
+fn write_files(name: &str, compression: Compression) -> Result<(Vec<PathBuf>, Vec<Vec<u8>>)> {
+    let mut paths = vec![];
+    let mut records = vec![];
+    // the second file is empty
+    for (file_index, num_records) in [4, 0, 3].into_iter().enumerate() {
+        let path = DATA_DIR.join(format!("{name}-{file_index}.tfrecord"));
+        let config = RecordWriterConfig {
+            compression,
+            ..Default::default()
+        };
+        let mut writer = BytesWriter::create_with_config(&path, config)?;
+        for index in 0..num_records {
+            let record = vec![file_index as u8; index * 5 + 1];
+            writer.send_bytes(&record)?;
+            records.push(record);
+        }
+        writer.close()?;
+        paths.push(path);
+    }
+    Ok((paths, records))
+}
+
+fn resume_test(name: &str, compression: Compression) -> Result<()> {
+    let (paths, records) = write_files(name, compression)?;
+    let config = RecordReaderConfig {
+        compression,
+        ..Default::default()
+    };
+
+    for num_consumed in 0..=records.len() {
+        let mut iter = ChainedRecordIter::<Vec<u8>>::from_paths(&paths, config.clone());
+        let mut output: Vec<_> = iter.by_ref().take(num_consumed).collect::<Result<_, _>>()?;
+        let checkpoint = iter.checkpoint();
+        ensure!(checkpoint.num_records == num_consumed as u64);
+
+        let iter =
+            ChainedRecordIter::<Vec<u8>>::from_checkpoint(&paths, config.clone(), checkpoint)?;
+        output.extend(iter.collect::<Result<Vec<_>, _>>()?);
+        ensure!(output == records);
+    }
+
+    // all files are consumed
+    let mut iter = ChainedRecordIter::<Vec<u8>>::from_paths(&paths, config.clone());
+    iter.by_ref().for_each(drop);
+    let checkpoint = iter.checkpoint();
+    ensure!(checkpoint.file_index == paths.len());
+    ensure!(checkpoint.path.is_none());
+    let mut iter =
+        ChainedRecordIter::<Vec<u8>>::from_checkpoint(&paths, config.clone(), checkpoint)?;
+    ensure!(iter.next().is_none());
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn checkpoint_resume_test() -> Result<()> {
+    resume_test("checkpoint", Compression::None)
+}
+
+#[test]
+fn checkpoint_resume_compressed_test() -> Result<()> {
+    resume_test("checkpoint_gzip", Compression::Gzip)
+}
+
+#[test]
+fn checkpoint_mismatch_test() -> Result<()> {
+    let (paths, _) = write_files("checkpoint_mismatch", Compression::None)?;
+    let mut iter = ChainedRecordIter::<Vec<u8>>::from_paths(&paths, Default::default());
+    iter.next().transpose()?;
+    let checkpoint = iter.checkpoint();
+    ensure!(
+        checkpoint
+            == ReaderCheckpoint {
+                file_index: 0,
+                path: Some(paths[0].clone()),
+                offset: 17,
+                file_records: 1,
+                num_records: 1,
+            }
+    );
+
+    // the file list differs
+    let reversed: Vec<_> = paths.iter().rev().collect();
+    ensure!(ChainedRecordIter::<Vec<u8>>::from_checkpoint(
+        &reversed,
+        Default::default(),
+        checkpoint
+    )
+    .is_err());
+
+    // the offset is not at a record
+    let checkpoint = ReaderCheckpoint {
+        offset: 5,
+        ..iter.checkpoint()
+    };
+    let mut iter =
+        ChainedRecordIter::<Vec<u8>>::from_checkpoint(&paths, Default::default(), checkpoint)?;
+    ensure!(iter.next().unwrap().is_err());
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "with-serde")]
+#[test]
+fn checkpoint_serde_test() -> Result<()> {
+    let checkpoint = ReaderCheckpoint {
+        file_index: 2,
+        path: Some(DATA_DIR.join("checkpoint.tfrecord")),
+        offset: 1024,
+        file_records: 10,
+        num_records: 30,
+    };
+    let text = serde_json::to_string(&checkpoint)?;
+    ensure!(serde_json::from_str::<ReaderCheckpoint>(&text)? == checkpoint);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::{StreamExt as _, TryStreamExt as _};
+use std::fs;
+use tfrecord::{
+    BytesWriter, ChainedRecordStream, Compression, RecordReaderConfig, RecordWriterConfig,
+};
+
+#[async_std::test]
+async fn async_checkpoint_resume_test() -> Result<()> {
+    for compression in [Compression::None, Compression::Gzip] {
+        let mut paths = vec![];
+        let mut records = vec![];
+        for (file_index, num_records) in [3, 0, 2].into_iter().enumerate() {
+            let path = DATA_DIR.join(format!(
+                "checkpoint_async-{compression:?}-{file_index}.tfrecord"
+            ));
+            let config = RecordWriterConfig {
+                compression,
+                ..Default::default()
+            };
+            let mut writer = BytesWriter::create_with_config(&path, config)?;
+            for index in 0..num_records {
+                let record = vec![file_index as u8; index * 5 + 1];
+                writer.send_bytes(&record)?;
+                records.push(record);
+            }
+            writer.close()?;
+            paths.push(path);
+        }
+        let config = RecordReaderConfig {
+            compression,
+            ..Default::default()
+        };
+
+        for num_consumed in 0..=records.len() {
+            let mut stream = ChainedRecordStream::<Vec<u8>>::from_paths(&paths, config.clone());
+            let mut output: Vec<_> = (&mut stream).take(num_consumed).try_collect().await?;
+            let checkpoint = stream.checkpoint();
+            ensure!(checkpoint.num_records == num_consumed as u64);
+
+            let stream = ChainedRecordStream::<Vec<u8>>::from_checkpoint(
+                &paths,
+                config.clone(),
+                checkpoint,
+            )?;
+            output.extend(stream.try_collect::<Vec<_>>().await?);
+            ensure!(output == records);
+        }
+
+        let reversed: Vec<_> = paths.iter().rev().collect();
+        let mut stream = ChainedRecordStream::<Vec<u8>>::from_paths(&paths, config.clone());
+        stream.try_next().await?;
+        ensure!(ChainedRecordStream::<Vec<u8>>::from_checkpoint(
+            &reversed,
+            config,
+            stream.checkpoint()
+        )
+        .is_err());
+
+        for path in paths {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+pub mod common;
+
+use common::*;
+use flate2::{read::GzDecoder, write::GzEncoder};
+use std::io::{prelude::*, Cursor};
+use tfrecord::{BytesIter, BytesWriter, Compression, RecordReaderConfig, RecordWriterConfig};
+
+fn sample_records() -> Vec<Vec<u8>> {
+    (0..64u8)
+        .map(|index| vec![index; index as usize * 7])
+        .collect()
+}
+
+fn write_records(config: RecordWriterConfig) -> Result<Vec<u8>> {
+    let path = DATA_DIR.join(format!(
+        "compression_{:?}_{:?}.tfrecord",
+        config.compression, config.compression_level
+    ));
+    {
+        let mut writer = BytesWriter::create_with_config(&path, config)?;
+        for record in sample_records() {
+            writer.send(record)?;
+        }
+    }
+    let bytes = std::fs::read(&path)?;
+    std::fs::remove_file(&path)?;
+    Ok(bytes)
+}
+
+fn read_records(bytes: Vec<u8>, compression: Compression) -> Result<Vec<Vec<u8>>> {
+    let config = RecordReaderConfig {
+        compression,
+        ..Default::default()
+    };
+    let records: Vec<_> =
+        BytesIter::from_reader(Cursor::new(bytes), config).collect::<Result<_, _>>()?;
+    Ok(records)
+}
+
+#[test]
+fn compression_round_trip_test() -> Result<()> {
+    for compression in [Compression::None, Compression::Gzip, Compression::Zlib] {
+        for compression_level in [None, Some(0), Some(9)] {
+            let config = RecordWriterConfig {
+                compression,
+                compression_level,
+                ..Default::default()
+            };
+            let bytes = write_records(config)?;
+            let records = read_records(bytes, compression)?;
+            ensure!(records == sample_records());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn gzip_compatibility_test() -> Result<()> {
+    let plain = write_records(Default::default())?;
+
+    // a GZIP TFRecord file is the gzip stream of an uncompressed TFRecord file
+    let compressed = {
+        let mut encoder = GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(&plain)?;
+        encoder.finish()?
+    };
+    ensure!(read_records(compressed, Compression::Gzip)? == sample_records());
+
+    let written = write_records(RecordWriterConfig {
+        compression: Compression::Gzip,
+        ..Default::default()
+    })?;
+    let mut decompressed = vec![];
+    GzDecoder::new(written.as_slice()).read_to_end(&mut decompressed)?;
+    ensure!(decompressed == plain);
+
+    Ok(())
+}
+
+/// The records in the fixtures generated by `tests/fixtures/generate_compressed.py`.
+fn fixture_records() -> Vec<Vec<u8>> {
+    vec![
+        vec![],
+        b"tfrecord".to_vec(),
+        (0..=255).collect(),
+        vec![b'x'; 1000],
+    ]
+}
+
+fn fixture_path(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn fixture_compatibility_test() -> Result<()> {
+    for (name, compression) in [
+        ("compressed.tfrecord.gzip", Compression::Gzip),
+        ("compressed.tfrecord.zlib", Compression::Zlib),
+    ] {
+        let bytes = std::fs::read(fixture_path(name))?;
+        ensure!(read_records(bytes.clone(), compression)? == fixture_records());
+
+        // the decoded streams are identical
+        let mut writer = BytesWriter::from_writer_with_config(
+            vec![],
+            RecordWriterConfig {
+                compression,
+                ..Default::default()
+            },
+        )?;
+        for record in fixture_records() {
+            writer.send(record)?;
+        }
+        let written = writer.close()?;
+        ensure!(read_records(written.clone(), compression)? == fixture_records());
+
+        // the GZIP header matches the one written by zlib
+        if compression == Compression::Gzip {
+            ensure!(written[..10] == bytes[..10]);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn invalid_compression_level_test() {
+    let config = RecordWriterConfig {
+        compression: Compression::Gzip,
+        compression_level: Some(10),
+        ..Default::default()
+    };
+    assert!(BytesWriter::from_writer_with_config(vec![], config).is_err());
+}
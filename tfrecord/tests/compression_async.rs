@@ -0,0 +1,48 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::{io::Cursor, stream::TryStreamExt as _};
+use tfrecord::{
+    BytesAsyncWriter, BytesStream, Compression, RecordReaderConfig, RecordWriterConfig,
+};
+
+#[async_std::test]
+async fn async_compression_round_trip_test() -> Result<()> {
+    let records: Vec<Vec<u8>> = (0..64u8)
+        .map(|index| vec![index; index as usize * 7])
+        .collect();
+
+    for compression in [Compression::None, Compression::Gzip, Compression::Zlib] {
+        let path = DATA_DIR.join(format!("compression_async_{:?}.tfrecord", compression));
+
+        let mut writer = BytesAsyncWriter::create_with_config(
+            &path,
+            RecordWriterConfig {
+                compression,
+                compression_level: Some(6),
+                ..Default::default()
+            },
+        )
+        .await?;
+        for record in records.clone() {
+            writer.send(record).await?;
+        }
+        writer.close().await?;
+
+        let bytes = async_std::fs::read(&path).await?;
+        async_std::fs::remove_file(&path).await?;
+
+        let config = RecordReaderConfig {
+            compression,
+            ..Default::default()
+        };
+        let output: Vec<_> = BytesStream::from_reader(Cursor::new(bytes), config)
+            .try_collect()
+            .await?;
+        ensure!(output == records);
+    }
+
+    Ok(())
+}
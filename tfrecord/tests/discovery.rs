@@ -0,0 +1,61 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    discovery, indexer, ChainedRecordIter, ShardManifest, ShardPolicy, ShardedWriter,
+    ShardedWriterConfig,
+};
+
+fn write_shards(name: &str) -> Result<ShardManifest> {
+    let dir = DATA_DIR.join(name);
+    fs::create_dir_all(&dir)?;
+    let config = ShardedWriterConfig {
+        policy: ShardPolicy::RoundRobin(4),
+        ..Default::default()
+    };
+    let mut writer = ShardedWriter::<Vec<u8>>::create(dir.join("part"), config)?;
+    for index in 0..10u8 {
+        writer.send(vec![index; 10])?;
+    }
+    let manifest = writer.close()?;
+    Ok(manifest)
+}
+
+#[test]
+fn discovery_test() -> Result<()> {
+    let manifest = write_shards("discovery")?;
+    let dir = DATA_DIR.join("discovery");
+    let dir = dir.to_str().unwrap();
+    let expect: Vec<_> = manifest.paths().map(|path| path.to_owned()).collect();
+
+    for pattern in [
+        format!("{dir}/part@4.tfrecord"),
+        format!("{dir}/part-?????-of-00004.tfrecord"),
+        format!("{dir}/part-*.tfrecord"),
+    ] {
+        ensure!(discovery::find_files(&pattern)? == expect);
+    }
+    ensure!(discovery::find_files(&format!("{dir}/part-*.json")).is_err());
+
+    // records are chained in shard order
+    let records: Vec<Vec<u8>> =
+        ChainedRecordIter::open_pattern(&format!("{dir}/part@4.tfrecord"), Default::default())?
+            .collect::<Result<_, _>>()?;
+    let expect_records: Vec<_> = (0..4u8)
+        .flat_map(|shard| (shard..10).step_by(4))
+        .map(|index| vec![index; 10])
+        .collect();
+    ensure!(records == expect_records);
+
+    let num_indexes = indexer::load_pattern(&format!("{dir}/part-*"), Default::default())?.count();
+    ensure!(num_indexes == 10);
+
+    // missing shards are detected
+    fs::remove_file(&expect[2])?;
+    ensure!(discovery::find_files(&format!("{dir}/part@4.tfrecord")).is_err());
+    ensure!(discovery::find_files(&format!("{dir}/part-*.tfrecord")).is_err());
+
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::TryStreamExt as _;
+use tfrecord::{
+    indexer, BytesAsyncWriter, ChainedRecordStream, ShardPolicy, ShardedAsyncWriter,
+    ShardedWriterConfig,
+};
+
+#[async_std::test]
+async fn async_discovery_test() -> Result<()> {
+    let dir = DATA_DIR.join("discovery_async");
+    async_std::fs::create_dir_all(&dir).await?;
+    let config = ShardedWriterConfig {
+        policy: ShardPolicy::MaxRecords(3),
+        ..Default::default()
+    };
+    let mut writer = ShardedAsyncWriter::<Vec<u8>>::create(dir.join("part"), config).await?;
+    for index in 0..8u8 {
+        writer.send(vec![index; 10]).await?;
+    }
+    writer.close().await?;
+
+    // a file not following the shard naming is matched by the glob pattern
+    let mut writer = BytesAsyncWriter::create(dir.join("part-extra.tfrecord")).await?;
+    writer.send(vec![8; 10]).await?;
+    writer.close().await?;
+
+    let pattern = format!("{}/part-*.tfrecord", dir.to_str().unwrap());
+    let records: Vec<Vec<u8>> = ChainedRecordStream::open_pattern(&pattern, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    ensure!(records == (0..9).map(|index| vec![index; 10]).collect::<Vec<_>>());
+
+    let pattern = format!("{}/part@3.tfrecord", dir.to_str().unwrap());
+    let indexes: Vec<_> = indexer::load_pattern_async(&pattern, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    ensure!(indexes.len() == 8);
+
+    async_std::fs::remove_dir_all(&dir).await?;
+    Ok(())
+}
@@ -0,0 +1,71 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    BytesIter, BytesWriter, DurabilityConfig, EventIter, EventWriter, EventWriterConfig,
+    RecordWriterConfig,
+};
+
+#[test]
+fn atomic_create_test() -> Result<()> {
+    let path = DATA_DIR.join("durability_atomic.tfrecord");
+    let tmp_path = DATA_DIR.join(".durability_atomic.tfrecord.tmp");
+    let config = RecordWriterConfig {
+        durability: DurabilityConfig {
+            atomic: true,
+            sync_data: true,
+            sync_dir: true,
+        },
+        ..Default::default()
+    };
+
+    let mut writer = BytesWriter::create_with_config(&path, config)?;
+    writer.send(vec![1; 10])?;
+    writer.send(vec![2; 20])?;
+    writer.flush()?;
+    ensure!(!path.exists() && tmp_path.exists());
+
+    writer.close()?;
+    ensure!(path.exists() && !tmp_path.exists());
+
+    let records: Vec<_> = BytesIter::open(&path, Default::default())?.collect::<Result<_, _>>()?;
+    ensure!(records == [vec![1; 10], vec![2; 20]]);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn atomic_event_writer_test() -> Result<()> {
+    let path = DATA_DIR.join("durability_events.tfevents");
+    let tmp_path = DATA_DIR.join(".durability_events.tfevents.tmp");
+    let config = EventWriterConfig {
+        durability: DurabilityConfig {
+            atomic: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut writer = EventWriter::create(&path, config)?;
+    writer.write_scalar("loss", 0, 1.0)?;
+    writer.write_scalar("loss", 1, 0.5)?;
+    ensure!(!path.exists() && tmp_path.exists());
+
+    writer.close()?;
+    ensure!(path.exists() && !tmp_path.exists());
+    ensure!(EventIter::open(&path, Default::default())?.count() == 2);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn close_returns_inner_writer_test() -> Result<()> {
+    let mut writer = BytesWriter::from_writer(vec![])?;
+    writer.send(vec![1; 10])?;
+    let bytes = writer.close()?;
+    ensure!(bytes.len() == 26);
+    Ok(())
+}
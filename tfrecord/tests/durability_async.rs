@@ -0,0 +1,39 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::TryStreamExt as _;
+use std::fs;
+use tfrecord::{BytesAsyncWriter, BytesStream, DurabilityConfig, RecordWriterConfig};
+
+#[async_std::test]
+async fn async_atomic_create_test() -> Result<()> {
+    let path = DATA_DIR.join("durability_atomic_async.tfrecord");
+    let tmp_path = DATA_DIR.join(".durability_atomic_async.tfrecord.tmp");
+    let config = RecordWriterConfig {
+        durability: DurabilityConfig {
+            atomic: true,
+            sync_data: true,
+            sync_dir: true,
+        },
+        ..Default::default()
+    };
+
+    let mut writer = BytesAsyncWriter::create_with_config(&path, config).await?;
+    writer.send(vec![1; 10]).await?;
+    writer.flush().await?;
+    ensure!(!path.exists() && tmp_path.exists());
+
+    writer.close().await?;
+    ensure!(path.exists() && !tmp_path.exists());
+
+    let records: Vec<_> = BytesStream::open(&path, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    ensure!(records == [vec![1; 10]]);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
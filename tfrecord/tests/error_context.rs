@@ -0,0 +1,117 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    indexer::{self, RecordIndex},
+    BytesIter, BytesWriter, ChecksumKind, Error, ErrorKind, Example,
+};
+
+fn write_corrupted(name: &str) -> Result<std::path::PathBuf> {
+    let path = DATA_DIR.join(name);
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for index in 0..3 {
+            writer.send(vec![index; 10])?;
+        }
+    }
+
+    // corrupt the data of record #1 and the length of record #2
+    let mut bytes = fs::read(&path)?;
+    bytes[26 + 14] ^= 0xff;
+    bytes[52] ^= 0xff;
+    fs::write(&path, bytes)?;
+    Ok(path)
+}
+
+#[test]
+fn reader_error_context_test() -> Result<()> {
+    let path = write_corrupted("error_context_reader.tfrecord")?;
+    let results: Vec<_> = BytesIter::open(&path, Default::default())?
+        .take(3)
+        .collect();
+    ensure!(results[0].is_ok());
+
+    let error = results[1].as_ref().unwrap_err();
+    ensure!(matches!(
+        error.inner(),
+        Error::ChecksumMismatch {
+            kind: ChecksumKind::Data,
+            ..
+        }
+    ));
+    let context = error.context().unwrap();
+    ensure!(context.path.as_deref() == Some(&*path));
+    ensure!(context.record_index == Some(1));
+    ensure!(context.offset == Some(26));
+    ensure!(error.to_string().contains("record #1"));
+
+    let error = results[2].as_ref().unwrap_err();
+    ensure!(matches!(
+        error.inner(),
+        Error::ChecksumMismatch {
+            kind: ChecksumKind::Length,
+            ..
+        }
+    ));
+    ensure!(error.context().unwrap().record_index == Some(2));
+    ensure!(error.context().unwrap().offset == Some(52));
+
+    // the kind is visible through the context
+    ensure!(matches!(error, Error::WithContext { .. }));
+    ensure!(error.kind() == ErrorKind::ChecksumMismatch);
+
+    // the path is known for errors opening the file
+    let missing = DATA_DIR.join("error_context_missing.tfrecord");
+    let error = BytesIter::open(&missing, Default::default()).err().unwrap();
+    ensure!(matches!(error.inner(), Error::IoError(_)));
+    ensure!(error.context().unwrap().path.as_deref() == Some(&*missing));
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn indexer_error_context_test() -> Result<()> {
+    let path = write_corrupted("error_context_indexer.tfrecord")?;
+    let results: Vec<_> = indexer::load_file(&*path, Default::default())?.collect();
+    ensure!(results.len() == 2);
+
+    let error = results[1].as_ref().unwrap_err();
+    ensure!(matches!(
+        error.inner(),
+        Error::ChecksumMismatch {
+            kind: ChecksumKind::Data,
+            ..
+        }
+    ));
+    let context = error.context().unwrap();
+    ensure!(context.path.as_deref() == Some(&*path));
+    ensure!(context.record_index == Some(1));
+    ensure!(context.offset == Some(26));
+
+    // the record bytes are not a valid example
+    let index = results[0].as_ref().unwrap();
+    let error = index.load::<Example>().unwrap_err();
+    ensure!(matches!(error.inner(), Error::ExampleDecodeError(_)));
+    let context = error.context().unwrap();
+    ensure!(context.path.as_deref() == Some(&*path));
+    ensure!(context.record_index.is_none());
+    ensure!(context.offset == Some(0));
+
+    let index = RecordIndex {
+        path: DATA_DIR.join("error_context_missing.tfrecord").into(),
+        ..index.clone()
+    };
+    ensure!(index.load::<Vec<u8>>().unwrap_err().context().is_some());
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn invalid_argument_test() -> Result<()> {
+    let error = tfrecord::discovery::find_files("[").unwrap_err();
+    ensure!(matches!(error, Error::InvalidArgumentsError { .. }));
+    Ok(())
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::StreamExt as _;
+use std::fs;
+use tfrecord::{indexer, BytesStream, BytesWriter, ChecksumKind, Error};
+
+#[async_std::test]
+async fn async_error_context_test() -> Result<()> {
+    let path = DATA_DIR.join("error_context_async.tfrecord");
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for index in 0..3 {
+            writer.send(vec![index; 10])?;
+        }
+    }
+    let mut bytes = fs::read(&path)?;
+    bytes[26 + 14] ^= 0xff;
+    fs::write(&path, bytes)?;
+
+    // the stream ends after the first error
+    let results: Vec<_> = BytesStream::open(&path, Default::default())
+        .await?
+        .collect()
+        .await;
+    ensure!(results.len() == 2);
+    let error = results[1].as_ref().unwrap_err();
+    ensure!(matches!(
+        error.inner(),
+        Error::ChecksumMismatch {
+            kind: ChecksumKind::Data,
+            ..
+        }
+    ));
+    let context = error.context().unwrap();
+    ensure!(context.path.as_deref() == Some(&*path));
+    ensure!(context.record_index == Some(1));
+    ensure!(context.offset == Some(26));
+
+    let results: Vec<_> = indexer::load_file_async(&*path, Default::default())
+        .await?
+        .collect()
+        .await;
+    ensure!(results.len() == 2);
+    let context = results[1].as_ref().unwrap_err().context().unwrap();
+    ensure!(context.path.as_deref() == Some(&*path));
+    ensure!(context.record_index == Some(1));
+    ensure!(context.offset == Some(26));
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
@@ -1,6 +1,6 @@
 #![cfg(feature = "async")]
 
-mod common;
+pub mod common;
 
 use common::*;
 use futures::stream::TryStreamExt as _;
@@ -0,0 +1,64 @@
+#!/usr/bin/env python3
+"""Generate compressed TFRecord fixtures the way TensorFlow writes them.
+
+TensorFlow's RecordWriter compresses the record stream with zlib through
+ZlibOutputBuffer, using the default compression level, memLevel 9 and the
+default strategy, with window bits 31 for GZIP and 15 for ZLIB. This script
+drives the system zlib with the same settings, so the output is what
+`tf.io.TFRecordWriter(path, options="GZIP")` or `"ZLIB"` produces on Unix.
+It is equivalent to running the following with TensorFlow installed:
+
+    for kind in ["GZIP", "ZLIB"]:
+        with tf.io.TFRecordWriter(path, options=kind) as writer:
+            for record in RECORDS:
+                writer.write(record)
+"""
+
+import os
+import struct
+import zlib
+
+RECORDS = [b"", b"tfrecord", bytes(range(256)), b"x" * 1000]
+
+
+def crc32c(data):
+    crc = 0xFFFFFFFF
+    for byte in data:
+        crc ^= byte
+        for _ in range(8):
+            crc = (crc >> 1) ^ (0x82F63B78 if crc & 1 else 0)
+    return crc ^ 0xFFFFFFFF
+
+
+def masked_crc(data):
+    crc = crc32c(data)
+    return ((((crc >> 15) | (crc << 17)) & 0xFFFFFFFF) + 0xA282EAD8) & 0xFFFFFFFF
+
+
+def encode_records(records):
+    out = b""
+    for record in records:
+        length = struct.pack("<Q", len(record))
+        out += length + struct.pack("<I", masked_crc(length))
+        out += record + struct.pack("<I", masked_crc(record))
+    return out
+
+
+def compress(data, wbits):
+    compressor = zlib.compressobj(
+        zlib.Z_DEFAULT_COMPRESSION, zlib.DEFLATED, wbits, 9, zlib.Z_DEFAULT_STRATEGY
+    )
+    return compressor.compress(data) + compressor.flush(zlib.Z_FINISH)
+
+
+def main():
+    directory = os.path.dirname(os.path.abspath(__file__))
+    stream = encode_records(RECORDS)
+    for name, wbits in [("gzip", zlib.MAX_WBITS + 16), ("zlib", zlib.MAX_WBITS)]:
+        path = os.path.join(directory, f"compressed.tfrecord.{name}")
+        with open(path, "wb") as file:
+            file.write(compress(stream, wbits))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,78 @@
+pub mod common;
+
+use common::*;
+use std::{fs, io::Write as _, thread, time::Duration};
+use tfrecord::{BytesWriter, Error, FollowConfig, FollowIter};
+
+fn encode(records: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut writer = BytesWriter::from_writer(&mut bytes)?;
+    for record in records {
+        writer.send(record.clone())?;
+    }
+    writer.flush()?;
+    drop(writer);
+    Ok(bytes)
+}
+
+fn follow_config() -> FollowConfig {
+    FollowConfig {
+        poll_interval: Duration::from_millis(10),
+        idle_timeout: Some(Duration::from_millis(500)),
+    }
+}
+
+#[test]
+fn follow_test() -> Result<()> {
+    let path = DATA_DIR.join("follow.tfrecord");
+    let records: Vec<_> = (0..4u8).map(|index| vec![index; 100]).collect();
+    let bytes = encode(&records)?;
+    let record_size = bytes.len() / records.len();
+
+    // write the first record and a half of the second record
+    fs::write(&path, &bytes[..(record_size + record_size / 2)])?;
+
+    let writer = thread::spawn({
+        let path = path.clone();
+        let bytes = bytes.clone();
+        move || -> Result<()> {
+            let mut file = fs::OpenOptions::new().append(true).open(path)?;
+            for chunk in bytes[(record_size + record_size / 2)..].chunks(30) {
+                thread::sleep(Duration::from_millis(20));
+                file.write_all(chunk)?;
+                file.flush()?;
+            }
+            Ok(())
+        }
+    });
+
+    let output: Vec<Vec<u8>> =
+        FollowIter::open(&path, Default::default(), follow_config())?.collect::<Result<_, _>>()?;
+    writer.join().unwrap()?;
+    ensure!(output == records);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn follow_truncated_tail_test() -> Result<()> {
+    let path = DATA_DIR.join("follow_truncated.tfrecord");
+    let records = vec![vec![1u8; 10], vec![2u8; 10]];
+    let bytes = encode(&records)?;
+    fs::write(&path, &bytes[..(bytes.len() - 5)])?;
+
+    let output: Vec<Result<Vec<u8>, _>> =
+        FollowIter::open(&path, Default::default(), follow_config())?.collect();
+    ensure!(output.len() == 2);
+    ensure!(output[0].as_ref().ok() == Some(&records[0]));
+    let error = output[1].as_ref().unwrap_err();
+    ensure!(matches!(error.inner(), Error::UnexpectedEof));
+    let context = error.context().unwrap();
+    ensure!(context.path.as_deref() == Some(&*path));
+    ensure!(context.record_index == Some(1));
+    ensure!(context.offset == Some(26));
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
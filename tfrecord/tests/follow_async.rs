@@ -0,0 +1,49 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::TryStreamExt as _;
+use std::{fs, io::Write as _, thread, time::Duration};
+use tfrecord::{BytesWriter, FollowConfig, FollowStream};
+
+#[async_std::test]
+async fn async_follow_test() -> Result<()> {
+    let path = DATA_DIR.join("follow_async.tfrecord");
+    let records: Vec<_> = (0..3u8).map(|index| vec![index; 50]).collect();
+    let mut bytes = vec![];
+    {
+        let mut writer = BytesWriter::from_writer(&mut bytes)?;
+        for record in &records {
+            writer.send(record.clone())?;
+        }
+        writer.flush()?;
+    }
+    fs::write(&path, &bytes[..20])?;
+
+    let writer = thread::spawn({
+        let path = path.clone();
+        move || -> Result<()> {
+            let mut file = fs::OpenOptions::new().append(true).open(path)?;
+            for chunk in bytes[20..].chunks(25) {
+                thread::sleep(Duration::from_millis(20));
+                file.write_all(chunk)?;
+            }
+            Ok(())
+        }
+    });
+
+    let config = FollowConfig {
+        poll_interval: Duration::from_millis(10),
+        idle_timeout: Some(Duration::from_millis(500)),
+    };
+    let output: Vec<Vec<u8>> = FollowStream::open(&path, Default::default(), config)
+        .await?
+        .try_collect()
+        .await?;
+    writer.join().unwrap()?;
+    ensure!(output == records);
+
+    async_std::fs::remove_file(&path).await?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+pub mod common;
+
+use common::*;
+use std::{fs, path::Path};
+use tfrecord::{
+    indexer::{self, IndexFormat, Position},
+    BytesWriter,
+};
+
+fn write_records(path: &Path, records: &[Vec<u8>]) -> Result<()> {
+    let mut writer = BytesWriter::create(path)?;
+    for record in records {
+        writer.send(record.clone())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[test]
+fn dali_index_test() -> Result<()> {
+    let path = DATA_DIR.join("dali_index.tfrecord");
+    let index_path = IndexFormat::Dali.sidecar_path(&path);
+    write_records(&path, &[vec![1; 10], vec![2; 20]])?;
+
+    let positions =
+        indexer::load_or_build_index(&path, &index_path, IndexFormat::Dali, Default::default())?;
+    ensure!(
+        positions
+            == [
+                Position {
+                    offset: 12,
+                    len: 10
+                },
+                Position {
+                    offset: 38,
+                    len: 20
+                }
+            ]
+    );
+    ensure!(fs::read_to_string(&index_path)? == "0 26\n26 36\n");
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Dali)? == Some(positions));
+
+    // records are loaded through the cached index
+    let indexes = indexer::load_file_cached(&path, IndexFormat::Dali, Default::default())?;
+    let record: Vec<u8> = indexes[1].load()?;
+    ensure!(record == vec![2; 20]);
+
+    // the index no longer covers the data file
+    write_records(&path, &[vec![1; 10], vec![2; 20], vec![3; 5]])?;
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Dali)?.is_none());
+    let indexes = indexer::load_file_cached(&path, IndexFormat::Dali, Default::default())?;
+    ensure!(indexes.len() == 3);
+
+    fs::remove_file(&path)?;
+    fs::remove_file(&index_path)?;
+    Ok(())
+}
+
+#[test]
+fn binary_index_test() -> Result<()> {
+    let path = DATA_DIR.join("binary_index.tfrecord");
+    let index_path = IndexFormat::Binary.sidecar_path(&path);
+    write_records(&path, &[vec![1; 10], vec![2; 20]])?;
+
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Binary)?.is_none());
+    let positions =
+        indexer::load_or_build_index(&path, &index_path, IndexFormat::Binary, Default::default())?;
+    ensure!(positions.len() == 2);
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Binary)? == Some(positions));
+
+    // the file size changes
+    write_records(&path, &[vec![1; 10]])?;
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Binary)?.is_none());
+
+    // the header has a corrupted modification time
+    indexer::load_or_build_index(&path, &index_path, IndexFormat::Binary, Default::default())?;
+    let bytes = fs::read(&index_path)?;
+    let mut corrupted = bytes.clone();
+    corrupted[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(&index_path, &corrupted)?;
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Binary).is_err());
+    let mut corrupted = bytes;
+    corrupted[16..24].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+    fs::write(&index_path, &corrupted)?;
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Binary).is_err());
+
+    // the text index is not accepted as a binary index
+    fs::write(&index_path, "0 26\n")?;
+    ensure!(indexer::load_index(&path, &index_path, IndexFormat::Binary).is_err());
+
+    fs::remove_file(&path)?;
+    fs::remove_file(&index_path)?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{BytesWriter, IndexedDataset, IndexedDatasetConfig};
+
+fn write_files(name: &str) -> Result<(String, Vec<Vec<u8>>)> {
+    let dir = DATA_DIR.join(name);
+    fs::create_dir_all(&dir)?;
+    let records: Vec<_> = (0..10u8)
+        .map(|index| vec![index; index as usize + 1])
+        .collect();
+
+    for (shard, chunk) in records.chunks(5).enumerate() {
+        let mut writer = BytesWriter::create(dir.join(format!("part-{shard:05}-of-00002")))?;
+        for record in chunk {
+            writer.send(record.clone())?;
+        }
+        writer.flush()?;
+    }
+    let pattern = format!("{}/part@2", dir.to_str().unwrap());
+    Ok((pattern, records))
+}
+
+#[test]
+fn indexed_dataset_test() -> Result<()> {
+    let (pattern, records) = write_files("indexed_dataset")?;
+    let config = IndexedDatasetConfig {
+        max_open_files: 1,
+        ..Default::default()
+    };
+    let dataset: IndexedDataset<Vec<u8>> =
+        IndexedDataset::load_pattern(&pattern, Default::default(), config)?;
+
+    ensure!(dataset.len() == 10);
+    ensure!(dataset.get(7)? == records[7]);
+    ensure!(dataset.get(10).is_err());
+
+    // the range spans two files
+    ensure!(dataset.get_range(3..8)? == records[3..8]);
+    ensure!(dataset.get_range(8..11).is_err());
+
+    let output = dataset.get_many(&[9, 0, 1, 2, 5, 5])?;
+    ensure!(output == [9, 0, 1, 2, 5, 5].map(|index: usize| records[index].clone()));
+
+    // the dataset is shared across threads
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|thread| {
+                let (dataset, records) = (&dataset, &records);
+                scope.spawn(move || -> Result<()> {
+                    for index in (thread..10).step_by(4) {
+                        ensure!(dataset.get(index)? == records[index]);
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().unwrap())
+    })?;
+
+    fs::remove_dir_all(DATA_DIR.join("indexed_dataset"))?;
+    Ok(())
+}
+
+#[test]
+fn indexed_dataset_checksum_test() -> Result<()> {
+    let (pattern, _) = write_files("indexed_dataset_checksum")?;
+    let dataset: IndexedDataset<Vec<u8>> =
+        IndexedDataset::load_pattern(&pattern, Default::default(), Default::default())?;
+
+    // corrupt the data of the second record
+    let index = &dataset.indexes()[1];
+    let mut bytes = fs::read(&*index.path)?;
+    bytes[index.offset as usize] ^= 0xff;
+    fs::write(&*index.path, bytes)?;
+
+    ensure!(dataset.get(0).is_ok());
+    ensure!(dataset.get(1).is_err());
+    ensure!(dataset.get_range(0..3).is_err());
+
+    fs::remove_dir_all(DATA_DIR.join("indexed_dataset_checksum"))?;
+    Ok(())
+}
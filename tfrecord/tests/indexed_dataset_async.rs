@@ -0,0 +1,34 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::TryStreamExt as _;
+use tfrecord::{indexer, BytesAsyncWriter, IndexedAsyncDataset, IndexedDataset};
+
+#[async_std::test]
+async fn async_indexed_dataset_test() -> Result<()> {
+    let path = DATA_DIR.join("indexed_dataset_async.tfrecord");
+    let records: Vec<_> = (0..6u8).map(|index| vec![index; 8]).collect();
+    let mut writer = BytesAsyncWriter::create(&path).await?;
+    for record in records.clone() {
+        writer.send(record).await?;
+    }
+    writer.close().await?;
+
+    let indexes: Vec<_> = indexer::load_file_async(&path, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    let dataset: IndexedAsyncDataset<Vec<u8>> =
+        IndexedDataset::from_indexes(indexes, Default::default()).into();
+
+    ensure!(dataset.len() == 6);
+    ensure!(dataset.get(2).await? == records[2]);
+    ensure!(dataset.get_range(1..4).await? == records[1..4]);
+    ensure!(dataset.clone().get_many(&[5, 0]).await? == [records[5].clone(), records[0].clone()]);
+    ensure!(dataset.get(6).await.is_err());
+
+    async_std::fs::remove_file(&path).await?;
+    Ok(())
+}
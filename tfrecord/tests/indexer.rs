@@ -1,4 +1,4 @@
-mod common;
+pub mod common;
 
 use common::*;
 use tfrecord::{Example, FeatureKind};
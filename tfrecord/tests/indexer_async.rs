@@ -1,6 +1,6 @@
 #![cfg(all(feature = "async"))]
 
-mod common;
+pub mod common;
 
 use common::*;
 use futures::stream::{StreamExt as _, TryStreamExt as _};
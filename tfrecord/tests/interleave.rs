@@ -0,0 +1,190 @@
+pub mod common;
+
+use common::*;
+use std::{fs, path::PathBuf};
+use tfrecord::{BytesWriter, Error, InterleaveConfig, InterleaveRecordIter, Record};
+
+/// A record type which panics on decoding the value 21.
+#[derive(Debug)]
+struct Fragile(u8);
+
+impl Record for Fragile {
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        assert!(bytes != [21], "fragile record");
+        Ok(Self(bytes[0]))
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        buf.push(self.0);
+        Ok(())
+    }
+}
+
+fn write_files(prefix: &str) -> Result<Vec<PathBuf>> {
+    let contents: [&[u8]; 4] = [&[0, 1, 2], &[10, 11], &[20, 21, 22, 23], &[30]];
+    contents
+        .iter()
+        .enumerate()
+        .map(|(index, values)| {
+            let path = DATA_DIR.join(format!("{prefix}-{index}.tfrecord"));
+            let mut writer = BytesWriter::create(&path)?;
+            for &value in values.iter() {
+                writer.send(vec![value])?;
+            }
+            writer.close()?;
+            Ok(path)
+        })
+        .collect()
+}
+
+fn read_values(paths: &[PathBuf], config: InterleaveConfig) -> Result<Vec<u8>> {
+    let values = InterleaveRecordIter::<Vec<u8>>::from_paths(paths, Default::default(), config)?
+        .map(|record| Ok(record?[0]))
+        .collect::<Result<_>>()?;
+    Ok(values)
+}
+
+#[test]
+fn interleave_test() -> Result<()> {
+    let paths = write_files("interleave")?;
+
+    let values = read_values(
+        &paths,
+        InterleaveConfig {
+            cycle_length: Some(2),
+            ..Default::default()
+        },
+    )?;
+    ensure!(values == [0, 10, 1, 11, 2, 20, 30, 21, 22, 23]);
+
+    let values = read_values(
+        &paths,
+        InterleaveConfig {
+            cycle_length: Some(2),
+            block_length: 2,
+            buffer_size: 1,
+            ..Default::default()
+        },
+    )?;
+    ensure!(values == [0, 1, 10, 11, 2, 20, 21, 30, 22, 23]);
+
+    // a cycle of one file reads the files in order
+    let values = read_values(
+        &paths,
+        InterleaveConfig {
+            cycle_length: Some(1),
+            block_length: 3,
+            ..Default::default()
+        },
+    )?;
+    ensure!(values == [0, 1, 2, 10, 11, 20, 21, 22, 23, 30]);
+
+    let mut values = read_values(
+        &paths,
+        InterleaveConfig {
+            cycle_length: Some(3),
+            deterministic: false,
+            ..Default::default()
+        },
+    )?;
+    values.sort_unstable();
+    ensure!(values == [0, 1, 2, 10, 11, 20, 21, 22, 23, 30]);
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn interleave_error_test() -> Result<()> {
+    let mut paths = write_files("interleave_error")?;
+    let missing = DATA_DIR.join("interleave_error-missing.tfrecord");
+    paths.insert(1, missing.clone());
+
+    for deterministic in [true, false] {
+        let results: Vec<_> = InterleaveRecordIter::<Vec<u8>>::from_paths(
+            &paths,
+            Default::default(),
+            InterleaveConfig {
+                cycle_length: Some(2),
+                deterministic,
+                ..Default::default()
+            },
+        )?
+        .collect();
+        // the missing file yields an error and the other files are read
+        ensure!(results.len() == 11);
+        let errors: Vec<_> = results
+            .iter()
+            .filter_map(|result| result.as_ref().err())
+            .collect();
+        ensure!(errors.len() == 1);
+        ensure!(errors[0].context().unwrap().path.as_deref() == Some(&*missing));
+    }
+
+    let result = InterleaveRecordIter::<Vec<u8>>::from_paths(
+        &paths,
+        Default::default(),
+        InterleaveConfig {
+            block_length: 0,
+            ..Default::default()
+        },
+    );
+    ensure!(result.is_err());
+
+    let empty: [PathBuf; 0] = [];
+    let mut iter =
+        InterleaveRecordIter::<Vec<u8>>::from_paths(empty, Default::default(), Default::default())?;
+    ensure!(iter.next().is_none());
+
+    for path in paths.into_iter().filter(|path| *path != missing) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn interleave_panic_test() -> Result<()> {
+    let paths = write_files("interleave_panic")?;
+
+    for deterministic in [true, false] {
+        let results: Vec<_> = InterleaveRecordIter::<Fragile>::from_paths(
+            &paths,
+            Default::default(),
+            InterleaveConfig {
+                cycle_length: Some(2),
+                deterministic,
+                ..Default::default()
+            },
+        )?
+        .collect();
+        // the panic is yielded as an error and the other files are read
+        ensure!(results.len() == 8);
+        let errors: Vec<_> = results
+            .iter()
+            .filter_map(|result| result.as_ref().err())
+            .collect();
+        ensure!(errors.len() == 1);
+        ensure!(errors[0].to_string().contains("fragile record"));
+        ensure!(errors[0].context().unwrap().path.as_deref() == Some(&*paths[2]));
+    }
+
+    // the workers blocked on full buffers are stopped on drop
+    let mut iter = InterleaveRecordIter::<Vec<u8>>::from_paths(
+        &paths,
+        Default::default(),
+        InterleaveConfig {
+            cycle_length: Some(2),
+            buffer_size: 1,
+            ..Default::default()
+        },
+    )?;
+    ensure!(iter.next().transpose()? == Some(vec![0]));
+    drop(iter);
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
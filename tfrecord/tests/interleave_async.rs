@@ -0,0 +1,87 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::TryStreamExt as _;
+use std::{fs, path::PathBuf};
+use tfrecord::{BytesWriter, InterleaveConfig, InterleaveRecordStream};
+
+fn write_files(prefix: &str) -> Result<Vec<PathBuf>> {
+    let contents: [&[u8]; 4] = [&[0, 1, 2], &[10, 11], &[20, 21, 22, 23], &[30]];
+    contents
+        .iter()
+        .enumerate()
+        .map(|(index, values)| {
+            let path = DATA_DIR.join(format!("{prefix}-{index}.tfrecord"));
+            let mut writer = BytesWriter::create(&path)?;
+            for &value in values.iter() {
+                writer.send(vec![value])?;
+            }
+            writer.close()?;
+            Ok(path)
+        })
+        .collect()
+}
+
+async fn read_values(paths: &[PathBuf], config: InterleaveConfig) -> Result<Vec<u8>> {
+    let values = InterleaveRecordStream::<Vec<u8>>::from_paths(paths, Default::default(), config)?
+        .map_ok(|record| record[0])
+        .try_collect()
+        .await?;
+    Ok(values)
+}
+
+#[async_std::test]
+async fn async_interleave_test() -> Result<()> {
+    let paths = write_files("interleave_async")?;
+
+    let values = read_values(
+        &paths,
+        InterleaveConfig {
+            cycle_length: Some(2),
+            ..Default::default()
+        },
+    )
+    .await?;
+    ensure!(values == [0, 10, 1, 11, 2, 20, 30, 21, 22, 23]);
+
+    let values = read_values(
+        &paths,
+        InterleaveConfig {
+            cycle_length: Some(2),
+            block_length: 2,
+            buffer_size: 1,
+            ..Default::default()
+        },
+    )
+    .await?;
+    ensure!(values == [0, 1, 10, 11, 2, 20, 21, 30, 22, 23]);
+
+    let mut values = read_values(
+        &paths,
+        InterleaveConfig {
+            cycle_length: Some(3),
+            deterministic: false,
+            ..Default::default()
+        },
+    )
+    .await?;
+    values.sort_unstable();
+    ensure!(values == [0, 1, 2, 10, 11, 20, 21, 22, 23, 30]);
+
+    let result = InterleaveRecordStream::<Vec<u8>>::from_paths(
+        &paths,
+        Default::default(),
+        InterleaveConfig {
+            cycle_length: Some(0),
+            ..Default::default()
+        },
+    );
+    ensure!(result.is_err());
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "mmap")]
+
+pub mod common;
+
+use common::*;
+use tfrecord::{indexer::Position, BytesWriter, MmapReader, RecordReaderConfig};
+
+#[test]
+fn mmap_reader_test() -> Result<()> {
+    let path = DATA_DIR.join("mmap_reader.tfrecord");
+    let records: Vec<Vec<u8>> = (0..32u8)
+        .map(|index| vec![index; index as usize * 3])
+        .collect();
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for record in records.clone() {
+            writer.send(record)?;
+        }
+    }
+
+    let reader = MmapReader::open(&path, Default::default())?;
+    ensure!(reader.len() == records.len());
+    ensure!(reader.iter().eq(records.iter().map(Vec::as_slice)));
+    ensure!(reader.get(5) == Some(records[5].as_slice()));
+    ensure!(reader.get(records.len()).is_none());
+    ensure!(reader.decode::<Vec<u8>>(7).transpose()? == Some(records[7].clone()));
+
+    // positions agree with the indexer
+    let positions: Vec<Position> = tfrecord::indexer::load_file(&*path, Default::default())?
+        .map(|index| {
+            let index = index?;
+            anyhow::Ok(Position {
+                offset: index.offset,
+                len: index.len,
+            })
+        })
+        .collect::<Result<_>>()?;
+    ensure!(positions == reader.positions());
+    for (position, record) in positions.iter().zip(&records) {
+        ensure!(reader.read_at(*position)? == record.as_slice());
+    }
+    ensure!(reader
+        .read_at(Position {
+            offset: 1 << 40,
+            len: 1
+        })
+        .is_err());
+
+    // truncated file
+    {
+        let bytes = std::fs::read(&path)?;
+        std::fs::write(&path, &bytes[..(bytes.len() - 1)])?;
+        ensure!(MmapReader::open(&path, Default::default()).is_err());
+    }
+
+    // compression is not supported
+    ensure!(MmapReader::open(
+        &path,
+        RecordReaderConfig {
+            compression: tfrecord::Compression::Gzip,
+            ..Default::default()
+        }
+    )
+    .is_err());
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
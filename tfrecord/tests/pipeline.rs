@@ -0,0 +1,127 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    indexer, pipeline, pipeline::PipelineIterExt as _, ChainedRecordIter, Error, ExampleIter,
+    ExampleWriter, Feature,
+};
+
+fn sample_examples() -> Vec<tfrecord::Example> {
+    (0..10)
+        .map(|index| {
+            vec![("index".to_string(), Feature::from_i64_iter([index]))]
+                .into_iter()
+                .collect()
+        })
+        .collect()
+}
+
+fn index_of(example: &tfrecord::Example) -> i64 {
+    example.features.as_ref().unwrap().feature["index"]
+        .as_i64_list()
+        .unwrap()[0]
+}
+
+#[test]
+fn batch_test() -> Result<()> {
+    let batches: Vec<_> = (0..7).batch(3, false).collect();
+    ensure!(batches == [vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+    let batches: Vec<_> = (0..7).batch(3, true).collect();
+    ensure!(batches == [vec![0, 1, 2], vec![3, 4, 5]]);
+
+    // errors are yielded as they come, keeping the pending items
+    let items = vec![Ok(0), Ok(1), Err(Error::UnexpectedEof), Ok(2), Ok(3)];
+    let batches: Vec<_> = items.into_iter().try_batch(3, false).collect();
+    ensure!(batches.len() == 3);
+    ensure!(batches[0].is_err());
+    ensure!(batches[1].as_ref().unwrap() == &[0, 1, 2]);
+    ensure!(batches[2].as_ref().unwrap() == &[3]);
+    Ok(())
+}
+
+#[test]
+fn repeat_test() -> Result<()> {
+    let items: Vec<i32> =
+        pipeline::repeat(Some(3), || Ok(vec![Ok(1), Ok(2)])).collect::<Result<_, _>>()?;
+    ensure!(items == [1, 2, 1, 2, 1, 2]);
+
+    // an empty source ends the repetition
+    let items: Vec<Result<i32, Error>> = pipeline::repeat(None, || Ok(vec![])).collect();
+    ensure!(items.is_empty());
+
+    let mut iter = pipeline::repeat(None, || Ok(vec![Ok(1)]));
+    ensure!(iter.by_ref().take(5).count() == 5);
+    ensure!(iter.epoch() == 5);
+
+    // a failure to build the source ends the repetition
+    let items: Vec<Result<i32, Error>> =
+        pipeline::repeat(None, || Err::<Vec<_>, _>(Error::UnexpectedEof)).collect();
+    ensure!(items.len() == 1);
+    Ok(())
+}
+
+#[test]
+fn record_pipeline_test() -> Result<()> {
+    let paths: Vec<_> = (0..2)
+        .map(|index| DATA_DIR.join(format!("pipeline-{index}.tfrecord")))
+        .collect();
+    let examples = sample_examples();
+    for (path, chunk) in paths.iter().zip(examples.chunks(5)) {
+        let mut writer = ExampleWriter::create(path)?;
+        for example in chunk {
+            writer.send_ref(example)?;
+        }
+        writer.close()?;
+    }
+
+    // two epochs over multiple files, keeping odd records
+    let batches: Vec<Vec<i64>> = pipeline::repeat(Some(2), {
+        let paths = paths.clone();
+        move || Ok(ChainedRecordIter::from_paths(&paths, Default::default()))
+    })
+    .map(|example| example.map(|example| index_of(&example)))
+    .filter(|index| !matches!(index, Ok(index) if index % 2 == 0))
+    .skip(1)
+    .take(8)
+    .try_batch(3, true)
+    .prefetch(2)
+    .collect::<Result<_, _>>()?;
+    ensure!(batches == [vec![3, 5, 7], vec![9, 1, 3]]);
+
+    // zip and enumerate the records of two files
+    let pairs: Vec<_> = ExampleIter::open(&paths[0], Default::default())?
+        .zip(ExampleIter::open(&paths[1], Default::default())?)
+        .enumerate()
+        .map(|(index, (lhs, rhs))| Ok((index, index_of(&lhs?), index_of(&rhs?))))
+        .collect::<Result<Vec<_>, Error>>()?;
+    ensure!(pairs[4] == (4, 4, 9));
+
+    // load records from the indexer
+    let examples: Vec<_> = indexer::load_paths(&paths, Default::default())
+        .map(|index| index?.load::<tfrecord::Example>())
+        .flat_map(|example| example.map(|example| index_of(&example)))
+        .batch(4, false)
+        .collect();
+    ensure!(examples.len() == 3);
+    ensure!(examples[2] == [8, 9]);
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn prefetch_panic_test() -> Result<()> {
+    let mut iter = (0..4)
+        .inspect(|&index| assert!(index < 2, "upstream panic"))
+        .prefetch(4);
+    ensure!(iter.next() == Some(0));
+    ensure!(iter.next() == Some(1));
+
+    // the panic is resumed instead of ending the iterator
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| iter.next()));
+    ensure!(result.is_err());
+    Ok(())
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::{self, StreamExt as _, TryStreamExt as _};
+use std::fs;
+use tfrecord::{
+    pipeline, pipeline::PipelineStreamExt as _, BytesStream, BytesWriter, ChainedRecordStream,
+    Error,
+};
+
+#[async_std::test]
+async fn async_batch_test() -> Result<()> {
+    let batches: Vec<_> = stream::iter(0..7).batch(3, false).collect().await;
+    ensure!(batches == [vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+    let batches: Vec<_> = stream::iter(0..7).batch(3, true).collect().await;
+    ensure!(batches == [vec![0, 1, 2], vec![3, 4, 5]]);
+
+    let items = vec![Ok(0), Err(Error::UnexpectedEof), Ok(1), Ok(2)];
+    let batches: Vec<_> = stream::iter(items).try_batch(2, false).collect().await;
+    ensure!(batches.len() == 3);
+    ensure!(batches[0].is_err());
+    ensure!(batches[1].as_ref().unwrap() == &[0, 1]);
+    ensure!(batches[2].as_ref().unwrap() == &[2]);
+    Ok(())
+}
+
+#[async_std::test]
+async fn async_record_pipeline_test() -> Result<()> {
+    let paths: Vec<_> = (0..2)
+        .map(|index| DATA_DIR.join(format!("pipeline_async-{index}.tfrecord")))
+        .collect();
+    for (file_index, path) in paths.iter().enumerate() {
+        let mut writer = BytesWriter::create(path)?;
+        for index in 0..4u8 {
+            writer.send(vec![file_index as u8 * 4 + index])?;
+        }
+        writer.close()?;
+    }
+
+    let paths_ref = &paths;
+    let batches: Vec<Vec<u8>> = pipeline::repeat_async(Some(2), || async move {
+        Ok(ChainedRecordStream::<Vec<u8>>::from_paths(
+            paths_ref,
+            Default::default(),
+        ))
+    })
+    .map_ok(|bytes| bytes[0])
+    .skip(2)
+    .try_batch(5, false)
+    .try_collect()
+    .await?;
+    ensure!(batches == [vec![2, 3, 4, 5, 6], vec![7, 0, 1, 2, 3], vec![4, 5, 6, 7]]);
+
+    let values: Vec<_> = BytesStream::open(&paths[1], Default::default())
+        .await?
+        .prefetch(1)
+        .map_ok(|bytes| bytes[0])
+        .try_collect()
+        .await?;
+    ensure!(values == [4, 5, 6, 7]);
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn async_prefetch_panic_test() -> Result<()> {
+    use futures::FutureExt as _;
+
+    let mut stream = stream::iter(0..4)
+        .inspect(|&index| assert!(index < 2, "upstream panic"))
+        .prefetch(4);
+    ensure!(stream.next().await == Some(0));
+    ensure!(stream.next().await == Some(1));
+
+    // the panic is resumed instead of ending the stream
+    let result = std::panic::AssertUnwindSafe(stream.next())
+        .catch_unwind()
+        .await;
+    ensure!(result.is_err());
+    Ok(())
+}
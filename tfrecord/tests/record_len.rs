@@ -0,0 +1,113 @@
+pub mod common;
+
+use common::*;
+use std::io::Cursor;
+use tfrecord::{
+    indexer::RecordIndexerConfig, BytesIter, BytesWriter, Error as TfError, RecordReaderConfig,
+};
+
+#[test]
+fn max_record_len_test() -> Result<()> {
+    let path = DATA_DIR.join("max_record_len.tfrecord");
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        writer.send(vec![0; 10])?;
+        writer.send(vec![1; 100])?;
+    }
+
+    // reader
+    {
+        let config = RecordReaderConfig {
+            max_record_len: Some(50),
+            ..Default::default()
+        };
+        let mut iter = BytesIter::open(&path, config)?;
+        ensure!(iter.next().transpose()?.is_some());
+        let error = iter.next().unwrap().unwrap_err();
+        ensure!(matches!(
+            error.inner(),
+            TfError::RecordTooLarge {
+                len: 100,
+                max_len: 50,
+                offset: Some(26)
+            }
+        ));
+        ensure!(error.kind() == tfrecord::ErrorKind::RecordTooLarge);
+        let context = error.context().unwrap();
+        ensure!(context.path.as_deref() == Some(&*path));
+        ensure!(context.record_index == Some(1));
+        ensure!(context.offset == Some(26));
+    }
+
+    // indexer
+    {
+        let config = RecordIndexerConfig {
+            max_record_len: Some(50),
+            ..Default::default()
+        };
+        let results: Vec<_> = tfrecord::indexer::load_file(&*path, config)?.collect();
+        ensure!(results.len() == 2);
+        ensure!(matches!(
+            results[1].as_ref().map_err(TfError::inner),
+            Err(TfError::RecordTooLarge {
+                len: 100,
+                offset: Some(26),
+                ..
+            })
+        ));
+    }
+
+    // corrupted header without integrity check
+    {
+        let mut bytes = std::fs::read(&path)?;
+        bytes[26 + 7] = 0x7f;
+        let config = RecordReaderConfig {
+            check_integrity: false,
+            max_record_len: Some(1 << 20),
+            ..Default::default()
+        };
+        let results: Vec<_> = BytesIter::from_reader(Cursor::new(bytes), config).collect();
+        ensure!(matches!(
+            results[1].as_ref().map_err(TfError::inner),
+            Err(TfError::RecordTooLarge {
+                offset: Some(26),
+                ..
+            })
+        ));
+    }
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn io_default_max_len_test() -> Result<()> {
+    let mut writer = BytesWriter::from_writer(vec![])?;
+    writer.send(vec![1; 100])?;
+    let mut bytes = writer.close()?;
+
+    let record = tfrecord::io::sync::try_read_record(&mut bytes.as_slice(), true)?;
+    ensure!(record == Some(vec![1; 100]));
+    let result =
+        tfrecord::io::sync::try_read_record_with_max_len(&mut bytes.as_slice(), true, Some(50));
+    ensure!(matches!(
+        result,
+        Err(TfError::RecordTooLarge {
+            len: 100,
+            max_len: 50,
+            ..
+        })
+    ));
+
+    // a corrupted length is rejected by the default limit
+    bytes[7] = 0x7f;
+    let result = tfrecord::io::sync::try_read_len(&mut bytes.as_slice(), false);
+    ensure!(matches!(
+        result,
+        Err(TfError::RecordTooLarge {
+            max_len: tfrecord::io::DEFAULT_MAX_RECORD_LEN,
+            ..
+        })
+    ));
+    Ok(())
+}
@@ -0,0 +1,35 @@
+pub mod common;
+
+use common::*;
+use tfrecord::{BytesWriter, RecordReader};
+
+#[test]
+fn read_next_into_test() -> Result<()> {
+    let path = DATA_DIR.join("read_next_into.tfrecord");
+    let records: Vec<Vec<u8>> = vec![vec![1; 100], vec![2; 10], vec![3; 50]];
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for record in records.clone() {
+            writer.send(record)?;
+        }
+    }
+
+    let mut reader = RecordReader::open(&path, Default::default())?;
+    let mut buf = Vec::with_capacity(100);
+    let ptr = buf.as_ptr();
+    let mut expect_offset = 0;
+
+    for record in &records {
+        let offset = reader.read_next_into(&mut buf)?;
+        ensure!(offset == Some(expect_offset));
+        ensure!(&buf == record);
+        // the buffer is reused
+        ensure!(buf.as_ptr() == ptr);
+        expect_offset += record.len() as u64 + 16;
+    }
+    ensure!(reader.offset() == expect_offset);
+    ensure!(reader.read_next_into(&mut buf)?.is_none());
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::{
+    io::AsyncRead,
+    stream::{StreamExt as _, TryStreamExt as _},
+};
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tfrecord::{BytesAsyncWriter, BytesStream, BytesWriter, RecordAsyncReader};
+
+/// A reader that yields one byte per read and returns pending on every other poll.
+struct TrickleReader<'a> {
+    bytes: &'a [u8],
+    pending: bool,
+}
+
+impl AsyncRead for TrickleReader<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let Some((&byte, rest)) = self.bytes.split_first() else {
+            return Poll::Ready(Ok(0));
+        };
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        buf[0] = byte;
+        self.bytes = rest;
+        Poll::Ready(Ok(1))
+    }
+}
+
+#[async_std::test]
+async fn async_read_next_into_test() -> Result<()> {
+    let path = DATA_DIR.join("async_read_next_into.tfrecord");
+    let records: Vec<Vec<u8>> = vec![vec![1; 100], vec![2; 10], vec![3; 50]];
+    {
+        let mut writer = BytesAsyncWriter::create(&path).await?;
+        for record in records.clone() {
+            writer.send(record).await?;
+        }
+        writer.close().await?;
+    }
+
+    let mut reader = RecordAsyncReader::open(&path, Default::default()).await?;
+    let mut buf = vec![];
+    let mut expect_offset = 0;
+
+    for record in &records {
+        let offset = reader.read_next_into(&mut buf).await?;
+        ensure!(offset == Some(expect_offset));
+        ensure!(&buf == record);
+        expect_offset += record.len() as u64 + 16;
+    }
+    ensure!(reader.read_next_into(&mut buf).await?.is_none());
+
+    async_std::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn borrowed_stream_test() -> Result<()> {
+    let records: Vec<Vec<u8>> = vec![vec![1; 100], vec![], vec![3; 50]];
+    let mut bytes = vec![];
+    {
+        let mut writer = BytesWriter::from_writer(&mut bytes)?;
+        for record in records.clone() {
+            writer.send(record)?;
+        }
+        writer.flush()?;
+    }
+
+    // stream from a borrowed slice
+    let output: Vec<_> = BytesStream::from_reader(bytes.as_slice(), Default::default())
+        .try_collect()
+        .await?;
+    ensure!(output == records);
+
+    // resume partially read records after pending polls
+    let reader = TrickleReader {
+        bytes: &bytes,
+        pending: false,
+    };
+    let output: Vec<_> = BytesStream::from_reader(reader, Default::default())
+        .try_collect()
+        .await?;
+    ensure!(output == records);
+
+    // the stream ends after the first error
+    let truncated = &bytes[..(bytes.len() - 1)];
+    let output: Vec<_> = BytesStream::from_reader(truncated, Default::default())
+        .collect()
+        .await;
+    ensure!(output.len() == records.len());
+    ensure!(output[..2].iter().all(|result| result.is_ok()));
+    ensure!(output[2].is_err());
+
+    Ok(())
+}
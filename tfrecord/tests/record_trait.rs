@@ -0,0 +1,128 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{indexer, Error, Record, RecordIter, RecordWriter};
+
+/// A record type implementing only the borrowed encode and decode methods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+impl Record for Point {
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes: [u8; 8] = bytes.try_into().map_err(|_| Error::ConversionError {
+            desc: "a point takes 8 bytes".into(),
+        })?;
+        Ok(Self {
+            x: u32::from_le_bytes(bytes[..4].try_into().unwrap()),
+            y: u32::from_le_bytes(bytes[4..].try_into().unwrap()),
+        })
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        buf.extend_from_slice(&self.x.to_le_bytes());
+        buf.extend_from_slice(&self.y.to_le_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_record_test() -> Result<()> {
+    let path = DATA_DIR.join("record_trait.tfrecord");
+    let points: Vec<_> = (0..5)
+        .map(|index| Point {
+            x: index,
+            y: index * 2,
+        })
+        .collect();
+    {
+        let mut writer = RecordWriter::<Point, _>::create(&path)?;
+        for point in &points {
+            writer.send_ref(point)?;
+        }
+        writer.close()?;
+    }
+
+    let output: Vec<Point> =
+        RecordIter::open(&path, Default::default())?.collect::<Result<_, _>>()?;
+    ensure!(output == points);
+
+    let index = indexer::load_file(&*path, Default::default())?
+        .nth(3)
+        .unwrap()?;
+    ensure!(index.load::<Point>()? == points[3]);
+
+    // a record of wrong length is rejected by the decoder
+    ensure!(Point::decode(&[0; 3]).is_err());
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn compatibility_shims_test() -> Result<()> {
+    let point = Point { x: 1, y: 2 };
+    let bytes = Point::to_bytes(point)?;
+    ensure!(bytes == [1, 0, 0, 0, 2, 0, 0, 0]);
+    ensure!(Point::from_bytes(bytes)? == point);
+
+    let example: tfrecord::Example = vec![(
+        "value".to_string(),
+        tfrecord::Feature::from_i64_iter([1, 2, 3]),
+    )]
+    .into_iter()
+    .collect();
+    let mut buf = vec![];
+    example.encode(&mut buf)?;
+    ensure!(Some(buf.len()) == example.encoded_len());
+    ensure!(tfrecord::Example::to_bytes(example.clone())? == buf);
+    ensure!(<tfrecord::Example as Record>::decode(&buf)? == example);
+    Ok(())
+}
+
+/// A record type implementing only the owned methods of earlier versions.
+#[derive(Debug, Clone, PartialEq)]
+struct Legacy(String);
+
+impl Record for Legacy {
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        String::from_utf8(bytes)
+            .map(Self)
+            .map_err(|_| Error::ConversionError {
+                desc: "not a UTF-8 string".into(),
+            })
+    }
+
+    fn to_bytes(record: Self) -> Result<Vec<u8>, Error> {
+        Ok(record.0.into_bytes())
+    }
+}
+
+#[test]
+fn legacy_record_test() -> Result<()> {
+    let path = DATA_DIR.join("record_trait_legacy.tfrecord");
+    let records: Vec<_> = ["a", "bc", "def"]
+        .into_iter()
+        .map(|text| Legacy(text.to_string()))
+        .collect();
+    {
+        let mut writer = RecordWriter::<Legacy, _>::create(&path)?;
+        for record in records.clone() {
+            writer.send(record)?;
+        }
+        // encoding by reference is not available
+        ensure!(writer.send_ref(&records[0]).is_err());
+        writer.close()?;
+    }
+
+    let output: Vec<Legacy> =
+        RecordIter::open(&path, Default::default())?.collect::<Result<_, _>>()?;
+    ensure!(output == records);
+    ensure!(Legacy::decode(b"xyz")? == Legacy("xyz".to_string()));
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
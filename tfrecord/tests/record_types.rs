@@ -0,0 +1,132 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    indexer,
+    protobuf::{SequenceExample, TensorProto},
+    ProtoRecord, Record, RecordIter, RecordWriter,
+};
+
+#[test]
+fn proto_record_test() -> Result<()> {
+    let path = DATA_DIR.join("record_types_proto.tfrecord");
+    let tensors: Vec<_> = (0..4)
+        .map(|index| TensorProto {
+            float_val: vec![index as f32; index + 1],
+            ..Default::default()
+        })
+        .collect();
+    {
+        let mut writer = RecordWriter::<ProtoRecord<TensorProto>, _>::create(&path)?;
+        for tensor in &tensors {
+            writer.send(tensor.clone().into())?;
+        }
+        writer.close()?;
+    }
+
+    let output: Vec<_> =
+        RecordIter::<ProtoRecord<TensorProto>, _>::open(&path, Default::default())?
+            .map(|record| record.map(ProtoRecord::into_inner))
+            .collect::<Result<_, _>>()?;
+    ensure!(output == tensors);
+
+    let index = indexer::load_file(&*path, Default::default())?
+        .nth(2)
+        .unwrap()?;
+    ensure!(index.load::<ProtoRecord<TensorProto>>()?.0 == tensors[2]);
+
+    // the payload is plain protobuf
+    let bytes: Vec<Vec<u8>> =
+        RecordIter::open(&path, Default::default())?.collect::<Result<_, _>>()?;
+    ensure!(<ProtoRecord<TensorProto> as Record>::decode(&bytes[1])?.float_val == [1.0, 1.0]);
+    ensure!(<ProtoRecord<SequenceExample> as Record>::decode(&[0xff]).is_err());
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[cfg(feature = "with-serde")]
+mod serde_records {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use tfrecord::{BincodeRecord, JsonRecord};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        name: String,
+        values: Vec<i32>,
+    }
+
+    fn samples() -> Vec<Sample> {
+        (0..5)
+            .map(|index| Sample {
+                name: format!("sample-{index}"),
+                values: (0..index).collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn json_record_test() -> Result<()> {
+        let path = DATA_DIR.join("record_types_json.tfrecord");
+        let samples = samples();
+        {
+            let mut writer = RecordWriter::<JsonRecord<Sample>, _>::create(&path)?;
+            for sample in &samples {
+                writer.send(JsonRecord(sample.clone()))?;
+            }
+            writer.close()?;
+        }
+
+        let output: Vec<_> = RecordIter::<JsonRecord<Sample>, _>::open(&path, Default::default())?
+            .map(|record| record.map(JsonRecord::into_inner))
+            .collect::<Result<_, _>>()?;
+        ensure!(output == samples);
+
+        let index = indexer::load_file(&*path, Default::default())?
+            .nth(4)
+            .unwrap()?;
+        ensure!(index.load::<JsonRecord<Sample>>()?.0 == samples[4]);
+
+        let bytes: Vec<Vec<u8>> =
+            RecordIter::open(&path, Default::default())?.collect::<Result<_, _>>()?;
+        ensure!(bytes[0] == br#"{"name":"sample-0","values":[]}"#);
+        ensure!(<JsonRecord<Sample> as Record>::decode(b"{").is_err());
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn bincode_record_test() -> Result<()> {
+        let path = DATA_DIR.join("record_types_bincode.tfrecord");
+        let samples = samples();
+        {
+            let mut writer = RecordWriter::<BincodeRecord<Sample>, _>::create(&path)?;
+            for sample in &samples {
+                let record = BincodeRecord(sample.clone());
+                let mut buf = vec![];
+                record.encode(&mut buf)?;
+                ensure!(record.encoded_len() == Some(buf.len()));
+                writer.send(record)?;
+            }
+            writer.close()?;
+        }
+
+        let output: Vec<_> =
+            RecordIter::<BincodeRecord<Sample>, _>::open(&path, Default::default())?
+                .map(|record| record.map(BincodeRecord::into_inner))
+                .collect::<Result<_, _>>()?;
+        ensure!(output == samples);
+
+        let index = indexer::load_file(&*path, Default::default())?
+            .nth(1)
+            .unwrap()?;
+        ensure!(index.load::<BincodeRecord<Sample>>()?.0 == samples[1]);
+        ensure!(<BincodeRecord<Sample> as Record>::decode(&[1, 2]).is_err());
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+pub mod common;
+
+use common::*;
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+};
+use tfrecord::{BytesIter, BytesWriter, CorruptedRange, CorruptionKind, RecordReaderConfig};
+
+const RECORD_LEN: usize = 20;
+const FRAME_LEN: u64 = RECORD_LEN as u64 + 16;
+
+fn corrupted_file() -> Result<Vec<u8>> {
+    let path = DATA_DIR.join("recovery.tfrecord");
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for index in 0..10u8 {
+            writer.send(vec![index; RECORD_LEN])?;
+        }
+    }
+    let mut bytes = std::fs::read(&path)?;
+    std::fs::remove_file(&path)?;
+
+    // corrupt the data of record 3
+    bytes[FRAME_LEN as usize * 3 + 12 + 5] ^= 0xff;
+    // corrupt the length of record 7
+    bytes[FRAME_LEN as usize * 7] ^= 0xff;
+    // truncate record 9
+    bytes.truncate(bytes.len() - 10);
+
+    Ok(bytes)
+}
+
+#[test]
+fn recovery_test() -> Result<()> {
+    let bytes = corrupted_file()?;
+    let ranges = Arc::new(Mutex::new(vec![]));
+
+    // strict mode fails at the first corrupted record
+    {
+        let records: Vec<_> =
+            BytesIter::from_reader(Cursor::new(bytes.clone()), Default::default()).collect();
+        ensure!(records[..3].iter().all(|record| record.is_ok()));
+        ensure!(records[3].is_err());
+    }
+
+    // recovery mode skips corrupted records
+    {
+        let config = RecordReaderConfig {
+            skip_corrupted: true,
+            ..Default::default()
+        };
+        let records: Vec<_> = BytesIter::from_reader(Cursor::new(bytes), config)
+            .on_corrupted({
+                let ranges = ranges.clone();
+                move |range| ranges.lock().unwrap().push(range)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let expect: Vec<_> = [0u8, 1, 2, 4, 5, 6, 8]
+            .into_iter()
+            .map(|index| vec![index; RECORD_LEN])
+            .collect();
+        ensure!(records == expect);
+    }
+
+    let expect = vec![
+        CorruptedRange {
+            start: FRAME_LEN * 3,
+            end: FRAME_LEN * 4,
+            kind: CorruptionKind::DataChecksum,
+        },
+        CorruptedRange {
+            start: FRAME_LEN * 7,
+            end: FRAME_LEN * 8,
+            kind: CorruptionKind::LengthChecksum,
+        },
+        CorruptedRange {
+            start: FRAME_LEN * 9,
+            end: FRAME_LEN * 10 - 10,
+            kind: CorruptionKind::Truncated,
+        },
+    ];
+    ensure!(*ranges.lock().unwrap() == expect);
+
+    Ok(())
+}
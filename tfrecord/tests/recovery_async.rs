@@ -0,0 +1,53 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::{io::Cursor, stream::TryStreamExt as _};
+use std::sync::{Arc, Mutex};
+use tfrecord::{BytesAsyncWriter, BytesStream, CorruptionKind, RecordReaderConfig};
+
+#[async_std::test]
+async fn async_recovery_test() -> Result<()> {
+    let path = DATA_DIR.join("recovery_async.tfrecord");
+    let mut writer = BytesAsyncWriter::create(&path).await?;
+    for index in 0..10u8 {
+        writer.send(vec![index; 20]).await?;
+    }
+    writer.close().await?;
+    let mut bytes = async_std::fs::read(&path).await?;
+    async_std::fs::remove_file(&path).await?;
+
+    // corrupt the data of record 5 and truncate record 9
+    bytes[36 * 5 + 12] ^= 0xff;
+    bytes.truncate(bytes.len() - 1);
+
+    let ranges = Arc::new(Mutex::new(vec![]));
+    let config = RecordReaderConfig {
+        skip_corrupted: true,
+        ..Default::default()
+    };
+    let records: Vec<_> = BytesStream::from_reader(Cursor::new(bytes), config)
+        .on_corrupted({
+            let ranges = ranges.clone();
+            move |range| ranges.lock().unwrap().push(range)
+        })
+        .try_collect()
+        .await?;
+
+    let expect: Vec<_> = [0u8, 1, 2, 3, 4, 6, 7, 8]
+        .into_iter()
+        .map(|index| vec![index; 20])
+        .collect();
+    ensure!(records == expect);
+
+    let kinds: Vec<_> = ranges
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|range| range.kind)
+        .collect();
+    ensure!(kinds == [CorruptionKind::DataChecksum, CorruptionKind::Truncated]);
+
+    Ok(())
+}
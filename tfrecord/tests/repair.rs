@@ -0,0 +1,168 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    repair::{self, RepairConfig},
+    BytesIter, BytesWriter, Compression, CorruptionKind, RecordReaderConfig, RecordWriterConfig,
+};
+
+fn write_file(name: &str, num_records: usize) -> Result<std::path::PathBuf> {
+    let path = DATA_DIR.join(name);
+    let mut writer = BytesWriter::create(&path)?;
+    for index in 0..num_records {
+        writer.send(vec![index as u8; 10])?;
+    }
+    writer.flush()?;
+    Ok(path)
+}
+
+fn read_file(path: &std::path::Path, config: RecordReaderConfig) -> Result<Vec<Vec<u8>>> {
+    let records: Vec<_> = BytesIter::open(path, config)?.collect::<Result<_, _>>()?;
+    Ok(records)
+}
+
+#[test]
+fn truncate_torn_record_test() -> Result<()> {
+    let path = write_file("repair_truncate.tfrecord", 4)?;
+    let bytes = fs::read(&path)?;
+    fs::write(&path, &bytes[..bytes.len() - 5])?;
+
+    let report = repair::truncate_file(&path, Default::default())?;
+    ensure!(report.num_records == 3);
+    ensure!(report.input_len == 99 && report.output_len == 78);
+    ensure!(report.dropped_bytes() == 21);
+    ensure!(report.dropped[0].kind == CorruptionKind::Truncated);
+    ensure!(fs::metadata(&path)?.len() == 78);
+    ensure!(read_file(&path, Default::default())?.len() == 3);
+
+    // repairing again is a no-op
+    let report = repair::truncate_file(&path, Default::default())?;
+    ensure!(report.is_clean() && report.num_records == 3 && report.output_len == 78);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn truncate_at_first_corruption_test() -> Result<()> {
+    // records start at 0, 26, 52 and 78
+    let path = write_file("repair_truncate_middle.tfrecord", 4)?;
+    let mut bytes = fs::read(&path)?;
+    bytes[26 + 12] ^= 0xff;
+    fs::write(&path, &bytes)?;
+
+    let report = repair::truncate_file(&path, Default::default())?;
+    ensure!(report.num_records == 1);
+    ensure!(report.dropped.len() == 1);
+    ensure!(report.dropped[0].start == 26 && report.dropped[0].end == 104);
+    ensure!(report.dropped[0].kind == CorruptionKind::DataChecksum);
+    ensure!(fs::metadata(&path)?.len() == 26);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn truncate_refuses_foreign_file_test() -> Result<()> {
+    // the first record is corrupted
+    let path = write_file("repair_truncate_foreign.tfrecord", 3)?;
+    let mut bytes = fs::read(&path)?;
+    bytes[0] ^= 0xff;
+    fs::write(&path, &bytes)?;
+    ensure!(repair::truncate_file(&path, Default::default()).is_err());
+    ensure!(fs::read(&path)? == bytes);
+    fs::remove_file(&path)?;
+
+    // a compressed file read as uncompressed
+    let path = DATA_DIR.join("repair_truncate_foreign.tfrecord.gz");
+    {
+        let config = RecordWriterConfig {
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let mut writer = BytesWriter::create_with_config(&path, config)?;
+        for index in 0..10 {
+            writer.send(vec![index; 64])?;
+        }
+        writer.close()?;
+    }
+    let bytes = fs::read(&path)?;
+    ensure!(repair::truncate_file(&path, Default::default()).is_err());
+    ensure!(fs::read(&path)? == bytes);
+    fs::remove_file(&path)?;
+
+    // a file which is not in TFRecord format
+    let path = DATA_DIR.join("repair_truncate_foreign.txt");
+    let bytes = b"this is not a record file\n".repeat(10);
+    fs::write(&path, &bytes)?;
+    ensure!(repair::truncate_file(&path, Default::default()).is_err());
+    ensure!(fs::read(&path)? == bytes);
+    fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn salvage_test() -> Result<()> {
+    let input = write_file("repair_salvage_input.tfrecord", 5)?;
+    let output = DATA_DIR.join("repair_salvage_output.tfrecord.gz");
+    let mut bytes = fs::read(&input)?;
+    bytes[26 + 12] ^= 0xff;
+    bytes.truncate(bytes.len() - 1);
+    fs::write(&input, &bytes)?;
+
+    let config = RepairConfig {
+        writer: RecordWriterConfig {
+            compression: Compression::Gzip,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let report = repair::salvage_file(&input, &output, config)?;
+    ensure!(report.num_records == 3);
+    ensure!(report.input_len == 129);
+    ensure!(report.dropped.len() == 2);
+    ensure!(report.dropped[0].start == 26 && report.dropped[0].end == 52);
+    ensure!(report.dropped[1].start == 104 && report.dropped[1].end == 129);
+
+    let records = read_file(
+        &output,
+        RecordReaderConfig {
+            compression: Compression::Gzip,
+            ..Default::default()
+        },
+    )?;
+    ensure!(records == [vec![0; 10], vec![2; 10], vec![3; 10]]);
+
+    fs::remove_file(&input)?;
+    fs::remove_file(&output)?;
+    Ok(())
+}
+
+#[test]
+fn salvage_same_path_test() -> Result<()> {
+    let path = write_file("repair_salvage_same_path.tfrecord", 3)?;
+    let alias = DATA_DIR.join(".").join("repair_salvage_same_path.tfrecord");
+
+    ensure!(repair::salvage_file(&path, &path, Default::default()).is_err());
+    ensure!(repair::salvage_file(&path, &alias, Default::default()).is_err());
+    ensure!(read_file(&path, Default::default())?.len() == 3);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn truncate_compressed_test() -> Result<()> {
+    let config = RepairConfig {
+        compression: Compression::Gzip,
+        ..Default::default()
+    };
+    let path = write_file("repair_compressed.tfrecord", 2)?;
+    ensure!(repair::truncate_file(&path, config).is_err());
+    ensure!(fs::metadata(&path)?.len() == 52);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,96 @@
+pub mod common;
+
+use common::*;
+use std::{fs, io::Cursor};
+use tfrecord::{
+    indexer, BytesIter, BytesWriter, Compression, ErrorKind, RecordReader, RecordReaderConfig,
+};
+
+fn sample_records() -> Vec<Vec<u8>> {
+    (0..10u8)
+        .map(|index| vec![index; index as usize * 3 + 1])
+        .collect()
+}
+
+#[test]
+fn skip_and_seek_test() -> Result<()> {
+    let path = DATA_DIR.join("seek.tfrecord");
+    let records = sample_records();
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for record in &records {
+            writer.send_bytes(record)?;
+        }
+        writer.close()?;
+    }
+
+    let mut iter = BytesIter::open(&path, Default::default())?;
+    ensure!(iter.skip_records(3)? == 3);
+    ensure!(iter.next().transpose()? == Some(records[3].clone()));
+
+    iter.seek_to_record(7)?;
+    ensure!(iter.next().transpose()? == Some(records[7].clone()));
+
+    // seeking to the end of stream is allowed, but not beyond it
+    iter.seek_to_record(10)?;
+    ensure!(iter.next().is_none());
+    ensure!(iter.seek_to_record(11).is_err());
+
+    // rewind after the end of stream is reached
+    iter.seek_to_record(0)?;
+    let output: Vec<_> = iter.by_ref().collect::<Result<_, _>>()?;
+    ensure!(output == records);
+    iter.seek_to_record(0)?;
+    ensure!(iter.skip_records(100)? == 10);
+
+    let positions: Vec<_> = indexer::load_reader(Cursor::new(fs::read(&path)?), Default::default())
+        .collect::<Result<_, _>>()?;
+    iter.seek_to_position(positions[5])?;
+    ensure!(iter.next().transpose()? == Some(records[5].clone()));
+    ensure!(iter.next().transpose()? == Some(records[6].clone()));
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn skip_without_reading_data_test() -> Result<()> {
+    let records = sample_records();
+    let mut bytes = vec![];
+    {
+        let mut writer = BytesWriter::from_writer(&mut bytes)?;
+        for record in &records {
+            writer.send_bytes(record)?;
+        }
+    }
+
+    // corrupt the data of record #1, which is skipped unnoticed
+    bytes[17 + 12] ^= 0xff;
+    let mut reader = RecordReader::from_reader(Cursor::new(bytes.clone()), Default::default());
+    ensure!(reader.skip_records(2)? == 2);
+    ensure!(reader.read_next()? == Some(records[2].clone()));
+
+    // corrupted headers are still detected
+    bytes[17] ^= 0xff;
+    let mut reader = RecordReader::from_reader(Cursor::new(bytes.clone()), Default::default());
+    ensure!(reader.skip_records(2).is_err());
+
+    // the stream is moved back after the failure
+    let mut iter = BytesIter::from_reader(Cursor::new(bytes.clone()), Default::default());
+    ensure!(iter.skip_records(3).is_err());
+    ensure!(iter.next().transpose()? == Some(records[0].clone()));
+    let error = iter.next().unwrap().unwrap_err();
+    ensure!(error.kind() == ErrorKind::ChecksumMismatch);
+    ensure!(error.context().unwrap().record_index == Some(1));
+    ensure!(error.context().unwrap().offset == Some(17));
+
+    // compressed streams cannot be sought
+    let config = RecordReaderConfig {
+        compression: Compression::Gzip,
+        ..Default::default()
+    };
+    let mut iter = BytesIter::from_reader(Cursor::new(bytes), config);
+    ensure!(iter.skip_records(1).is_err());
+    ensure!(iter.seek_to_record(0).is_err());
+    Ok(())
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::{io::Cursor, stream::TryStreamExt as _};
+use std::fs;
+use tfrecord::{indexer, BytesStream, BytesWriter, ErrorKind, RecordAsyncReader};
+
+#[async_std::test]
+async fn async_skip_and_seek_test() -> Result<()> {
+    let path = DATA_DIR.join("seek_async.tfrecord");
+    let records: Vec<Vec<u8>> = (0..10u8)
+        .map(|index| vec![index; index as usize * 3 + 1])
+        .collect();
+    {
+        let mut writer = BytesWriter::create(&path)?;
+        for record in &records {
+            writer.send_bytes(record)?;
+        }
+        writer.close()?;
+    }
+
+    let mut stream = BytesStream::open(&path, Default::default()).await?;
+    ensure!(stream.skip_records(3).await? == 3);
+    ensure!(stream.try_next().await? == Some(records[3].clone()));
+
+    stream.seek_to_record(10).await?;
+    ensure!(stream.try_next().await?.is_none());
+    ensure!(stream.seek_to_record(11).await.is_err());
+
+    stream.seek_to_record(0).await?;
+    let output: Vec<_> = (&mut stream).try_collect().await?;
+    ensure!(output == records);
+
+    let positions: Vec<_> = indexer::load_file(&*path, Default::default())?
+        .map(|index| index.map(|index| index.offset))
+        .collect::<Result<_, _>>()?;
+    let position = indexer::Position {
+        offset: positions[8],
+        len: records[8].len(),
+    };
+    stream.seek_to_position(position).await?;
+    ensure!(stream.try_next().await? == Some(records[8].clone()));
+
+    let mut reader =
+        RecordAsyncReader::from_reader(Cursor::new(fs::read(&path)?), Default::default());
+    ensure!(reader.skip_records(100).await? == 10);
+    ensure!(reader.read_next().await?.is_none());
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn async_skip_corrupted_header_test() -> Result<()> {
+    let records: Vec<Vec<u8>> = (0..3u8).map(|index| vec![index; 10]).collect();
+    let mut bytes = vec![];
+    {
+        let mut writer = BytesWriter::from_writer(&mut bytes)?;
+        for record in &records {
+            writer.send_bytes(record)?;
+        }
+    }
+
+    // corrupt the header of record #1
+    bytes[26] ^= 0xff;
+    let mut stream = BytesStream::from_reader(Cursor::new(bytes), Default::default());
+    ensure!(stream.skip_records(3).await.is_err());
+
+    // the stream is moved back after the failure
+    ensure!(stream.try_next().await? == Some(records[0].clone()));
+    let error = stream.try_next().await.unwrap_err();
+    ensure!(error.kind() == ErrorKind::ChecksumMismatch);
+    ensure!(error.context().unwrap().record_index == Some(1));
+    ensure!(error.context().unwrap().offset == Some(26));
+    Ok(())
+}
@@ -0,0 +1,173 @@
+pub mod common;
+
+use common::*;
+use std::{fs, path::Path};
+use tfrecord::{
+    indexer::{self, IndexFormat},
+    BytesIter, RecordReaderConfig, RecordWriterConfig, ShardManifest, ShardPolicy, ShardedWriter,
+    ShardedWriterConfig,
+};
+
+fn write_shards(name: &str, policy: ShardPolicy, num_records: u8) -> Result<ShardManifest> {
+    let dir = DATA_DIR.join(name);
+    fs::create_dir_all(&dir)?;
+    let config = ShardedWriterConfig {
+        policy,
+        ..Default::default()
+    };
+    let mut writer = ShardedWriter::<Vec<u8>>::create(dir.join("part"), config)?;
+    for index in 0..num_records {
+        writer.send(vec![index; 10])?;
+    }
+    let manifest = writer.close()?;
+    Ok(manifest)
+}
+
+fn read_shard(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let records: Vec<_> =
+        BytesIter::open(path, RecordReaderConfig::default())?.collect::<Result<_, _>>()?;
+    Ok(records)
+}
+
+#[test]
+fn sharded_max_records_test() -> Result<()> {
+    let manifest = write_shards("sharded_max_records", ShardPolicy::MaxRecords(4), 10)?;
+    let dir = DATA_DIR.join("sharded_max_records");
+
+    let counts: Vec<_> = manifest
+        .shards
+        .iter()
+        .map(|shard| shard.num_records)
+        .collect();
+    ensure!(counts == [4, 4, 2]);
+    ensure!(manifest.num_records() == 10);
+    for (index, shard) in manifest.shards.iter().enumerate() {
+        ensure!(shard.path == dir.join(format!("part-{index:05}-of-00003.tfrecord")));
+        ensure!(shard.num_bytes == shard.num_records as u64 * 26);
+    }
+
+    let records: Vec<_> = manifest
+        .paths()
+        .map(read_shard)
+        .collect::<Result<Vec<_>>>()?
+        .concat();
+    ensure!(records == (0..10).map(|index| vec![index; 10]).collect::<Vec<_>>());
+
+    // no temporary files are left
+    ensure!(fs::read_dir(&dir)?.count() == 3);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn sharded_max_bytes_test() -> Result<()> {
+    // each record takes 26 bytes
+    let manifest = write_shards("sharded_max_bytes", ShardPolicy::MaxBytes(60), 5)?;
+    let counts: Vec<_> = manifest
+        .shards
+        .iter()
+        .map(|shard| shard.num_records)
+        .collect();
+    ensure!(counts == [2, 2, 1]);
+    ensure!(manifest.shards.iter().all(|shard| shard.num_bytes <= 60));
+
+    fs::remove_dir_all(DATA_DIR.join("sharded_max_bytes"))?;
+    Ok(())
+}
+
+#[test]
+fn sharded_round_robin_test() -> Result<()> {
+    let manifest = write_shards("sharded_round_robin", ShardPolicy::RoundRobin(3), 7)?;
+    let dir = DATA_DIR.join("sharded_round_robin");
+
+    let counts: Vec<_> = manifest
+        .shards
+        .iter()
+        .map(|shard| shard.num_records)
+        .collect();
+    ensure!(counts == [3, 2, 2]);
+    ensure!(manifest.shards[1].path == dir.join("part-00001-of-00003.tfrecord"));
+    ensure!(read_shard(&manifest.shards[1].path)? == [vec![1; 10], vec![4; 10]]);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn sharded_invalid_policy_test() -> Result<()> {
+    let config = ShardedWriterConfig {
+        policy: ShardPolicy::RoundRobin(0),
+        ..Default::default()
+    };
+    let result = ShardedWriter::<Vec<u8>>::create(DATA_DIR.join("sharded_invalid"), config);
+    ensure!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn sharded_index_sidecar_test() -> Result<()> {
+    let dir = DATA_DIR.join("sharded_index_sidecar");
+    fs::create_dir_all(&dir)?;
+    let config = ShardedWriterConfig {
+        policy: ShardPolicy::MaxRecords(2),
+        record_writer: RecordWriterConfig {
+            index: Some(IndexFormat::Dali),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut writer = ShardedWriter::<Vec<u8>>::create(dir.join("part"), config)?;
+    for index in 0..3 {
+        writer.send(vec![index; 10])?;
+    }
+    let manifest = writer.close()?;
+
+    for path in manifest.paths() {
+        let index_path = IndexFormat::Dali.sidecar_path(path);
+        ensure!(indexer::load_index(path, &index_path, IndexFormat::Dali)?.is_some());
+    }
+    ensure!(fs::read_dir(&dir)?.count() == 4);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn sharded_create_failure_test() -> Result<()> {
+    let dir = DATA_DIR.join("sharded_create_failure");
+    fs::create_dir_all(&dir)?;
+    let config = ShardedWriterConfig {
+        policy: ShardPolicy::MaxRecords(1),
+        ..Default::default()
+    };
+    let mut writer = ShardedWriter::<Vec<u8>>::create(dir.join("part"), config)?;
+    writer.send(vec![0; 10])?;
+
+    // a directory in place of the next shard file makes its creation fail
+    let blocker = dir.join("part-00001.tfrecord.tmp");
+    fs::create_dir(&blocker)?;
+    ensure!(writer.send(vec![1; 10]).is_err());
+    ensure!(writer.send(vec![1; 10]).is_err());
+
+    fs::remove_dir(&blocker)?;
+    writer.send(vec![1; 10])?;
+    writer.send(vec![2; 10])?;
+    let manifest = writer.close()?;
+
+    let counts: Vec<_> = manifest
+        .shards
+        .iter()
+        .map(|shard| shard.num_records)
+        .collect();
+    ensure!(counts == [1, 1, 1]);
+    let records: Vec<_> = manifest
+        .paths()
+        .map(read_shard)
+        .collect::<Result<Vec<_>>>()?
+        .concat();
+    ensure!(records == [vec![0; 10], vec![1; 10], vec![2; 10]]);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::stream::TryStreamExt as _;
+use tfrecord::{
+    BytesStream, Compression, RecordReaderConfig, RecordWriterConfig, ShardPolicy,
+    ShardedAsyncWriter, ShardedWriterConfig,
+};
+
+#[async_std::test]
+async fn async_sharded_writer_test() -> Result<()> {
+    let dir = DATA_DIR.join("sharded_async");
+    async_std::fs::create_dir_all(&dir).await?;
+
+    let config = ShardedWriterConfig {
+        policy: ShardPolicy::MaxRecords(3),
+        suffix: ".tfrecord.gz".into(),
+        record_writer: RecordWriterConfig {
+            compression: Compression::Gzip,
+            ..Default::default()
+        },
+    };
+    let mut writer = ShardedAsyncWriter::<Vec<u8>>::create(dir.join("part"), config).await?;
+    for index in 0..8u8 {
+        writer.send(vec![index; 10]).await?;
+    }
+    let manifest = writer.close().await?;
+
+    ensure!(manifest.shards.len() == 3);
+    ensure!(manifest.shards[2].path == dir.join("part-00002-of-00003.tfrecord.gz"));
+
+    let mut records = vec![];
+    for path in manifest.paths() {
+        let config = RecordReaderConfig {
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let shard: Vec<_> = BytesStream::open(path, config).await?.try_collect().await?;
+        records.extend(shard);
+    }
+    ensure!(records == (0..8).map(|index| vec![index; 10]).collect::<Vec<_>>());
+
+    async_std::fs::remove_dir_all(&dir).await?;
+    Ok(())
+}
@@ -5,7 +5,7 @@
     feature = "with-ndarray"
 ))]
 
-mod common;
+pub mod common;
 
 use common::*;
 use rand::seq::SliceRandom;
@@ -0,0 +1,308 @@
+#![cfg(feature = "tokio")]
+
+pub mod common;
+
+use common::*;
+use futures::TryStreamExt as _;
+use std::time::Duration;
+use tfrecord::{
+    indexer, BytesTokioStream, BytesTokioWriter, ChainedRecordStream, Compression,
+    EventTokioStream, EventTokioWriter, FollowConfig, FollowStream, IndexedAsyncDataset,
+    IndexedDataset, InterleaveConfig, InterleaveRecordStream, RecordReaderConfig,
+    RecordTokioReader, RecordWriterConfig,
+};
+
+#[tokio::test]
+async fn tokio_round_trip_test() -> Result<()> {
+    let path = DATA_DIR.join("tokio_round_trip.tfrecord");
+    let records: Vec<Vec<u8>> = vec![vec![1; 100], vec![2; 10], vec![3; 50]];
+    {
+        let mut writer = BytesTokioWriter::create(&path).await?;
+        for record in records.clone() {
+            writer.send(record).await?;
+        }
+        writer.close().await?;
+    }
+
+    let stream = BytesTokioStream::open(&path, Default::default()).await?;
+    let output: Vec<_> = stream.try_collect().await?;
+    ensure!(output == records);
+
+    let indexes: Vec<_> = indexer::load_file_tokio(&path, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    ensure!(indexes.len() == records.len());
+    for (index, record) in indexes.iter().zip(&records) {
+        let output: Vec<u8> = index.load_tokio().await?;
+        ensure!(&output == record);
+    }
+
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_compressed_test() -> Result<()> {
+    let path = DATA_DIR.join("tokio_compressed.tfrecord.gz");
+    let records: Vec<Vec<u8>> = (0..20u8).map(|index| vec![index; 64]).collect();
+    let config = RecordWriterConfig {
+        compression: Compression::Gzip,
+        ..Default::default()
+    };
+    {
+        let mut writer = BytesTokioWriter::create_with_config(&path, config).await?;
+        for record in records.clone() {
+            writer.send(record).await?;
+        }
+        writer.close().await?;
+    }
+
+    let mut reader = RecordTokioReader::open(
+        &path,
+        RecordReaderConfig {
+            compression: Compression::Gzip,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let mut buf = vec![];
+    for record in &records {
+        ensure!(reader.read_next_into(&mut buf).await?.is_some());
+        ensure!(&buf == record);
+    }
+    ensure!(reader.read_next_into(&mut buf).await?.is_none());
+
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_event_writer_test() -> Result<()> {
+    let path = DATA_DIR.join("tokio_event_writer.tfevents");
+    {
+        let mut writer = EventTokioWriter::create(&path, Default::default()).await?;
+        writer.write_scalar("loss", 0, 1.5).await?;
+        writer.write_scalar("loss", 1, 0.5).await?;
+        writer.close().await?;
+    }
+
+    let bytes = tokio::fs::read(&path).await?;
+    let steps: Vec<_> = EventTokioStream::from_reader(bytes.as_slice(), Default::default())
+        .map_ok(|event| event.step)
+        .try_collect()
+        .await?;
+    ensure!(steps == [0, 1]);
+
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_pattern_test() -> Result<()> {
+    let dir = DATA_DIR.join("tokio_pattern");
+    tokio::fs::create_dir_all(&dir).await?;
+    for shard in 0..2u8 {
+        let path = dir.join(format!("part-{shard:05}-of-00002"));
+        let mut writer = BytesTokioWriter::create(path).await?;
+        writer.send(vec![shard; 10]).await?;
+        writer.close().await?;
+    }
+
+    let pattern = format!("{}/part@2", dir.to_str().unwrap());
+    let records: Vec<Vec<u8>> =
+        ChainedRecordStream::open_pattern_tokio(&pattern, Default::default())
+            .await?
+            .try_collect()
+            .await?;
+    ensure!(records == [vec![0; 10], vec![1; 10]]);
+
+    let indexes: Vec<_> = indexer::load_pattern_tokio(&pattern, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    ensure!(indexes.len() == 2);
+
+    tokio::fs::remove_dir_all(&dir).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_follow_test() -> Result<()> {
+    let path = DATA_DIR.join("tokio_follow.tfrecord");
+    let mut writer = BytesTokioWriter::create(&path).await?;
+    writer.send(vec![1; 10]).await?;
+    writer.flush().await?;
+
+    let config = FollowConfig {
+        poll_interval: Duration::from_millis(10),
+        idle_timeout: Some(Duration::from_millis(300)),
+    };
+    let stream = FollowStream::<Vec<u8>>::open_tokio(&path, Default::default(), config).await?;
+    let reader = tokio::spawn(stream.try_collect::<Vec<_>>());
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    writer.send(vec![2; 10]).await?;
+    writer.close().await?;
+
+    let output = reader.await??;
+    ensure!(output == [vec![1; 10], vec![2; 10]]);
+
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_append_test() -> Result<()> {
+    let path = DATA_DIR.join("tokio_append.tfrecord");
+    let _ = tokio::fs::remove_file(&path).await;
+
+    for index in 0..3u8 {
+        let (mut writer, info) = BytesTokioWriter::open_append(&path).await?;
+        ensure!(info.num_records == index as usize);
+        writer.send(vec![index; 10]).await?;
+        writer.close().await?;
+    }
+
+    let stream = BytesTokioStream::open(&path, Default::default()).await?;
+    let output: Vec<_> = stream.try_collect().await?;
+    ensure!(output == [vec![0; 10], vec![1; 10], vec![2; 10]]);
+
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_seek_test() -> Result<()> {
+    let path = DATA_DIR.join("tokio_seek.tfrecord");
+    let records: Vec<Vec<u8>> = (0..5u8).map(|index| vec![index; 10]).collect();
+    {
+        let mut writer = BytesTokioWriter::create(&path).await?;
+        for record in &records {
+            writer.send_bytes(record).await?;
+        }
+        writer.close().await?;
+    }
+
+    let mut stream = BytesTokioStream::open(&path, Default::default()).await?;
+    ensure!(stream.skip_records(2).await? == 2);
+    ensure!(stream.try_next().await? == Some(records[2].clone()));
+    stream.seek_to_record(4).await?;
+    ensure!(stream.try_next().await? == Some(records[4].clone()));
+
+    // the stream is moved back after failing on a corrupted header
+    let mut bytes = tokio::fs::read(&path).await?;
+    bytes[26] ^= 0xff;
+    let mut stream = BytesTokioStream::from_reader(std::io::Cursor::new(bytes), Default::default());
+    ensure!(stream.skip_records(3).await.is_err());
+    ensure!(stream.try_next().await? == Some(records[0].clone()));
+    let error = stream.try_next().await.unwrap_err();
+    ensure!(error.context().unwrap().record_index == Some(1));
+    ensure!(error.context().unwrap().offset == Some(26));
+
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_checkpoint_test() -> Result<()> {
+    let paths: Vec<_> = (0..2)
+        .map(|index| DATA_DIR.join(format!("tokio_checkpoint-{index}.tfrecord")))
+        .collect();
+    let records: Vec<Vec<u8>> = (0..6u8).map(|index| vec![index; 10]).collect();
+    for (path, chunk) in paths.iter().zip(records.chunks(3)) {
+        let mut writer = BytesTokioWriter::create(path).await?;
+        for record in chunk {
+            writer.send_bytes(record).await?;
+        }
+        writer.close().await?;
+    }
+
+    let mut stream = ChainedRecordStream::<Vec<u8>>::from_paths_tokio(&paths, Default::default());
+    let first = stream.try_next().await?;
+    let second = stream.try_next().await?;
+    let checkpoint = stream.checkpoint();
+    ensure!(checkpoint.file_records == 2);
+
+    let rest: Vec<_> = ChainedRecordStream::<Vec<u8>>::from_checkpoint_tokio(
+        &paths,
+        Default::default(),
+        checkpoint,
+    )?
+    .try_collect()
+    .await?;
+    let output: Vec<_> = [first, second].into_iter().flatten().chain(rest).collect();
+    ensure!(output == records);
+
+    for path in paths {
+        tokio::fs::remove_file(path).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_prefetch_test() -> Result<()> {
+    use futures::StreamExt as _;
+    use tfrecord::pipeline::PipelineStreamExt as _;
+
+    let items: Vec<_> = futures::stream::iter(0..10)
+        .prefetch_tokio(3)
+        .batch(4, true)
+        .collect()
+        .await;
+    ensure!(items == [vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_interleave_test() -> Result<()> {
+    let paths: Vec<_> = (0..3)
+        .map(|index| DATA_DIR.join(format!("tokio_interleave-{index}.tfrecord")))
+        .collect();
+    for (file_index, path) in paths.iter().enumerate() {
+        let mut writer = tfrecord::BytesWriter::create(path)?;
+        for index in 0..2u8 {
+            writer.send(vec![file_index as u8 * 10 + index])?;
+        }
+        writer.close()?;
+    }
+
+    let config = InterleaveConfig {
+        cycle_length: Some(3),
+        ..Default::default()
+    };
+    let values: Vec<_> =
+        InterleaveRecordStream::<Vec<u8>>::from_paths_tokio(&paths, Default::default(), config)?
+            .map_ok(|record| record[0])
+            .try_collect()
+            .await?;
+    ensure!(values == [0, 10, 20, 1, 11, 21]);
+
+    for path in paths {
+        tokio::fs::remove_file(path).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokio_indexed_dataset_test() -> Result<()> {
+    let path = DATA_DIR.join("tokio_indexed_dataset.tfrecord");
+    let records: Vec<_> = (0..4u8).map(|index| vec![index; 8]).collect();
+    let mut writer = BytesTokioWriter::create(&path).await?;
+    for record in records.clone() {
+        writer.send(record).await?;
+    }
+    writer.close().await?;
+
+    let indexes: Vec<_> = indexer::load_file_tokio(&path, Default::default())
+        .await?
+        .try_collect()
+        .await?;
+    let dataset: IndexedAsyncDataset<Vec<u8>> =
+        IndexedDataset::from_indexes(indexes, Default::default()).into();
+    ensure!(dataset.get(3).await? == records[3]);
+    ensure!(dataset.get_range(0..2).await? == records[0..2]);
+
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
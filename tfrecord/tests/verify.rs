@@ -0,0 +1,118 @@
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    verify::{self, VerifyConfig},
+    BytesWriter, CorruptionKind,
+};
+
+fn write_file(name: &str, lens: &[usize]) -> Result<std::path::PathBuf> {
+    let path = DATA_DIR.join(name);
+    let mut writer = BytesWriter::create(&path)?;
+    for (index, &len) in lens.iter().enumerate() {
+        writer.send(vec![index as u8; len])?;
+    }
+    writer.flush()?;
+    Ok(path)
+}
+
+#[test]
+fn verify_clean_file_test() -> Result<()> {
+    let path = write_file("verify_clean.tfrecord", &[10, 30, 20])?;
+    let report = verify::verify_file(&path, &Default::default())?;
+
+    ensure!(report.is_ok() && report.clean_end);
+    ensure!(report.path.as_deref() == Some(path.as_path()));
+    ensure!(report.num_records == 3);
+    ensure!(report.total_bytes == 60);
+    ensure!(report.min_record_len == Some(10));
+    ensure!(report.max_record_len == Some(30));
+    ensure!(report.mean_record_len() == Some(20.0));
+    ensure!(report.stream_len == 60 + 3 * 16);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn verify_corrupted_file_test() -> Result<()> {
+    // records start at 0, 26, 52, 78, 104 and 130
+    let path = write_file("verify_corrupted.tfrecord", &[10; 6])?;
+    let mut bytes = fs::read(&path)?;
+    bytes[26 + 9] ^= 0xff; // length checksum of the second record
+    bytes[78 + 12] ^= 0xff; // data of the fourth record
+    bytes.truncate(bytes.len() - 3); // truncated tail
+    fs::write(&path, &bytes)?;
+
+    let report = verify::verify_file(&path, &Default::default())?;
+    ensure!(report.num_records == 3);
+    ensure!(report.stream_len == 153);
+    ensure!(!report.is_ok() && !report.clean_end);
+
+    let kinds: Vec<_> = report.corruptions.iter().map(|range| range.kind).collect();
+    ensure!(
+        kinds
+            == [
+                CorruptionKind::LengthChecksum,
+                CorruptionKind::DataChecksum,
+                CorruptionKind::Truncated
+            ]
+    );
+    let first = report.first_corruption().unwrap();
+    ensure!(first.start == 26 && first.end == 52);
+    ensure!(report.corruptions[1].start == 78 && report.corruptions[1].end == 104);
+    ensure!(report.corruptions[2].start == 130 && report.corruptions[2].end == 153);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn verify_paths_test() -> Result<()> {
+    let paths: Vec<_> = (0..4)
+        .map(|index| write_file(&format!("verify_paths_{index}.tfrecord"), &[index + 1; 3]))
+        .collect::<Result<_>>()?;
+    fs::write(&paths[2], b"garbage")?;
+
+    let config = VerifyConfig {
+        num_threads: Some(2),
+        ..Default::default()
+    };
+    let reports = verify::verify_paths(&paths, config)?;
+    ensure!(reports.len() == 4);
+    for (index, report) in reports.iter().enumerate() {
+        ensure!(report.path.as_ref() == Some(&paths[index]));
+        ensure!(report.is_ok() == (index != 2));
+    }
+    ensure!(reports[3].total_bytes == 12);
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn verify_default_max_len_test() -> Result<()> {
+    const CASTAGNOLI: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+
+    // a record followed by a header declaring a huge length with a valid checksum
+    let mut bytes = vec![];
+    let mut writer = BytesWriter::from_writer(&mut bytes)?;
+    writer.send(vec![0; 10])?;
+    writer.flush()?;
+    drop(writer);
+    let len_buf = (1u64 << 40).to_le_bytes();
+    let cksum = CASTAGNOLI.checksum(&len_buf);
+    let cksum = ((cksum >> 15) | (cksum << 17)).wrapping_add(0xa282ead8);
+    bytes.extend_from_slice(&len_buf);
+    bytes.extend_from_slice(&cksum.to_le_bytes());
+    bytes.extend_from_slice(&[0; 10]);
+
+    let report = verify::verify_reader(bytes.as_slice(), &Default::default())?;
+    ensure!(report.num_records == 1);
+    let first = report.first_corruption().unwrap();
+    ensure!(first.kind == CorruptionKind::TooLarge && first.start == 26);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+pub mod common;
+
+use common::*;
+use std::io::{self, IoSlice, Write};
+use tfrecord::{
+    protobuf::Example, BytesIter, BytesWriter, ExampleIter, ExampleWriter, Feature,
+    RecordReaderConfig,
+};
+
+/// A writer accepting a few bytes per call, interrupted every other call.
+#[derive(Default)]
+struct ChunkWriter {
+    bytes: Vec<u8>,
+    interrupt: bool,
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        let len = buf.len().min(5);
+        self.bytes.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let buf = bufs
+            .iter()
+            .find(|buf| !buf.is_empty())
+            .map_or(&[][..], |buf| buf);
+        self.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn borrowed_write_test() -> Result<()> {
+    let records: Vec<Vec<u8>> = vec![vec![1; 17], vec![], vec![2; 3]];
+
+    let expect = {
+        let mut writer = BytesWriter::from_writer(vec![])?;
+        for record in records.clone() {
+            writer.send(record)?;
+        }
+        writer.close()?
+    };
+    let output = {
+        let mut writer = BytesWriter::from_writer(ChunkWriter::default())?;
+        for record in &records {
+            writer.send_bytes(record)?;
+        }
+        writer.close()?.bytes
+    };
+    ensure!(output == expect);
+
+    let output: Vec<_> = BytesIter::from_reader(output.as_slice(), RecordReaderConfig::default())
+        .collect::<Result<_, _>>()?;
+    ensure!(output == records);
+    Ok(())
+}
+
+#[test]
+fn example_send_ref_test() -> Result<()> {
+    let examples: Vec<Example> = (0..3)
+        .map(|index| {
+            let example: Example = vec![("value".to_string(), Feature::from_i64_iter(0..index))]
+                .into_iter()
+                .collect();
+            example
+        })
+        .collect();
+
+    let mut writer = ExampleWriter::from_writer(vec![])?;
+    for example in &examples {
+        writer.send_ref(example)?;
+    }
+    let bytes = writer.close()?;
+
+    let output: Vec<Example> =
+        ExampleIter::from_reader(bytes.as_slice(), Default::default()).collect::<Result<_, _>>()?;
+    ensure!(output == examples);
+    Ok(())
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use futures::io::Cursor;
+use tfrecord::{protobuf::Example, BytesAsyncWriter, ExampleAsyncWriter, Feature};
+
+#[async_std::test]
+async fn async_borrowed_write_test() -> Result<()> {
+    let records: Vec<Vec<u8>> = vec![vec![1; 17], vec![], vec![2; 3]];
+
+    let mut owned = BytesAsyncWriter::from_writer(Cursor::new(vec![]))?;
+    let mut borrowed = BytesAsyncWriter::from_writer(Cursor::new(vec![]))?;
+    for record in &records {
+        let expect = owned.send_with_position(record.clone()).await?;
+        ensure!(borrowed.send_bytes(record).await? == expect);
+    }
+    owned.close().await?;
+    borrowed.close().await?;
+    ensure!(owned.stats() == borrowed.stats());
+
+    let example: Example = vec![("value".to_string(), Feature::from_f32_iter([1.0, 2.0]))]
+        .into_iter()
+        .collect();
+    let mut owned = ExampleAsyncWriter::from_writer(Cursor::new(vec![]))?;
+    let mut borrowed = ExampleAsyncWriter::from_writer(Cursor::new(vec![]))?;
+    for _ in 0..2 {
+        let expect = owned.send_with_position(example.clone()).await?;
+        ensure!(borrowed.send_ref(&example).await? == expect);
+    }
+    owned.flush().await?;
+    borrowed.flush().await?;
+    ensure!(owned.stats() == borrowed.stats());
+    Ok(())
+}
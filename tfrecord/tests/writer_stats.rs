@@ -0,0 +1,106 @@
+pub mod common;
+
+use common::*;
+use std::{fs, path::PathBuf};
+use tfrecord::{
+    indexer::{self, IndexFormat},
+    BytesWriter, Compression, RecordWriterConfig,
+};
+
+#[test]
+fn writer_position_test() -> Result<()> {
+    let path = DATA_DIR.join("writer_position.tfrecord");
+    let mut writer = BytesWriter::create(&path)?;
+    let positions: Vec<_> = [10, 0, 30]
+        .into_iter()
+        .map(|len| writer.send_with_position(vec![1; len]))
+        .collect::<Result<_, _>>()?;
+    writer.flush()?;
+
+    let stats = writer.stats();
+    ensure!(stats.num_records == 3);
+    ensure!(stats.payload_bytes == 40);
+    ensure!(stats.stream_bytes == 88);
+    ensure!(stats.written_bytes == 88);
+    writer.close()?;
+
+    let expect: Vec<_> = indexer::load_reader(
+        std::io::BufReader::new(fs::File::open(&path)?),
+        Default::default(),
+    )
+    .collect::<Result<_, _>>()?;
+    ensure!(positions == expect);
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn compressed_writer_stats_test() -> Result<()> {
+    let config = RecordWriterConfig {
+        compression: Compression::Gzip,
+        ..Default::default()
+    };
+    let mut writer = BytesWriter::from_writer_with_config(vec![], config)?;
+    for _ in 0..100 {
+        writer.send(vec![0; 100])?;
+    }
+    writer.flush()?;
+
+    let stats = writer.stats();
+    ensure!(stats.stream_bytes == 11600);
+    ensure!(stats.written_bytes > 0 && stats.written_bytes < stats.stream_bytes);
+
+    let bytes = writer.close()?;
+    ensure!(bytes.len() as u64 >= stats.written_bytes);
+
+    let config = RecordWriterConfig {
+        compression: Compression::Gzip,
+        index: Some(IndexFormat::Dali),
+        ..Default::default()
+    };
+    let path = DATA_DIR.join("writer_stats_compressed.tfrecord.gz");
+    ensure!(BytesWriter::create_with_config(&path, config).is_err());
+    Ok(())
+}
+
+#[test]
+fn writer_index_sidecar_test() -> Result<()> {
+    for format in [IndexFormat::Dali, IndexFormat::Binary] {
+        let path = DATA_DIR.join(format!("writer_sidecar_{format:?}.tfrecord"));
+        let index_path = format.sidecar_path(&path);
+        let tmp_path = PathBuf::from(format!("{}.tmp", index_path.display()));
+        let config = RecordWriterConfig {
+            index: Some(format),
+            ..Default::default()
+        };
+
+        let mut positions = vec![];
+        {
+            let mut writer = BytesWriter::create_with_config(&path, config.clone())?;
+            for len in [5, 10] {
+                positions.push(writer.send_with_position(vec![2; len])?);
+            }
+            // the entries are streamed to a temporary file until close
+            ensure!(tmp_path.exists() && !index_path.exists());
+            writer.close()?;
+        }
+        ensure!(!tmp_path.exists());
+        ensure!(indexer::load_index(&path, &index_path, format)? == Some(positions.clone()));
+
+        // the index covers existing records in append mode
+        {
+            let (mut writer, info) = BytesWriter::open_append_with_config(&path, config)?;
+            ensure!(info.num_records == 2);
+            positions.push(writer.send_with_position(vec![3; 15])?);
+            ensure!(writer.stats().num_records == 1);
+            writer.close()?;
+        }
+        ensure!(positions[2].offset == 12 + 21 + 26);
+        ensure!(indexer::load_index(&path, &index_path, format)? == Some(positions));
+
+        fs::remove_file(&path)?;
+        fs::remove_file(&index_path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "async")]
+
+pub mod common;
+
+use common::*;
+use std::fs;
+use tfrecord::{
+    indexer::{self, IndexFormat},
+    BytesAsyncWriter, RecordWriterConfig,
+};
+
+#[async_std::test]
+async fn async_writer_index_sidecar_test() -> Result<()> {
+    let path = DATA_DIR.join("writer_sidecar_async.tfrecord");
+    let index_path = IndexFormat::Dali.sidecar_path(&path);
+    let config = RecordWriterConfig {
+        index: Some(IndexFormat::Dali),
+        ..Default::default()
+    };
+
+    let mut writer = BytesAsyncWriter::create_with_config(&path, config).await?;
+    let mut positions = vec![];
+    for len in [7, 0, 3] {
+        positions.push(writer.send_with_position(vec![4; len]).await?);
+    }
+    writer.flush().await?;
+    let stats = writer.stats();
+    ensure!(stats.num_records == 3 && stats.payload_bytes == 10 && stats.written_bytes == 58);
+    writer.close().await?;
+
+    let loaded = indexer::load_index(&path, &index_path, IndexFormat::Dali)?;
+    ensure!(loaded == Some(positions));
+
+    fs::remove_file(&path)?;
+    fs::remove_file(&index_path)?;
+    Ok(())
+}